@@ -0,0 +1,43 @@
+//! Compares the `blake2` crate with `blake2b_simd`, which is what `simd_blake2b` hashes with.
+//!
+//! Run with `cargo bench --features simd_blake2b`.
+use blake2::digest::{Update, VariableOutput};
+use std::time::{Duration, Instant};
+
+const ITERATIONS: u32 = 200_000;
+
+fn bench(name: &str, f: impl Fn(&[u8]) -> Vec<u8>) -> Duration {
+    // Roughly the size of a hashed state block.
+    let data = [7u8; 176];
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(f(std::hint::black_box(&data)));
+    }
+    let elapsed = start.elapsed();
+    println!(
+        "{:<12} {:>10.0?} total {:>8.0?} per hash",
+        name,
+        elapsed,
+        elapsed / ITERATIONS
+    );
+    elapsed
+}
+
+fn main() {
+    let blake2 = bench("blake2", |data| {
+        let mut blake = blake2::VarBlake2b::new(32).unwrap();
+        blake.update(data);
+        blake.finalize_boxed().to_vec()
+    });
+    let simd = bench("blake2b_simd", |data| {
+        blake2b_simd::Params::new()
+            .hash_length(32)
+            .hash(data)
+            .as_bytes()
+            .to_vec()
+    });
+    println!(
+        "blake2b_simd is {:.2}x the speed of blake2",
+        blake2.as_secs_f64() / simd.as_secs_f64()
+    );
+}
@@ -3,6 +3,8 @@ mod block_hash;
 mod change_block;
 mod open_block;
 mod receive_block;
+#[cfg(feature = "node")]
+pub(crate) mod record;
 mod send_block;
 mod state_block;
 
@@ -22,12 +24,15 @@ use crate::{Private, Public, Raw, Signature, Work};
 use anyhow::{anyhow, Context};
 pub use block_hash::BlockHash;
 pub use change_block::ChangeBlock;
+use futures::{Stream, StreamExt};
 pub use open_block::OpenBlock;
 pub use receive_block::ReceiveBlock;
 pub use send_block::SendBlock;
 use serde;
 use serde::{Deserialize, Serialize};
 pub use state_block::{Link, StateBlock, Subtype};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::str::FromStr;
 use strum_macros::EnumString;
@@ -91,7 +96,13 @@ pub enum BlockHolder {
 #[cfg(feature = "node")]
 impl Wire for BlockHolder {
     fn serialize(&self) -> Vec<u8> {
-        unimplemented!()
+        match self {
+            BlockHolder::State(block) => Wire::serialize(block),
+            BlockHolder::Send(block) => Wire::serialize(block),
+            BlockHolder::Receive(block) => Wire::serialize(block),
+            BlockHolder::Open(block) => Wire::serialize(block),
+            BlockHolder::Change(block) => Wire::serialize(block),
+        }
     }
 
     fn deserialize(header: Option<&Header>, data: &[u8]) -> anyhow::Result<Self>
@@ -112,7 +123,14 @@ impl Wire for BlockHolder {
                 BlockHolder::State(Wire::deserialize(header, data).context(context)?)
             }
             BlockType::Send => BlockHolder::Send(Wire::deserialize(header, data).context(context)?),
-            _ => todo!(),
+            BlockType::Receive => {
+                BlockHolder::Receive(Wire::deserialize(header, data).context(context)?)
+            }
+            BlockType::Open => BlockHolder::Open(Wire::deserialize(header, data).context(context)?),
+            BlockType::Change => {
+                BlockHolder::Change(Wire::deserialize(header, data).context(context)?)
+            }
+            block_type => return Err(anyhow!("{:?} is not a block", block_type)).context(context),
         };
         Ok(holder)
     }
@@ -125,12 +143,16 @@ impl Wire for BlockHolder {
         match header.as_ref().unwrap().ext().block_type()? {
             BlockType::State => StateBlock::len(header),
             BlockType::Send => SendBlock::len(header),
-            _ => todo!(),
+            BlockType::Receive => ReceiveBlock::len(header),
+            BlockType::Open => OpenBlock::len(header),
+            BlockType::Change => ChangeBlock::len(header),
+            block_type => Err(anyhow!("{:?} is not a block", block_type)),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+/// Serialized the same way as the node does, as a hex block hash which is all zeros for `Open`.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Previous {
     Block(BlockHash),
     Open,
@@ -145,6 +167,28 @@ impl Previous {
     }
 }
 
+impl Serialize for Previous {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Previous::Block(b) => b.serialize(serializer),
+            Previous::Open => BlockHash::zero().serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Previous {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s: String = Deserialize::deserialize(deserializer)?;
+        Previous::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
 impl TryFrom<&[u8]> for Previous {
     type Error = crate::Error;
 
@@ -204,13 +248,14 @@ pub struct Block {
     signature: Option<Signature>,
 
     /// The proof of work applied to this block.
+    #[serde(with = "crate::pow::work_hex")]
     work: Option<Work>,
 
     /// What level of trust do we have with this block?
     state: ValidationState,
 }
 
-#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ValidationState {
     Published,
     PresumedValid,
@@ -219,6 +264,18 @@ pub enum ValidationState {
     WorkFailed,
 }
 
+/// A field that differs between two blocks, see [Block::diff].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldDiff {
+    Account,
+    Previous,
+    Balance,
+    Link,
+    Representative,
+    Signature,
+    Work,
+}
+
 impl Block {
     pub fn new(
         block_type: BlockType,
@@ -301,6 +358,14 @@ impl Block {
         }
     }
 
+    /// Hash the fields again instead of returning the cached hash, e.g. to check a block from
+    /// an untrusted source.
+    pub fn recompute_hash(&self) -> anyhow::Result<BlockHash> {
+        let mut block = self.to_owned();
+        block.calc_hash()?;
+        Ok(block.hash()?.to_owned())
+    }
+
     /// Generates the hash for this block.
     /// Will be None if block type is Invalid or NotABlock
     // TODO: Can this ever fail?
@@ -375,8 +440,35 @@ impl Block {
         &self.representative
     }
 
+    /// The account whose key signed this block on `network`, to verify the signature against.
+    ///
+    /// Legacy open blocks carry the account themselves, while other legacy blocks are given the
+    /// account of their chain when converted, so this is the account for most blocks. Epoch
+    /// blocks are signed by the network's epoch signer instead. They're recognised by their link
+    /// like in [epoch_version], since [Block::subtype] would need the previous block.
+    pub fn signing_account(&self, network: &Network) -> anyhow::Result<Public> {
+        match epoch_link_version(&self.link) {
+            Some(version) if self.block_type == BlockType::State => network.epoch_signer(version),
+            _ => Ok(self.account.to_owned()),
+        }
+    }
+
     pub fn is_genesis(&self, network: &Network) -> anyhow::Result<bool> {
-        Ok(&network.genesis_hash() == self.hash()?)
+        Ok(&network.genesis_hash()? == self.hash()?)
+    }
+
+    /// Reject blocks whose account is the zero public key.
+    ///
+    /// The zero key is the burn account, which funds can be sent to but which can never sign a
+    /// block, so a block claiming it as its account was built from a missing or default key.
+    pub fn verify_account(&self) -> anyhow::Result<()> {
+        if self.account.as_bytes() == [0u8; Public::LEN] {
+            return Err(anyhow!(
+                "Block account is the zero public key (burn account): {:?}",
+                self
+            ));
+        }
+        Ok(())
     }
 
     pub fn verify_signature(&self, account: &Public) -> anyhow::Result<()> {
@@ -402,9 +494,28 @@ impl Block {
         &self.previous
     }
 
-    /// For an open or recv block, get the sender's block hash, otherwise Err.
+    /// The hash of the previous block, or `None` if this is the first block of the account.
+    pub fn previous_hash(&self) -> Option<BlockHash> {
+        match &self.previous {
+            Previous::Block(hash) => Some(hash.to_owned()),
+            Previous::Open => None,
+        }
+    }
+
+    pub fn link(&self) -> &Link {
+        &self.link
+    }
+
+    pub fn validation_state(&self) -> &ValidationState {
+        &self.state
+    }
+
+    /// For an open, recv or state block, get the sender's block hash, otherwise Err.
     pub fn source(&self) -> anyhow::Result<&BlockHash> {
-        if self.block_type != BlockType::Open {
+        if !matches!(
+            self.block_type,
+            BlockType::Open | BlockType::Receive | BlockType::State
+        ) {
             return Err(anyhow!(
                 "Source requested for a {:?} block",
                 self.block_type
@@ -421,6 +532,69 @@ impl Block {
         }
     }
 
+    /// Classify this block, using the block before it in the account chain for state blocks.
+    ///
+    /// `previous` is `None` when this is the first block of the account.
+    pub fn subtype(&self, previous: Option<&Block>) -> anyhow::Result<Subtype> {
+        Ok(match self.block_type {
+            BlockType::Send => Subtype::Send,
+            BlockType::Receive => Subtype::Receive,
+            BlockType::Open => Subtype::Open,
+            BlockType::Change => Subtype::Change,
+            BlockType::State => match previous {
+                None => Subtype::Open,
+                Some(previous) => match self.balance.to_u128().cmp(&previous.balance.to_u128()) {
+                    Ordering::Less => Subtype::Send,
+                    Ordering::Greater => Subtype::Receive,
+                    Ordering::Equal if self.link.as_bytes().iter().all(|b| *b == 0) => {
+                        Subtype::Change
+                    }
+                    Ordering::Equal => Subtype::Epoch,
+                },
+            },
+            _ => return Err(anyhow!("No subtype for a {:?} block", self.block_type)),
+        })
+    }
+
+    /// Whether this block delegates to a different representative than `previous`, the block
+    /// before it in the account chain.
+    ///
+    /// Unlike checking for a [Subtype::Change], this includes send and receive blocks which also
+    /// change the representative.
+    pub fn is_representative_change(&self, previous: &Block) -> bool {
+        self.representative != previous.representative
+    }
+
+    /// The fields which differ from `other`, e.g. between a locally built block and the node's
+    /// copy of it.
+    ///
+    /// Links are compared by their bytes, so an unresolved link equals the resolved one.
+    pub fn diff(&self, other: &Block) -> Vec<FieldDiff> {
+        let mut diffs = vec![];
+        if self.account != other.account {
+            diffs.push(FieldDiff::Account);
+        }
+        if self.previous != other.previous {
+            diffs.push(FieldDiff::Previous);
+        }
+        if self.balance != other.balance {
+            diffs.push(FieldDiff::Balance);
+        }
+        if self.link.as_bytes() != other.link.as_bytes() {
+            diffs.push(FieldDiff::Link);
+        }
+        if self.representative != other.representative {
+            diffs.push(FieldDiff::Representative);
+        }
+        if self.signature != other.signature {
+            diffs.push(FieldDiff::Signature);
+        }
+        if self.work != other.work {
+            diffs.push(FieldDiff::Work);
+        }
+        diffs
+    }
+
     /// For a send block, the destination account being sent to.
     pub fn destination(&self) -> anyhow::Result<&Public> {
         if self.block_type != BlockType::Send {
@@ -442,6 +616,155 @@ impl Block {
     }
 }
 
+/// The representative history of an account, as `(block hash, old rep, new rep)` for each
+/// change block.
+///
+/// `chain` has to be ordered from the first block of the account.
+pub fn representative_changes(chain: &[Block]) -> anyhow::Result<Vec<(BlockHash, Public, Public)>> {
+    let mut changes = vec![];
+    let mut previous: Option<&Block> = None;
+    for block in chain {
+        if block.subtype(previous)? == Subtype::Change {
+            let previous = previous.ok_or_else(|| anyhow!("Change block without a previous"))?;
+            changes.push((
+                block.hash()?.to_owned(),
+                previous.representative.to_owned(),
+                block.representative.to_owned(),
+            ));
+        }
+        previous = Some(block);
+    }
+    Ok(changes)
+}
+
+/// A Graphviz DOT graph of an account chain, with a node for each block labelled with its subtype
+/// and amount, and an edge from each previous block to the block after it.
+///
+/// `chain` has to be ordered from the first block of the account.
+pub fn chain_to_dot(chain: &[Block]) -> anyhow::Result<String> {
+    let mut dot = String::from("digraph chain {\n");
+    let mut previous: Option<&Block> = None;
+    for block in chain {
+        let hash = block.hash()?;
+        let amount = match previous {
+            Some(previous) => previous.balance.delta_to(&block.balance).1,
+            None => block.balance.to_owned(),
+        };
+        dot.push_str(&format!(
+            "    \"{}\" [label=\"{:?}\\n{} raw\"];\n",
+            hash,
+            block.subtype(previous)?,
+            amount
+        ));
+        if let Previous::Block(previous_hash) = &block.previous {
+            dot.push_str(&format!("    \"{}\" -> \"{}\";\n", previous_hash, hash));
+        }
+        previous = Some(block);
+    }
+    dot.push_str("}\n");
+    Ok(dot)
+}
+
+/// The representative the account was opened with, i.e. its initial delegation, which might
+/// differ from the representative of its latest block.
+///
+/// Returns `None` if `chain` doesn't contain the open block.
+pub fn open_representative(chain: &[Block]) -> Option<&Public> {
+    chain
+        .iter()
+        .find(|block| block.previous() == &Previous::Open)
+        .map(|block| block.representative())
+}
+
+/// The previous block hashes referenced in `blocks` that aren't in `blocks` themselves, i.e. what
+/// to request next when bootstrapping.
+///
+/// Each gap is only returned once, in the order it's first referenced.
+pub fn find_gaps(blocks: &[Block]) -> anyhow::Result<Vec<BlockHash>> {
+    let mut known = HashSet::new();
+    for block in blocks {
+        known.insert(block.hash()?);
+    }
+    let mut gaps = vec![];
+    for block in blocks {
+        if let Previous::Block(previous) = block.previous() {
+            if !known.contains(previous) && !gaps.contains(previous) {
+                gaps.push(previous.to_owned());
+            }
+        }
+    }
+    Ok(gaps)
+}
+
+/// Pairs of conflicting block hashes in `blocks`, i.e. blocks of the same account with the same
+/// previous block, e.g. when the same chain was fetched from several peers.
+///
+/// Each pair is `(first seen, later)`, and copies of the same block are not forks.
+pub fn find_forks(blocks: &[Block]) -> anyhow::Result<Vec<(BlockHash, BlockHash)>> {
+    let mut siblings: HashMap<(&Public, Vec<u8>), Vec<&BlockHash>> = HashMap::new();
+    let mut forks = vec![];
+    for block in blocks {
+        let hash = block.hash()?;
+        let seen = siblings
+            .entry((block.account(), block.previous().to_bytes()))
+            .or_default();
+        if seen.contains(&hash) {
+            continue;
+        }
+        for sibling in seen.iter() {
+            forks.push(((*sibling).to_owned(), hash.to_owned()));
+        }
+        seen.push(hash);
+    }
+    Ok(forks)
+}
+
+/// Check the signature of each block in `blocks`, yielding its hash and whether it was valid, e.g.
+/// to validate a download as it arrives instead of holding it all in memory.
+///
+/// Each block is checked against [Block::signing_account] on `network` one at a time, since batch
+/// verification isn't enabled in ed25519-dalek. Blocks which can't be hashed are skipped as
+/// there's no hash to report them by.
+pub fn verify_blocks_stream<S>(network: Network, blocks: S) -> impl Stream<Item = (BlockHash, bool)>
+where
+    S: Stream<Item = Block>,
+{
+    blocks.filter_map(move |mut block| async move {
+        if block.hash.is_none() {
+            block.calc_hash().ok()?;
+        }
+        let valid = match block.signing_account(&network) {
+            Ok(account) => block.verify_signature(&account).is_ok(),
+            Err(_) => false,
+        };
+        Some((block.hash().ok()?.to_owned(), valid))
+    })
+}
+
+/// The epoch the account has been upgraded to, i.e. the highest epoch block in `chain`, or 0 if
+/// there are none.
+///
+/// Epoch blocks are recognised by their link, e.g. `epoch v2 block` padded with zeros. Whether
+/// the block was signed by the epoch signer isn't checked.
+pub fn epoch_version(chain: &[Block]) -> u8 {
+    chain
+        .iter()
+        .filter_map(|block| epoch_link_version(block.link()))
+        .max()
+        .unwrap_or(0)
+}
+
+fn epoch_link_version(link: &Link) -> Option<u8> {
+    let bytes = link.as_bytes();
+    let end = bytes.iter().rposition(|b| *b != 0)? + 1;
+    std::str::from_utf8(&bytes[..end])
+        .ok()?
+        .strip_prefix("epoch v")?
+        .strip_suffix(" block")?
+        .parse()
+        .ok()
+}
+
 pub fn hash_block(parts: &[&[u8]]) -> BlockHash {
     let mut v = Vec::new(); // TODO: with_capacity
     for b in parts {
@@ -453,21 +776,28 @@ pub fn hash_block(parts: &[&[u8]]) -> BlockHash {
 
 #[cfg(test)]
 mod tests {
-    use crate::blocks::{Block, BlockHash, Link, Previous, StateBlock};
+    use crate::blocks::{
+        chain_to_dot, epoch_version, find_forks, find_gaps, open_representative,
+        representative_changes, verify_blocks_stream, Block, BlockHash, BlockType, FieldDiff, Link,
+        OpenBlock, Previous, StateBlock, Subtype, ValidationState,
+    };
+    use crate::encoding::to_hex;
     use crate::network::Network;
-    use crate::{Public, Raw};
+    use crate::{Private, Public, Raw, Seed, Work};
+    use futures::StreamExt;
+    use std::convert::TryFrom;
     use std::str::FromStr;
 
     #[test]
     fn json() {
-        let genesis = Network::Live.genesis_block();
+        let genesis = Network::Live.genesis_block().unwrap();
         let a = serde_json::to_string_pretty(&genesis).unwrap();
         dbg!(&a);
         assert!(a.contains(r#"type": "open""#));
         assert!(a.contains(r#"link": "E8"#));
         assert!(a.contains(r#"representative": "nano_3t"#));
         assert!(a.contains(r#"account": "nano_3t"#));
-        assert!(a.contains(r#"work": "62F"#));
+        assert!(a.contains(r#"work": "62f05417dd3fb691""#));
         assert!(a.contains(r#"signature": "9F"#));
     }
 
@@ -500,4 +830,351 @@ mod tests {
         let block_1 = Block::from_state_block(&StateBlock::from(block_0.clone()));
         assert_eq!(block_0, block_1)
     }
+
+    #[test]
+    fn previous_hash() {
+        let open = Block::from_state_block(&test_state_block());
+        assert_eq!(open.previous_hash(), None);
+
+        let successor = StateBlock::new(
+            open.account().to_owned(),
+            Previous::Block(open.hash().unwrap().to_owned()),
+            open.representative().to_owned(),
+            Raw(400),
+            Link::DestinationAccount(open.representative().to_owned()),
+        );
+        let successor = Block::from_state_block(&successor);
+        assert_eq!(
+            successor.previous_hash().as_ref(),
+            Some(open.hash().unwrap())
+        );
+    }
+
+    #[test]
+    fn diff() {
+        let local = Block::from_state_block(&test_state_block());
+        assert!(local.diff(&local).is_empty());
+
+        let mut remote = local.clone();
+        remote.set_work(Work::from_str("8a142e07a10996d5").unwrap());
+        assert_eq!(local.diff(&remote), vec![FieldDiff::Work]);
+    }
+
+    #[test]
+    fn signing_account() {
+        // A legacy open block from the live network.
+        let open: OpenBlock = serde_json::from_str(
+            r#"{
+                "source": "A170D51B94E00371ACE76E35AC81DC9405D5D04D4CEBC399AEACE07AE05DD293",
+                "representative": "nano_1awsn43we17c1oshdru4azeqjz9wii41dy8npubm4rg11so7dx3jtqgoeahy",
+                "account": "nano_13ezf4od79h1tgj9aiu4djzcmmguendtjfuhwfukhuucboua8cpoihmh8byo",
+                "work": "e997c097a452a1b1",
+                "signature": "E950FFDF0C9C4DAF43C27AE3993378E4D8AD6FA591C24497C53E07A3BC80468539B0A467992A916F0DDA6F267AD764A3C1A5BDBD8F489DFAE8175EEE0E337402"
+            }"#,
+        )
+        .unwrap();
+        let open = Block::from_open_block(&open, &Previous::Open, &Raw::from(1));
+        let signer = open.signing_account(&Network::Live).unwrap();
+        assert_eq!(
+            signer.to_address().to_string(),
+            "nano_13ezf4od79h1tgj9aiu4djzcmmguendtjfuhwfukhuucboua8cpoihmh8byo"
+        );
+        open.verify_signature(&signer).unwrap();
+
+        let private = Seed::zero().derive(0);
+        let mut state = Block::from_state_block(&test_state_block());
+        state.account = private.to_public().unwrap();
+        state.calc_hash().unwrap();
+        state.sign(private).unwrap();
+        let signer = state.signing_account(&Network::Live).unwrap();
+        assert_eq!(&signer, state.account());
+        state.verify_signature(&signer).unwrap();
+    }
+
+    #[test]
+    fn epoch_signing_account() {
+        let network = Network::Test;
+        let mut bytes = [0u8; 32];
+        bytes[..14].copy_from_slice(b"epoch v2 block");
+        let open = Block::from_state_block(&test_state_block());
+        let mut epoch = Block::new(
+            BlockType::State,
+            open.account().to_owned(),
+            Previous::Block(open.hash().unwrap().to_owned()),
+            open.representative().to_owned(),
+            open.balance().to_owned(),
+            Link::Source(BlockHash::try_from(bytes.as_ref()).unwrap()),
+            ValidationState::Valid,
+        );
+        // The test network's genesis key signs its epochs.
+        let private =
+            Private::from_str("34F0A37AAD20F4A260F0A5B3CB3D7FB50673212263E58A380BC10474BB039CE4")
+                .unwrap();
+        epoch.sign(private).unwrap();
+
+        let signer = epoch.signing_account(&network).unwrap();
+        assert_eq!(&signer, network.genesis_block().unwrap().account());
+        assert_ne!(&signer, epoch.account());
+        epoch.verify_signature(&signer).unwrap();
+
+        // The beta network's epoch signer isn't known.
+        assert!(epoch.signing_account(&Network::Beta).is_err());
+    }
+
+    #[test]
+    fn is_representative_change() {
+        let account = Seed::zero().derive(0).to_public().unwrap();
+        let rep = |index| Seed::zero().derive(index).to_public().unwrap();
+        let open = Block::new(
+            BlockType::State,
+            account.to_owned(),
+            Previous::Open,
+            rep(1),
+            Raw::from(100),
+            Link::Source(BlockHash::zero()),
+            ValidationState::Valid,
+        );
+        let block = |representative| {
+            Block::new(
+                BlockType::State,
+                account.to_owned(),
+                Previous::Block(open.hash().unwrap().to_owned()),
+                representative,
+                Raw::from(50),
+                Link::DestinationAccount(rep(9)),
+                ValidationState::Valid,
+            )
+        };
+
+        let send_and_change = block(rep(2));
+        assert_eq!(send_and_change.subtype(Some(&open)).unwrap(), Subtype::Send);
+        assert!(send_and_change.is_representative_change(&open));
+        assert!(!block(rep(1)).is_representative_change(&open));
+    }
+
+    #[test]
+    fn representative_history() {
+        let account = Seed::zero().derive(0).to_public().unwrap();
+        let rep = |index| Seed::zero().derive(index).to_public().unwrap();
+        let source =
+            BlockHash::from_str("570EDFC56651FBBC9AEFE5B0769DBD210614A0C0E6962F5CA0EA2FFF4C08A4B0")
+                .unwrap();
+
+        let mut chain: Vec<Block> = vec![];
+        let mut add = |representative: Public, balance: u128, link: Link| {
+            let previous = match chain.last() {
+                Some(block) => Previous::Block(block.hash().unwrap().to_owned()),
+                None => Previous::Open,
+            };
+            chain.push(Block::new(
+                BlockType::State,
+                account.to_owned(),
+                previous,
+                representative,
+                Raw::from(balance),
+                link,
+                ValidationState::Valid,
+            ));
+        };
+        add(rep(1), 100, Link::Source(source.to_owned()));
+        add(rep(2), 100, Link::Nothing);
+        add(rep(2), 50, Link::DestinationAccount(rep(9)));
+        add(rep(3), 50, Link::Nothing);
+
+        let changes = representative_changes(&chain).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                (chain[1].hash().unwrap().to_owned(), rep(1), rep(2)),
+                (chain[3].hash().unwrap().to_owned(), rep(2), rep(3)),
+            ]
+        );
+
+        // Changed away from the representative it was opened with.
+        assert_eq!(open_representative(&chain), Some(&rep(1)));
+        assert_eq!(chain.last().unwrap().representative(), &rep(3));
+
+        // Kept the representative it was opened with.
+        let kept = vec![chain[0].to_owned()];
+        assert_eq!(open_representative(&kept), Some(&rep(1)));
+        assert_eq!(kept.last().unwrap().representative(), &rep(1));
+
+        assert_eq!(open_representative(&chain[1..]), None);
+    }
+
+    #[test]
+    fn epoch() {
+        let epoch_link = |version: u8| {
+            let mut bytes = [0u8; 32];
+            let text = format!("epoch v{} block", version);
+            bytes[..text.len()].copy_from_slice(text.as_bytes());
+            Link::Source(BlockHash::try_from(bytes.as_ref()).unwrap())
+        };
+        assert_eq!(
+            to_hex(epoch_link(2).as_bytes()),
+            "65706F636820763220626C6F636B000000000000000000000000000000000000"
+        );
+
+        let open = Block::from_state_block(&test_state_block());
+        let upgrade = |previous: &Block, version: u8| {
+            Block::new(
+                BlockType::State,
+                previous.account().to_owned(),
+                Previous::Block(previous.hash().unwrap().to_owned()),
+                previous.representative().to_owned(),
+                previous.balance().to_owned(),
+                epoch_link(version),
+                ValidationState::Valid,
+            )
+        };
+        let epoch_1 = upgrade(&open, 1);
+        let epoch_2 = upgrade(&epoch_1, 2);
+
+        assert_eq!(epoch_version(&[open.to_owned()]), 0);
+        assert_eq!(epoch_version(&[open.to_owned(), epoch_1.to_owned()]), 1);
+        assert_eq!(epoch_version(&[open, epoch_1, epoch_2]), 2);
+    }
+
+    #[test]
+    fn gaps() {
+        let open = Block::from_state_block(&test_state_block());
+        let mut chain = vec![open];
+        for balance in &[400, 300, 200] {
+            let previous = chain.last().unwrap();
+            chain.push(Block::new(
+                BlockType::State,
+                previous.account().to_owned(),
+                Previous::Block(previous.hash().unwrap().to_owned()),
+                previous.representative().to_owned(),
+                Raw::from(*balance),
+                Link::DestinationAccount(previous.representative().to_owned()),
+                ValidationState::Valid,
+            ));
+        }
+        assert!(find_gaps(&chain).unwrap().is_empty());
+
+        let missing = chain.remove(2);
+        assert_eq!(
+            find_gaps(&chain).unwrap(),
+            vec![missing.hash().unwrap().to_owned()]
+        );
+
+        // A lone block is missing its previous block.
+        assert_eq!(
+            find_gaps(&chain[2..]).unwrap(),
+            vec![missing.hash().unwrap().to_owned()]
+        );
+        assert_eq!(
+            find_gaps(&chain[1..2]).unwrap(),
+            vec![chain[0].hash().unwrap().to_owned()]
+        );
+    }
+
+    #[test]
+    fn forks() {
+        let open = Block::from_state_block(&test_state_block());
+        let send = |balance: u128| {
+            Block::new(
+                BlockType::State,
+                open.account().to_owned(),
+                Previous::Block(open.hash().unwrap().to_owned()),
+                open.representative().to_owned(),
+                Raw::from(balance),
+                Link::DestinationAccount(open.representative().to_owned()),
+                ValidationState::Valid,
+            )
+        };
+        let (a, b) = (send(400), send(300));
+        let hash = |block: &Block| block.hash().unwrap().to_owned();
+
+        let blocks = vec![open.to_owned(), a.to_owned(), a.to_owned()];
+        assert!(find_forks(&blocks).unwrap().is_empty());
+
+        let blocks = vec![open.to_owned(), a.to_owned(), b.to_owned(), a.to_owned()];
+        assert_eq!(find_forks(&blocks).unwrap(), vec![(hash(&a), hash(&b))]);
+
+        // Another account with the same previous block isn't a fork.
+        let mut other = b.to_owned();
+        other.account = Seed::zero().derive(0).to_public().unwrap();
+        other.calc_hash().unwrap();
+        assert!(find_forks(&[a, other]).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn verify_stream() {
+        let signed = |index: u32, balance: u128| {
+            let private = Seed::zero().derive(index);
+            let account = private.to_public().unwrap();
+            let mut block = Block::new(
+                BlockType::State,
+                account.to_owned(),
+                Previous::Open,
+                account.to_owned(),
+                Raw::from(balance),
+                Link::Source(BlockHash::zero()),
+                ValidationState::Valid,
+            );
+            block.sign(private).unwrap();
+            block
+        };
+        let valid = signed(0, 100);
+        let mut invalid = signed(1, 200);
+        invalid.set_signature(signed(2, 200).signature().unwrap().to_owned());
+        let unsigned = Block::from_state_block(&test_state_block());
+        let last = signed(3, 300);
+        let hash = |block: &Block| block.hash().unwrap().to_owned();
+
+        let blocks = vec![
+            valid.to_owned(),
+            invalid.to_owned(),
+            unsigned.to_owned(),
+            last.to_owned(),
+        ];
+        let results: Vec<(BlockHash, bool)> =
+            verify_blocks_stream(Network::Live, futures::stream::iter(blocks))
+                .collect()
+                .await;
+        assert_eq!(
+            results,
+            vec![
+                (hash(&valid), true),
+                (hash(&invalid), false),
+                (hash(&unsigned), false),
+                (hash(&last), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn dot() {
+        let open = Block::from_state_block(&test_state_block());
+        let mut chain = vec![open];
+        for balance in &[400, 300] {
+            let previous = chain.last().unwrap();
+            chain.push(Block::new(
+                BlockType::State,
+                previous.account().to_owned(),
+                Previous::Block(previous.hash().unwrap().to_owned()),
+                previous.representative().to_owned(),
+                Raw::from(*balance),
+                Link::DestinationAccount(previous.representative().to_owned()),
+                ValidationState::Valid,
+            ));
+        }
+
+        let dot = chain_to_dot(&chain).unwrap();
+        assert!(dot.starts_with("digraph chain {\n"));
+        assert!(dot.ends_with("}\n"));
+        assert_eq!(dot.matches("[label=").count(), 3);
+        assert_eq!(dot.matches(" -> ").count(), 2);
+        assert!(dot.contains(&format!(
+            "\"{}\" [label=\"Send\\n100 raw\"]",
+            chain[2].hash().unwrap()
+        )));
+        assert!(dot.contains(&format!(
+            "\"{}\" -> \"{}\"",
+            chain[1].hash().unwrap(),
+            chain[2].hash().unwrap()
+        )));
+    }
 }
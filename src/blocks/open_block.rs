@@ -1,3 +1,21 @@
+#[cfg(feature = "node")]
+use crate::node::Header;
+
+#[cfg(feature = "node")]
+use crate::node::Wire;
+
+#[cfg(feature = "node")]
+use crate::blocks::BlockType;
+
+#[cfg(feature = "node")]
+use crate::bytes::Bytes;
+
+#[cfg(feature = "node")]
+use crate::encoding::expect_len;
+
+#[cfg(feature = "node")]
+use std::convert::TryFrom;
+
 use crate::blocks::BlockHash;
 use crate::keys::public::{from_address, to_address};
 use crate::{Public, Signature, Work};
@@ -18,6 +36,8 @@ pub struct OpenBlock {
 }
 
 impl OpenBlock {
+    pub const LEN: usize = 168;
+
     pub fn new(source: BlockHash, representative: Public, account: Public) -> Self {
         Self {
             source,
@@ -28,3 +48,55 @@ impl OpenBlock {
         }
     }
 }
+
+#[cfg(feature = "node")]
+impl Wire for OpenBlock {
+    /// A missing signature or work is written as zeros.
+    fn serialize(&self) -> Vec<u8> {
+        let mut v = Vec::with_capacity(Self::LEN);
+        v.extend_from_slice(self.source.as_bytes());
+        v.extend_from_slice(self.representative.as_bytes());
+        v.extend_from_slice(self.account.as_bytes());
+        match &self.signature {
+            Some(signature) => v.extend_from_slice(signature.as_bytes()),
+            None => v.extend_from_slice(&[0u8; Signature::LEN]),
+        }
+        match &self.work {
+            Some(work) => v.extend_from_slice(work.as_bytes()),
+            None => v.extend_from_slice(&[0u8; Work::LEN]),
+        }
+        v
+    }
+
+    fn deserialize(header: Option<&Header>, data: &[u8]) -> anyhow::Result<Self>
+    where
+        Self: Sized,
+    {
+        expect_len(data.len(), Self::len(header)?, "Open block")?;
+        let mut data = Bytes::new(data);
+        let source = BlockHash::try_from(data.slice(BlockHash::LEN)?)?;
+        let representative = Public::try_from(data.slice(Public::LEN)?)?;
+        let account = Public::try_from(data.slice(Public::LEN)?)?;
+        let signature = Some(Signature::try_from(data.slice(Signature::LEN)?)?);
+        let work = Some(Work::try_from(data.slice(Work::LEN)?)?);
+
+        Ok(Self {
+            source,
+            representative,
+            account,
+            work,
+            signature,
+        })
+    }
+
+    fn len(header: Option<&Header>) -> anyhow::Result<usize>
+    where
+        Self: Sized,
+    {
+        debug_assert!(header.is_some());
+        let header = header.unwrap();
+        debug_assert_eq!(header.ext().block_type()?, BlockType::Open);
+
+        Ok(OpenBlock::LEN)
+    }
+}
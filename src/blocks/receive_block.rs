@@ -1,3 +1,21 @@
+#[cfg(feature = "node")]
+use crate::node::Header;
+
+#[cfg(feature = "node")]
+use crate::node::Wire;
+
+#[cfg(feature = "node")]
+use crate::blocks::BlockType;
+
+#[cfg(feature = "node")]
+use crate::bytes::Bytes;
+
+#[cfg(feature = "node")]
+use crate::encoding::expect_len;
+
+#[cfg(feature = "node")]
+use std::convert::TryFrom;
+
 use crate::blocks::BlockHash;
 use crate::{Public, Signature, Work};
 use serde::{Deserialize, Serialize};
@@ -9,3 +27,65 @@ pub struct ReceiveBlock {
     pub work: Option<Work>,
     pub signature: Option<Signature>,
 }
+
+impl ReceiveBlock {
+    pub const LEN: usize = 136;
+
+    pub fn new(previous: BlockHash, source: Public) -> Self {
+        Self {
+            previous,
+            source,
+            work: None,
+            signature: None,
+        }
+    }
+}
+
+#[cfg(feature = "node")]
+impl Wire for ReceiveBlock {
+    /// A missing signature or work is written as zeros.
+    fn serialize(&self) -> Vec<u8> {
+        let mut v = Vec::with_capacity(Self::LEN);
+        v.extend_from_slice(self.previous.as_bytes());
+        v.extend_from_slice(self.source.as_bytes());
+        match &self.signature {
+            Some(signature) => v.extend_from_slice(signature.as_bytes()),
+            None => v.extend_from_slice(&[0u8; Signature::LEN]),
+        }
+        match &self.work {
+            Some(work) => v.extend_from_slice(work.as_bytes()),
+            None => v.extend_from_slice(&[0u8; Work::LEN]),
+        }
+        v
+    }
+
+    fn deserialize(header: Option<&Header>, data: &[u8]) -> anyhow::Result<Self>
+    where
+        Self: Sized,
+    {
+        expect_len(data.len(), Self::len(header)?, "Receive block")?;
+        let mut data = Bytes::new(data);
+        let previous = BlockHash::try_from(data.slice(BlockHash::LEN)?)?;
+        let source = Public::try_from(data.slice(Public::LEN)?)?;
+        let signature = Some(Signature::try_from(data.slice(Signature::LEN)?)?);
+        let work = Some(Work::try_from(data.slice(Work::LEN)?)?);
+
+        Ok(Self {
+            previous,
+            source,
+            work,
+            signature,
+        })
+    }
+
+    fn len(header: Option<&Header>) -> anyhow::Result<usize>
+    where
+        Self: Sized,
+    {
+        debug_assert!(header.is_some());
+        let header = header.unwrap();
+        debug_assert_eq!(header.ext().block_type()?, BlockType::Receive);
+
+        Ok(ReceiveBlock::LEN)
+    }
+}
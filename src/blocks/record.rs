@@ -0,0 +1,119 @@
+use crate::blocks::{Block, BlockHash, BlockType, Link, Previous, ValidationState};
+use crate::{Public, Raw, Signature, Work};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+/// The link is tagged so it comes back as the same variant, unlike [Link]'s own serde.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum LinkRecord {
+    Nothing,
+    Unsure(String),
+    Source(BlockHash),
+    DestinationAccount(Public),
+}
+
+/// All the fields needed to rebuild a [Block] exactly, unlike the RPC style JSON of [Block].
+///
+/// The hash isn't included, it's calculated again when converting back to a [Block].
+#[derive(Serialize, Deserialize)]
+pub(crate) struct BlockRecord {
+    block_type: BlockType,
+    account: Public,
+    previous: Previous,
+    representative: Public,
+    balance: Raw,
+    link: LinkRecord,
+    signature: Option<Signature>,
+    work: Option<Work>,
+    state: ValidationState,
+}
+
+impl From<&Block> for BlockRecord {
+    fn from(block: &Block) -> Self {
+        let link = match &block.link {
+            Link::Nothing => LinkRecord::Nothing,
+            Link::Unsure(unsure) => LinkRecord::Unsure(unsure.as_hex()),
+            Link::Source(hash) => LinkRecord::Source(hash.to_owned()),
+            Link::DestinationAccount(account) => LinkRecord::DestinationAccount(account.to_owned()),
+        };
+        Self {
+            block_type: block.block_type.to_owned(),
+            account: block.account.to_owned(),
+            previous: block.previous.to_owned(),
+            representative: block.representative.to_owned(),
+            balance: block.balance.to_owned(),
+            link,
+            signature: block.signature.to_owned(),
+            work: block.work.to_owned(),
+            state: block.state.to_owned(),
+        }
+    }
+}
+
+/// Fails instead of panicking like [Block::new] when the block can't be hashed.
+impl TryFrom<BlockRecord> for Block {
+    type Error = anyhow::Error;
+
+    fn try_from(record: BlockRecord) -> anyhow::Result<Self> {
+        let link = match record.link {
+            LinkRecord::Nothing => Link::Nothing,
+            LinkRecord::Unsure(hex) => Link::unsure_from_str(&hex)?,
+            LinkRecord::Source(hash) => Link::Source(hash),
+            LinkRecord::DestinationAccount(account) => Link::DestinationAccount(account),
+        };
+        let mut block = Self {
+            hash: None,
+            block_type: record.block_type,
+            account: record.account,
+            previous: record.previous,
+            representative: record.representative,
+            balance: record.balance,
+            link,
+            signature: record.signature,
+            work: record.work,
+            state: record.state,
+        };
+        block.calc_hash()?;
+        Ok(block)
+    }
+}
+
+/// Serde helpers for fields holding a [Block], using [BlockRecord].
+pub(crate) fn serialize<S: serde::Serializer>(
+    block: &Block,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    BlockRecord::from(block).serialize(serializer)
+}
+
+pub(crate) fn deserialize<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Block, D::Error> {
+    Block::try_from(BlockRecord::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+}
+
+/// Like [serialize] and [deserialize] for a list of blocks.
+pub(crate) mod list {
+    use super::BlockRecord;
+    use crate::blocks::Block;
+    use serde::{Deserialize, Serialize};
+    use std::convert::TryFrom;
+
+    pub(crate) fn serialize<S: serde::Serializer>(
+        blocks: &[Block],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let records: Vec<BlockRecord> = blocks.iter().map(BlockRecord::from).collect();
+        records.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Block>, D::Error> {
+        Vec::<BlockRecord>::deserialize(deserializer)?
+            .into_iter()
+            .map(|record| Block::try_from(record).map_err(serde::de::Error::custom))
+            .collect()
+    }
+}
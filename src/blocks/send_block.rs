@@ -10,6 +10,9 @@ use crate::blocks::BlockType;
 #[cfg(feature = "node")]
 use crate::bytes::Bytes;
 
+#[cfg(feature = "node")]
+use crate::encoding::expect_len;
+
 #[cfg(feature = "node")]
 use std::convert::TryFrom;
 
@@ -53,14 +56,28 @@ impl SendBlock {
 
 #[cfg(feature = "node")]
 impl Wire for SendBlock {
+    /// A missing signature or work is written as zeros.
     fn serialize(&self) -> Vec<u8> {
-        unimplemented!()
+        let mut v = Vec::with_capacity(Self::LEN);
+        v.extend_from_slice(self.previous.as_bytes());
+        v.extend_from_slice(self.destination.as_bytes());
+        v.extend_from_slice(&self.balance.to_vec());
+        match &self.work {
+            Some(work) => v.extend_from_slice(work.as_bytes()),
+            None => v.extend_from_slice(&[0u8; Work::LEN]),
+        }
+        match &self.signature {
+            Some(signature) => v.extend_from_slice(signature.as_bytes()),
+            None => v.extend_from_slice(&[0u8; Signature::LEN]),
+        }
+        v
     }
 
-    fn deserialize(_: Option<&Header>, data: &[u8]) -> anyhow::Result<Self>
+    fn deserialize(header: Option<&Header>, data: &[u8]) -> anyhow::Result<Self>
     where
         Self: Sized,
     {
+        expect_len(data.len(), Self::len(header)?, "Send block")?;
         let mut data = Bytes::new(data);
         let previous = BlockHash::try_from(data.slice(BlockHash::LEN)?)?;
         let destination = Public::try_from(data.slice(Public::LEN)?)?;
@@ -41,7 +41,9 @@ pub enum Subtype {
     Epoch,
 }
 
+/// When deserializing, the hash is always calculated from the fields instead of being trusted.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(from = "StateBlockFields")]
 pub struct StateBlock {
     #[serde(serialize_with = "to_address", deserialize_with = "from_address")]
     pub account: Public,
@@ -56,6 +58,7 @@ pub struct StateBlock {
     #[serde(deserialize_with = "deserialize_to_unsure_link")]
     pub link: Link,
 
+    #[serde(with = "crate::pow::work_hex")]
     pub work: Option<Work>,
 
     pub signature: Option<Signature>,
@@ -66,6 +69,37 @@ pub struct StateBlock {
     amount: Option<Amount>,
 }
 
+/// The fields of a [StateBlock] that can be deserialized, as given by a node.
+#[derive(Deserialize)]
+struct StateBlockFields {
+    #[serde(deserialize_with = "from_address")]
+    account: Public,
+    previous: Previous,
+    #[serde(deserialize_with = "from_address")]
+    representative: Public,
+    balance: Raw,
+    #[serde(deserialize_with = "deserialize_to_unsure_link")]
+    link: Link,
+    #[serde(default, with = "crate::pow::work_hex")]
+    work: Option<Work>,
+    signature: Option<Signature>,
+}
+
+impl From<StateBlockFields> for StateBlock {
+    fn from(fields: StateBlockFields) -> Self {
+        let mut block = StateBlock::new(
+            fields.account,
+            fields.previous,
+            fields.representative,
+            fields.balance,
+            fields.link,
+        );
+        block.work = fields.work;
+        block.signature = fields.signature;
+        block
+    }
+}
+
 impl StateBlock {
     pub const LEN: usize = 216;
 
@@ -149,14 +183,30 @@ impl StateBlock {
 
 #[cfg(feature = "node")]
 impl Wire for StateBlock {
+    /// A missing signature or work is written as zeros.
     fn serialize(&self) -> Vec<u8> {
-        unimplemented!()
+        let mut v = Vec::with_capacity(Self::LEN);
+        v.extend_from_slice(self.account.as_bytes());
+        v.extend_from_slice(&self.previous.to_bytes());
+        v.extend_from_slice(self.representative.as_bytes());
+        v.extend_from_slice(&self.balance.to_vec());
+        v.extend_from_slice(self.link.as_bytes());
+        match &self.signature {
+            Some(signature) => v.extend_from_slice(signature.as_bytes()),
+            None => v.extend_from_slice(&[0u8; Signature::LEN]),
+        }
+        match &self.work {
+            Some(work) => v.extend_from_slice(work.as_bytes()),
+            None => v.extend_from_slice(&[0u8; Work::LEN]),
+        }
+        v
     }
 
-    fn deserialize(_header: Option<&Header>, data: &[u8]) -> anyhow::Result<Self>
+    fn deserialize(header: Option<&Header>, data: &[u8]) -> anyhow::Result<Self>
     where
         Self: Sized,
     {
+        expect_len(data.len(), Self::len(header)?, "State block")?;
         let mut data = Bytes::new(data);
 
         let account = Public::try_from(data.slice(Public::LEN)?)?;
@@ -326,6 +376,36 @@ mod tests {
         assert_eq!(state_block.link, Link::Source(source));
     }
 
+    #[test]
+    fn json_work() {
+        let mut block = StateBlock::new(
+            account_0(),
+            parent_0(),
+            representative_0(),
+            balance_0(),
+            Link::DestinationAccount(account_0()),
+        );
+        block.work = Some(Work::from_str("8A142E07A10996D5").unwrap());
+
+        // Nodes use lowercase hex for work.
+        let mut value = serde_json::to_value(&block).unwrap();
+        assert_eq!(value["work"], "8a142e07a10996d5");
+        let block_value = serde_json::to_value(&Block::from_state_block(&block)).unwrap();
+        assert_eq!(block_value["work"], "8a142e07a10996d5");
+        assert_eq!(
+            serde_json::from_value::<StateBlock>(value.clone())
+                .unwrap()
+                .work,
+            block.work
+        );
+
+        value.as_object_mut().unwrap().remove("work");
+        assert_eq!(
+            serde_json::from_value::<StateBlock>(value).unwrap().work,
+            None
+        );
+    }
+
     #[test]
     fn set_nothing_link() {
         let unsure_link = Link::Unsure(UnsureLink([0u8; 32]));
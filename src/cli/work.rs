@@ -1,6 +1,6 @@
 use crate::blocks::BlockHash;
 use crate::pow::{Subject, Work};
-use crate::Difficulty;
+use crate::{Difficulty, Network};
 use clap::Clap;
 use tracing::info;
 
@@ -36,7 +36,7 @@ impl WorkOpts {
             Difficulty::normal()
         };
         info!("Finding work for {:?} at {:?}", &subject, &difficulty);
-        let result = Work::generate(&subject, &difficulty)?;
+        let result = Work::generate(&Network::Live, &subject, &difficulty)?;
         dbg!(result);
         Ok(())
     }
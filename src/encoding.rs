@@ -1,6 +1,8 @@
 use crate::Error;
 use bitvec::prelude::*;
+#[cfg(not(feature = "simd_blake2b"))]
 use blake2::digest::{Update, VariableOutput};
+#[cfg(not(feature = "simd_blake2b"))]
 use blake2::VarBlake2b;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Deserializer};
@@ -44,8 +46,8 @@ where
     T: FromStr,
     <T as std::str::FromStr>::Err: std::fmt::Display,
 {
-    let s: &str = Deserialize::deserialize(deserializer)?;
-    Ok(T::from_str(s).map_err(serde::de::Error::custom)?)
+    let s: String = Deserialize::deserialize(deserializer)?;
+    Ok(T::from_str(&s).map_err(serde::de::Error::custom)?)
 }
 
 pub fn deserialize_from_string<'de, T, D>(
@@ -61,16 +63,82 @@ where
 }
 
 pub fn blake2b(size: usize, data: &[u8]) -> Box<[u8]> {
-    let mut blake = VarBlake2b::new(size).expect("Output size was zero");
-    blake.update(&data);
-    blake.finalize_boxed()
+    blake2b_keyed(&[], size, data)
 }
 
 /// Use this instead of [blake2b] to probably prevent an allocation.
 pub fn blake2b_callback(size: usize, data: &[u8], f: impl FnOnce(&[u8])) {
-    let mut blake = VarBlake2b::new(size).expect("Output size was zero");
-    blake.update(&data);
-    blake.finalize_variable(f)
+    blake2b_keyed_callback(&[], size, data, f)
+}
+
+/// Same as [blake2b] but with a key. An empty key gives the same result as [blake2b].
+pub fn blake2b_keyed(key: &[u8], size: usize, data: &[u8]) -> Box<[u8]> {
+    let mut hash = None;
+    blake2b_keyed_callback(key, size, data, |b| hash = Some(Box::from(b)));
+    hash.expect("Hash callback not called")
+}
+
+/// Same as [blake2b_callback] but with a key. An empty key gives the same result as
+/// [blake2b_callback].
+///
+/// Hashing is done by the `blake2` crate, or `blake2b_simd` with the `simd_blake2b` feature.
+pub fn blake2b_keyed_callback(key: &[u8], size: usize, data: &[u8], f: impl FnOnce(&[u8])) {
+    assert_ne!(size, 0, "Output size was zero");
+
+    #[cfg(not(feature = "simd_blake2b"))]
+    {
+        let mut blake = VarBlake2b::new_keyed(key, size);
+        blake.update(data);
+        blake.finalize_variable(f)
+    }
+
+    #[cfg(feature = "simd_blake2b")]
+    {
+        let hash = blake2b_simd::Params::new()
+            .hash_length(size)
+            .key(key)
+            .hash(data);
+        f(hash.as_bytes())
+    }
+}
+
+/// Like [blake2b] for data that's fed in pieces, so it doesn't have to be collected first.
+pub struct Blake2bHasher {
+    #[cfg(not(feature = "simd_blake2b"))]
+    state: VarBlake2b,
+
+    #[cfg(feature = "simd_blake2b")]
+    state: blake2b_simd::State,
+}
+
+impl Blake2bHasher {
+    pub fn new(size: usize) -> Self {
+        assert_ne!(size, 0, "Output size was zero");
+
+        #[cfg(not(feature = "simd_blake2b"))]
+        let state = VarBlake2b::new(size).expect("Invalid output size");
+
+        #[cfg(feature = "simd_blake2b")]
+        let state = blake2b_simd::Params::new().hash_length(size).to_state();
+
+        Self { state }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.state.update(data);
+    }
+
+    pub fn finalize(self) -> Box<[u8]> {
+        #[cfg(not(feature = "simd_blake2b"))]
+        {
+            self.state.finalize_boxed()
+        }
+
+        #[cfg(feature = "simd_blake2b")]
+        {
+            Box::from(self.state.finalize().as_bytes())
+        }
+    }
 }
 
 pub(crate) const ALPHABET: &str = "13456789abcdefghijkmnopqrstuwxyz";
@@ -292,6 +360,36 @@ mod tests {
             assert!(result.is_err())
         }
     }
+
+    /// Hashes with the `blake2` crate directly, to compare with the selected backend.
+    fn reference_blake2b(key: &[u8], size: usize, data: &[u8]) -> Box<[u8]> {
+        use blake2::digest::{Update, VariableOutput};
+
+        let mut blake = blake2::VarBlake2b::new_keyed(key, size);
+        blake.update(data);
+        blake.finalize_boxed()
+    }
+
+    #[test]
+    fn blake2b_backend() {
+        let data: Vec<u8> = (0..=255).collect();
+        for size in &[5, 8, 32, 64] {
+            assert_eq!(blake2b(*size, &data), reference_blake2b(&[], *size, &data));
+            assert_eq!(
+                blake2b_keyed(b"key", *size, &data),
+                reference_blake2b(b"key", *size, &data)
+            );
+        }
+        let mut hasher = Blake2bHasher::new(32);
+        for chunk in data.chunks(100) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize(), blake2b(32, &data));
+        assert_eq!(
+            to_hex(&blake2b(32, b"")),
+            "0E5751C026E543B2E8AB2EB06099DAA1D1E5DF47778F7787FAAB45CDF12FE3A8"
+        );
+    }
 }
 
 pub fn expect_len(got_len: usize, expected_len: usize, msg: &str) -> crate::Result<()> {
@@ -1,3 +1,4 @@
+use crate::{Address, Raw};
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -74,4 +75,31 @@ pub enum Error {
 
     #[error("RPC error: {0}")]
     RPCError(String),
+
+    #[error("Block verification failed: {0}")]
+    BlockVerificationFailed(String),
+
+    #[error("Insufficient balance: {balance} is less than {amount}")]
+    InsufficientBalance { balance: Raw, amount: Raw },
+
+    #[error("Balance overflow: {balance} plus {amount} is more than raw can hold")]
+    BalanceOverflow { balance: Raw, amount: Raw },
+
+    #[error("Sending to {0} requires confirmation")]
+    ConfirmationRequired(Address),
+
+    #[error("{0} has more decimal places than raw can represent")]
+    TooManyDecimals(String),
+
+    #[error("Decryption failed: wrong password or corrupted key")]
+    DecryptionFailed,
+
+    #[error("Invalid key derivation parameters: {0}")]
+    InvalidKdfParams(String),
+
+    #[error("A password was given but the seed isn't encrypted")]
+    UnexpectedPassword,
+
+    #[error("The seed is encrypted and needs a password")]
+    PasswordRequired,
 }
@@ -1,4 +1,4 @@
-use crate::encoding::{self, to_hex};
+use crate::encoding::{self, expect_len, to_hex};
 use crate::keys::public::{Public, self};
 use crate::Error;
 use bitvec::prelude::*;
@@ -11,7 +11,8 @@ use std::str;
 
 /// Nano address. e.g. `nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg`
 ///
-/// You can parse and validate a Nano address using trait@FromStr:
+/// You can parse and validate a Nano address using trait@FromStr. The legacy `xrb_` prefix is
+/// also accepted and converted to `nano_`:
 /// ```
 /// use feeless::Address;
 /// use std::str::FromStr;
@@ -39,12 +40,44 @@ impl Address {
     /// Length of "nano_".
     pub(crate) const PREFIX_LEN: usize = 5;
 
+    /// Length of the legacy "xrb_".
+    pub(crate) const LEGACY_PREFIX_LEN: usize = 4;
+
     /// Length of the encoded public key.
     pub(crate) const ENCODED_PUBLIC_KEY_LEN: usize = 52;
 
     /// 4 bits of padding in the front of the public key when encoding.
     pub(crate) const ENCODED_PADDED_BITS: usize = 4;
 
+    /// Parse an address from the forms nodes and users tend to hand over, not only `nano_...`.
+    ///
+    /// Accepts the legacy `xrb_` prefix, the 60 encoded characters without a prefix, or a 64
+    /// character hex public key. The result always has the `nano_` prefix.
+    pub fn from_loose(s: &str) -> Result<Self, Error> {
+        let s = s.trim();
+        if s.starts_with("nano_") || s.starts_with("xrb_") {
+            return Address::from_str(s);
+        }
+        if s.len() == Self::LEN - Self::PREFIX_LEN {
+            return Address::from_str(&format!("nano_{}", s));
+        }
+        if s.len() == Public::LEN * 2 {
+            return Ok(Public::from_str(s)?.to_address());
+        }
+        Err(Error::InvalidAddress)
+    }
+
+    /// Build the full address from only the 52 character encoded public key, i.e. without the
+    /// prefix and checksum, recomputing the checksum.
+    pub fn from_encoded_key(encoded: &str) -> Result<Self, Error> {
+        if !ENCODED_KEY_REGEX.is_match(encoded) {
+            return Err(Error::InvalidAddress);
+        }
+        // The checksum is ignored when extracting the key.
+        let unchecked = Address(format!("nano_{}{}", encoded, "1".repeat(8)));
+        Ok(unchecked.extract_public_key()?.to_address())
+    }
+
     /// Convert this Nano address into a [struct@Public] key.
     pub fn to_public(&self) -> Public {
         // We don't need to check the checksum because we assume if it's already stored, it's valid.
@@ -52,33 +85,96 @@ impl Address {
         self.extract_public_key().unwrap()
     }
 
+    /// Check each stored address again, returning its index and whether it's still valid, e.g. to
+    /// find corrupted rows in a database.
+    ///
+    /// Deserialized addresses aren't validated and [Address::to_public] doesn't check the
+    /// checksum, so corruption otherwise goes unnoticed.
+    pub fn audit_batch(addresses: &[Address]) -> Vec<(usize, bool)> {
+        addresses
+            .iter()
+            .enumerate()
+            .map(|(idx, address)| (idx, Address::from_str(&address.0).is_ok()))
+            .collect()
+    }
+
+    /// Whether both addresses are for the same public key, whichever of the `nano_` and legacy
+    /// `xrb_` prefixes they have.
+    ///
+    /// Parsed addresses are always `nano_`, but a deserialized one keeps the prefix it was stored
+    /// with, so comparing with `==` can miss that they're the same account.
+    ///
+    /// Malformed addresses aren't the same account as anything.
+    pub fn same_account(&self, other: &Address) -> bool {
+        match (self.extract_public_key(), other.extract_public_key()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+    }
+
+    /// A seed for drawing an identicon of this address, derived from the public key so it's the
+    /// same on every device. How it maps to colors and shapes is up to the UI.
+    ///
+    /// Fails for a malformed address, e.g. one deserialized without being validated.
+    pub fn identicon_seed(&self) -> Result<[u8; 16], Error> {
+        let public = self.extract_public_key()?;
+        let mut seed = [0u8; 16];
+        seed.copy_from_slice(&encoding::blake2b(16, public.as_bytes()));
+        Ok(seed)
+    }
+
+    /// The length of the prefix, which is `xrb_` for a legacy address deserialized as is.
+    fn prefix_len(&self) -> Result<usize, Error> {
+        if self.0.starts_with("nano_") {
+            Ok(Self::PREFIX_LEN)
+        } else if self.0.starts_with("xrb_") {
+            Ok(Self::LEGACY_PREFIX_LEN)
+        } else {
+            Err(Error::InvalidAddress)
+        }
+    }
+
+    /// The length checks here are kept in release builds so a malformed address results in an
+    /// error instead of silently decoding to the wrong key.
     fn extract_public_key(&self) -> Result<Public, Error> {
-        let public_key_part =
-            &self.0[Self::PREFIX_LEN..(Self::PREFIX_LEN + Self::ENCODED_PUBLIC_KEY_LEN)];
-        debug_assert_eq!(public_key_part.len(), Self::ENCODED_PUBLIC_KEY_LEN);
+        let prefix_len = self.prefix_len()?;
+        expect_len(
+            self.0.len(),
+            Self::LEN - Self::PREFIX_LEN + prefix_len,
+            "Address",
+        )?;
+        let public_key_part = self
+            .0
+            .get(prefix_len..(prefix_len + Self::ENCODED_PUBLIC_KEY_LEN))
+            .ok_or(Error::InvalidAddress)?;
 
         let bits = encoding::decode_nano_base_32(&public_key_part)?;
-        debug_assert_eq!(bits.len(), 8 * Public::LEN + Self::ENCODED_PADDED_BITS);
+        expect_len(
+            bits.len(),
+            8 * Public::LEN + Self::ENCODED_PADDED_BITS,
+            "Decoded address bits",
+        )?;
 
-        
-        
-     // Remove padding.
+        // Remove padding.
         // The to_owned() here is necessary to ensure the vec is aligned half way through the byte.
         // Otherwise it will essentially ignore the [ENCODED_PADDED_BITS..] offset.
         let bits: &BitVec<u8, Msb0> = &bits[4..260].to_owned();
-        debug_assert_eq!(bits.len(), 8 * Public::LEN);
+        expect_len(bits.len(), 8 * Public::LEN, "Address public key bits")?;
         let public_key_bytes: Vec<u8> = bits.to_owned().to_bitvec().into_vec();
         let mut s = to_hex(public_key_bytes.as_slice());
         let _s0 = s.remove(0);
-        let _s64 = s.remove(s.len()-1);
-        let public_key_bytes = hex::decode(s).unwrap();
-        debug_assert_eq!(public_key_bytes.len(), Public::LEN);
+        let _s64 = s.remove(s.len() - 1);
+        let public_key_bytes = hex::decode(s).map_err(|source| Error::FromHexError {
+            msg: String::from("Decoding address public key"),
+            source,
+        })?;
+        expect_len(public_key_bytes.len(), Public::LEN, "Address public key")?;
         Public::try_from(public_key_bytes.as_slice())
     }
 
     fn validate_checksum(&self, public: &Public) -> Result<(), Error> {
-        let idx = Self::PREFIX_LEN + Self::ENCODED_PUBLIC_KEY_LEN;
-        let checksum = &self.0[idx..];
+        let idx = self.prefix_len()? + Self::ENCODED_PUBLIC_KEY_LEN;
+        let checksum = self.0.get(idx..).ok_or(Error::InvalidAddress)?;
         if public.checksum() != checksum {
             return Err(Error::InvalidChecksum);
         }
@@ -87,10 +183,16 @@ impl Address {
 }
 
 static ADDRESS_REGEX: Lazy<Regex> = Lazy::new(|| {
-    Regex::new("^nano_[13][13456789abcdefghijkmnopqrstuwxyz]{59}$")
+    Regex::new("^(nano|xrb)_[13][13456789abcdefghijkmnopqrstuwxyz]{59}$")
         .expect("Could not build regexp for nano address.")
 });
 
+/// The first character only holds one bit of the key after the padding.
+static ENCODED_KEY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new("^[13][13456789abcdefghijkmnopqrstuwxyz]{51}$")
+        .expect("Could not build regexp for encoded public key.")
+});
+
 impl FromStr for Address {
     type Err = Error;
 
@@ -99,7 +201,11 @@ impl FromStr for Address {
             return Err(Error::InvalidAddress);
         }
 
-        let address = Address(s.into());
+        // Legacy `xrb_` addresses encode the same key and checksum, so they're stored as `nano_`.
+        let address = match s.strip_prefix("xrb_") {
+            Some(rest) => Address(format!("nano_{}", rest)),
+            None => Address(s.into()),
+        };
         let public = address.extract_public_key()?;
         address.validate_checksum(&public)?;
         Ok(address)
@@ -122,7 +228,8 @@ impl From<&Public> for Address {
         bits.extend_from_bitslice(&pad);
         bits.extend_from_raw_slice(&public.as_bytes());
         debug_assert_eq!(bits.capacity(), PKP_CAPACITY);
-        debug_assert_eq!(bits.len(), PKP_LEN);
+        // Kept in release builds, a wrong length would silently produce a wrong address.
+        assert_eq!(bits.len(), PKP_LEN);
         let public_key_part = encoding::encode_nano_base_32(&bits);
         s.push_str(&public_key_part);
 
@@ -130,14 +237,203 @@ impl From<&Public> for Address {
         let checksum = public.checksum();
         s.push_str(&checksum);
 
-        debug_assert_eq!(s.len(), Self::LEN);
+        assert_eq!(s.len(), Self::LEN);
         debug_assert_eq!(s.capacity(), Self::LEN);
         Address(s)
     }
 }
 
+/// Unlike [Address::to_public], an address which doesn't decode or has a wrong checksum is an
+/// error instead of a panic.
+impl TryFrom<&Address> for Public {
+    type Error = Error;
+
+    fn try_from(address: &Address) -> Result<Self, Self::Error> {
+        let public = address.extract_public_key()?;
+        address.validate_checksum(&public)?;
+        Ok(public)
+    }
+}
+
 impl std::fmt::Display for Address {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.0.fmt(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn malformed_internal_address() {
+        // These can't come from `from_str`, but the checks should still fire in release builds.
+        let short = Address(String::from("nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7"));
+        assert!(matches!(
+            short.extract_public_key(),
+            Err(Error::WrongLength { .. })
+        ));
+
+        let multi_byte = Address(format!("nano_{}é{}", "1".repeat(25), "1".repeat(33)));
+        assert_eq!(multi_byte.0.len(), Address::LEN);
+        assert!(multi_byte.extract_public_key().is_err());
+    }
+
+    #[test]
+    fn legacy_prefix() {
+        let address =
+            Address::from_str("xrb_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg")
+                .unwrap();
+        assert_eq!(
+            address.to_string(),
+            "nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg"
+        );
+        assert_eq!(
+            address.to_public(),
+            Address::from_str("nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg")
+                .unwrap()
+                .to_public()
+        );
+
+        // Stored legacy addresses aren't normalized when deserialized, but decode the same.
+        let stored: Address = serde_json::from_str(
+            r#""xrb_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg""#,
+        )
+        .unwrap();
+        assert_eq!(stored.to_public(), address.to_public());
+        assert_eq!(
+            stored.identicon_seed().unwrap(),
+            address.identicon_seed().unwrap()
+        );
+        stored.validate_checksum(&stored.to_public()).unwrap();
+        let owner = crate::Seed::zero();
+        let legacy: Address = serde_json::from_str(&format!(
+            "\"{}\"",
+            owner.derive(2).to_address().unwrap().to_string().replace("nano_", "xrb_")
+        ))
+        .unwrap();
+        assert_eq!(owner.owns(&legacy, 5), Some(2));
+
+        assert!(matches!(
+            Address::from_str("xrb_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkh"),
+            Err(Error::InvalidChecksum)
+        ));
+        assert!(matches!(
+            Address::from_str("xrb3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg"),
+            Err(Error::InvalidAddress)
+        ));
+    }
+
+    #[test]
+    fn from_encoded_key() {
+        let s = "nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg";
+        let encoded = &s[Address::PREFIX_LEN..Address::PREFIX_LEN + Address::ENCODED_PUBLIC_KEY_LEN];
+        assert_eq!(
+            Address::from_encoded_key(encoded).unwrap(),
+            Address::from_str(s).unwrap()
+        );
+
+        assert!(Address::from_encoded_key(&encoded[1..]).is_err());
+        assert!(Address::from_encoded_key(&format!("{}1", encoded)).is_err());
+        assert!(Address::from_encoded_key(&encoded.replace('o', "0")).is_err());
+        assert!(Address::from_encoded_key(&format!("5{}", &encoded[1..])).is_err());
+    }
+
+    #[test]
+    fn identicon_seed() {
+        let a =
+            Address::from_str("nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg")
+                .unwrap();
+        let b =
+            Address::from_str("nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d")
+                .unwrap();
+        assert_eq!(a.identicon_seed().unwrap(), a.clone().identicon_seed().unwrap());
+        assert_eq!(
+            a.identicon_seed().unwrap(),
+            Address::from_str("xrb_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg")
+                .unwrap()
+                .identicon_seed()
+                .unwrap()
+        );
+        assert_ne!(a.identicon_seed().unwrap(), b.identicon_seed().unwrap());
+
+        let malformed: Address = serde_json::from_str(
+            r#""nano_1tgkjkq9r96zd3pg8i3ni1dz6kcntsbsf8aw2xnkmywgpcy6zp7sbju7cydj""#,
+        )
+        .unwrap();
+        assert!(malformed.identicon_seed().is_err());
+    }
+
+    #[test]
+    fn audit_batch() {
+        let stored: Vec<Address> = serde_json::from_str(
+            r#"[
+                "nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg",
+                "nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo8xkg",
+                "xrb_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d",
+                "nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4"
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            Address::audit_batch(&stored),
+            vec![(0, true), (1, false), (2, true), (3, false)]
+        );
+        assert!(Address::audit_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn same_account() {
+        let nano =
+            Address::from_str("nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg")
+                .unwrap();
+        let xrb: Address = serde_json::from_str(
+            r#""xrb_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg""#,
+        )
+        .unwrap();
+        assert_ne!(nano, xrb);
+        assert!(nano.same_account(&xrb));
+        assert!(xrb.same_account(&nano));
+        assert!(nano.same_account(
+            &Address::from_str("xrb_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg")
+                .unwrap()
+        ));
+
+        let other =
+            Address::from_str("nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d")
+                .unwrap();
+        assert!(!nano.same_account(&other));
+        assert!(!xrb.same_account(&other));
+
+        let malformed = Address(String::from("nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk"));
+        assert!(!malformed.same_account(&malformed));
+    }
+
+    #[test]
+    fn from_loose() {
+        let expected =
+            Address::from_str("nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg")
+                .unwrap();
+        let hex = expected.to_public().to_string();
+        let forms = vec![
+            String::from("nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg"),
+            String::from("xrb_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg"),
+            String::from("3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg"),
+            hex.to_lowercase(),
+            hex,
+        ];
+        for form in forms {
+            assert_eq!(Address::from_loose(&form).unwrap(), expected, "{}", form);
+        }
+
+        assert!(Address::from_loose(
+            "ban_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg"
+        )
+        .is_err());
+        assert!(Address::from_loose(
+            "3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkh"
+        )
+        .is_err());
+        assert!(Address::from_loose("").is_err());
+    }
+}
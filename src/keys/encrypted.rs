@@ -0,0 +1,303 @@
+use crate::encoding::to_hex;
+use crate::{Error, Private, Seed};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, NewAead, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::convert::TryFrom;
+
+/// The Argon2id parameters used to stretch the password of an [EncryptedKey].
+///
+/// They're stored with the key and authenticated along with it. Parameters beyond the limits below
+/// are rejected when deserializing, so an edited file can't make decrypting take forever.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "KdfParamsFields")]
+pub struct KdfParams {
+    /// Memory in KiB.
+    pub memory: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl KdfParams {
+    pub const MAX_MEMORY: u32 = 1024 * 1024;
+    pub const MAX_ITERATIONS: u32 = 64;
+    pub const MAX_PARALLELISM: u32 = 16;
+
+    /// Fail with [Error::InvalidKdfParams] when beyond the limits or rejected by Argon2.
+    pub fn check(&self) -> Result<(), Error> {
+        if self.memory > Self::MAX_MEMORY
+            || self.iterations > Self::MAX_ITERATIONS
+            || self.parallelism > Self::MAX_PARALLELISM
+        {
+            return Err(Error::InvalidKdfParams(format!(
+                "{:?} is over the limits",
+                self
+            )));
+        }
+        self.to_argon2().map(|_| ())
+    }
+
+    fn to_argon2(&self) -> Result<Argon2<'static>, Error> {
+        let params = Params::new(
+            self.memory,
+            self.iterations,
+            self.parallelism,
+            Some(EncryptedKey::KEY_LEN),
+        )
+        .map_err(|err| Error::InvalidKdfParams(err.to_string()))?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+
+    /// The key for the cipher, from `password` and `salt`.
+    fn derive_key(
+        &self,
+        password: &str,
+        salt: &[u8],
+    ) -> Result<[u8; EncryptedKey::KEY_LEN], Error> {
+        self.check()?;
+        let mut key = [0u8; EncryptedKey::KEY_LEN];
+        self.to_argon2()?
+            .hash_password_into(password.as_bytes(), salt, &mut key)
+            .map_err(|err| Error::InvalidKdfParams(err.to_string()))?;
+        Ok(key)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(12);
+        bytes.extend_from_slice(&self.memory.to_le_bytes());
+        bytes.extend_from_slice(&self.iterations.to_le_bytes());
+        bytes.extend_from_slice(&self.parallelism.to_le_bytes());
+        bytes
+    }
+}
+
+/// The recommended minimum Argon2id parameters from OWASP.
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            memory: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct KdfParamsFields {
+    memory: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+impl TryFrom<KdfParamsFields> for KdfParams {
+    type Error = Error;
+
+    fn try_from(fields: KdfParamsFields) -> Result<Self, Error> {
+        let params = Self {
+            memory: fields.memory,
+            iterations: fields.iterations,
+            parallelism: fields.parallelism,
+        };
+        params.check()?;
+        Ok(params)
+    }
+}
+
+/// A [Private] key or [Seed] encrypted with a password, e.g. to store it on disk.
+///
+/// The password is stretched with Argon2id and the key is encrypted with ChaCha20-Poly1305. The
+/// KDF parameters and salt are authenticated as associated data, so a wrong password or an edited
+/// file is detected instead of decrypting to a different key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EncryptedKey {
+    kdf: KdfParams,
+    #[serde(serialize_with = "serialize_hex", deserialize_with = "deserialize_hex")]
+    salt: [u8; EncryptedKey::SALT_LEN],
+    #[serde(serialize_with = "serialize_hex", deserialize_with = "deserialize_hex")]
+    nonce: [u8; EncryptedKey::NONCE_LEN],
+    /// The encrypted key followed by the Poly1305 tag.
+    #[serde(serialize_with = "serialize_hex", deserialize_with = "deserialize_hex")]
+    ciphertext: Vec<u8>,
+}
+
+impl EncryptedKey {
+    const KEY_LEN: usize = 32;
+    const SALT_LEN: usize = 16;
+    const NONCE_LEN: usize = 12;
+
+    /// Encrypt `private` with `password`, stretching it with `kdf`.
+    pub fn new(private: &Private, password: &str, kdf: KdfParams) -> Result<Self, Error> {
+        Self::encrypt(private.as_bytes(), password, kdf)
+    }
+
+    /// Like [EncryptedKey::new] for a seed.
+    pub fn from_seed(seed: &Seed, password: &str, kdf: KdfParams) -> Result<Self, Error> {
+        Self::encrypt(seed.as_bytes(), password, kdf)
+    }
+
+    fn encrypt(secret: &[u8], password: &str, kdf: KdfParams) -> Result<Self, Error> {
+        let mut salt = [0u8; Self::SALT_LEN];
+        let mut nonce = [0u8; Self::NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let key = kdf.derive_key(password, &salt)?;
+        let aad = associated_data(&kdf, &salt);
+        let ciphertext = ChaCha20Poly1305::new(Key::from_slice(&key))
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: secret,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| Error::DecryptionFailed)?;
+        Ok(Self {
+            kdf,
+            salt,
+            nonce,
+            ciphertext,
+        })
+    }
+
+    pub fn kdf(&self) -> &KdfParams {
+        &self.kdf
+    }
+
+    /// Decrypt the private key, failing with [Error::DecryptionFailed] if `password` is wrong or
+    /// the encrypted key was modified.
+    pub fn decrypt(&self, password: &str) -> Result<Private, Error> {
+        Private::try_from(self.decrypt_secret(password)?.as_slice())
+    }
+
+    /// Like [EncryptedKey::decrypt] for a key made with [EncryptedKey::from_seed].
+    pub fn decrypt_seed(&self, password: &str) -> Result<Seed, Error> {
+        Seed::try_from(self.decrypt_secret(password)?.as_slice())
+    }
+
+    fn decrypt_secret(&self, password: &str) -> Result<Vec<u8>, Error> {
+        let key = self.kdf.derive_key(password, &self.salt)?;
+        let aad = associated_data(&self.kdf, &self.salt);
+        let secret = ChaCha20Poly1305::new(Key::from_slice(&key))
+            .decrypt(
+                Nonce::from_slice(&self.nonce),
+                Payload {
+                    msg: &self.ciphertext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| Error::DecryptionFailed)?;
+        if secret.len() != Private::LEN {
+            return Err(Error::DecryptionFailed);
+        }
+        Ok(secret)
+    }
+}
+
+impl Private {
+    /// Encrypt this key with `password` using the default [KdfParams].
+    pub fn to_encrypted(&self, password: &str) -> EncryptedKey {
+        EncryptedKey::new(self, password, KdfParams::default())
+            .expect("Default KDF parameters are valid")
+    }
+}
+
+fn associated_data(kdf: &KdfParams, salt: &[u8]) -> Vec<u8> {
+    let mut aad = kdf.to_bytes();
+    aad.extend_from_slice(salt);
+    aad
+}
+
+fn serialize_hex<S: Serializer, T: AsRef<[u8]>>(
+    bytes: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&to_hex(bytes.as_ref()))
+}
+
+fn deserialize_hex<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: TryFrom<Vec<u8>>,
+{
+    let s: String = Deserialize::deserialize(deserializer)?;
+    let bytes = hex::decode(&s).map_err(serde::de::Error::custom)?;
+    T::try_from(bytes).map_err(|_| serde::de::Error::custom("Wrong length for encrypted key"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Seed;
+    use serde_json::Value;
+
+    /// Cheap parameters so the tests are quick.
+    fn kdf() -> KdfParams {
+        KdfParams {
+            memory: 64,
+            iterations: 1,
+            parallelism: 1,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let private = Seed::zero().derive(0);
+        let encrypted = EncryptedKey::new(&private, "correct horse", kdf()).unwrap();
+        assert_eq!(
+            encrypted.decrypt("correct horse").unwrap().as_bytes(),
+            private.as_bytes()
+        );
+        assert!(matches!(
+            encrypted.decrypt("wrong horse"),
+            Err(Error::DecryptionFailed)
+        ));
+
+        let json = serde_json::to_string(&encrypted).unwrap();
+        let decoded: EncryptedKey = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, encrypted);
+        assert_eq!(
+            decoded.decrypt("correct horse").unwrap().as_bytes(),
+            private.as_bytes()
+        );
+
+        let seed = Seed::zero();
+        let encrypted = EncryptedKey::from_seed(&seed, "correct horse", kdf()).unwrap();
+        assert_eq!(encrypted.decrypt_seed("correct horse").unwrap(), seed);
+
+        let mut tampered = encrypted;
+        tampered.ciphertext[0] ^= 1;
+        assert!(matches!(
+            tampered.decrypt("correct horse"),
+            Err(Error::DecryptionFailed)
+        ));
+    }
+
+    #[test]
+    fn kdf_params_are_authenticated_and_bounded() {
+        let private = Seed::zero().derive(0);
+        let encrypted = EncryptedKey::new(&private, "correct horse", kdf()).unwrap();
+        let json: Value = serde_json::to_value(&encrypted).unwrap();
+
+        let mut edited = json.clone();
+        edited["kdf"]["iterations"] = 2.into();
+        let edited: EncryptedKey = serde_json::from_value(edited).unwrap();
+        assert!(matches!(
+            edited.decrypt("correct horse"),
+            Err(Error::DecryptionFailed)
+        ));
+
+        let mut expensive = json;
+        expensive["kdf"]["iterations"] = u32::MAX.into();
+        assert!(serde_json::from_value::<EncryptedKey>(expensive).is_err());
+
+        let mut too_much_memory = kdf();
+        too_much_memory.memory = KdfParams::MAX_MEMORY + 1;
+        assert!(matches!(
+            EncryptedKey::new(&private, "correct horse", too_much_memory),
+            Err(Error::InvalidKdfParams(_))
+        ));
+    }
+}
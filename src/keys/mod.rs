@@ -1,10 +1,13 @@
 pub mod address;
 pub mod armor;
+pub mod encrypted;
 pub mod phrase;
 pub mod private;
 pub mod public;
+mod scheme;
 pub mod seed;
 pub mod signature;
+pub mod source;
 
 #[cfg(test)]
 mod tests {
@@ -1,10 +1,13 @@
 //! BIP39 and BIP44 mnemonic seed phrase.
 use crate::encoding::to_hex;
 use crate::Error;
-use crate::Private;
+use crate::{Address, Private};
 use bip39::Mnemonic;
 pub use bip39::MnemonicType;
+use bitvec::prelude::*;
 use ed25519_dalek_bip32::{DerivationPath, ExtendedSecretKey};
+use rand::rngs::OsRng;
+use rand::RngCore;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::convert::TryFrom;
 use std::fmt::{Display, Formatter};
@@ -105,6 +108,20 @@ impl<'de> Deserialize<'de> for Entropy {
 pub struct Phrase {
     language: Language,
     entropy: Entropy,
+
+    /// Only false when created with [Phrase::from_words_unchecked].
+    #[serde(default = "default_checksum_valid")]
+    checksum_valid: bool,
+
+    /// The checksum bits of the final word as typed when they're wrong, so the phrase is still
+    /// displayed as it was entered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    typed_checksum: Option<u8>,
+}
+
+/// Phrases serialized before the flag was stored always had a valid checksum.
+fn default_checksum_valid() -> bool {
+    true
 }
 
 impl Phrase {
@@ -113,10 +130,31 @@ impl Phrase {
         Self {
             entropy: Entropy(m.entropy().to_vec()),
             language: language.into(),
+            checksum_valid: true,
+            typed_checksum: None,
         }
     }
 
+    /// A new phrase of `word_count` words (12, 15, 18, 21 or 24), from the operating system's
+    /// secure random number generator.
+    pub fn generate(language: Language, word_count: usize) -> Result<Self, Error> {
+        let mnemonic_type = MnemonicType::for_word_count(word_count)?;
+        let mut entropy = vec![0u8; mnemonic_type.entropy_bits() / 8];
+        OsRng.fill_bytes(&mut entropy);
+        Ok(Self {
+            language,
+            entropy: Entropy(entropy),
+            checksum_valid: true,
+            typed_checksum: None,
+        })
+    }
+
+    /// Fails for a phrase with a wrong checksum, since the mnemonic would have a different final
+    /// word than the one entered.
     pub fn to_mnemonic(&self) -> Result<Mnemonic, Error> {
+        if !self.checksum_valid {
+            return Err(Error::MnemonicError(bip39::ErrorKind::InvalidChecksum));
+        }
         Ok(Mnemonic::from_entropy(
             &self.entropy.0,
             self.language.to_owned().into(),
@@ -141,23 +179,144 @@ impl Phrase {
         Ok(derived)
     }
 
+    /// Fails for a phrase with a wrong checksum, see [Phrase::to_mnemonic].
     pub fn to_private(&self, account: u32, passphrase: &str) -> Result<Private, Error> {
         let ext_key = self.to_bip32_ext_key(account, passphrase)?;
         let bip39_seed = ext_key.secret_key.as_ref();
         Ok(Private::try_from(bip39_seed)?)
     }
 
+    /// Whether account `index` of this phrase is `expected`, e.g. to check a phrase was entered
+    /// correctly against an address written down with the backup.
+    ///
+    /// A wrong password gives a different account, so this is also false then. `expected` may
+    /// have the legacy `xrb_` prefix.
+    pub fn verify_account(&self, index: u32, password: &str, expected: &Address) -> bool {
+        match self
+            .to_private(index, password)
+            .and_then(|private| private.to_address())
+        {
+            Ok(address) => address.same_account(expected),
+            Err(_) => false,
+        }
+    }
+
     pub fn from_words(language: Language, words: &str) -> Result<Self, Error> {
         let m = Mnemonic::from_phrase(words, language.to_owned().into())?;
         Ok(Self {
             language,
             entropy: Entropy(m.entropy().to_vec()),
+            checksum_valid: true,
+            typed_checksum: None,
+        })
+    }
+
+    /// A phrase for raw BIP39 entropy, e.g. 32 bytes for 24 words.
+    pub fn from_entropy(language: Language, entropy: &[u8]) -> Result<Self, Error> {
+        let m = Mnemonic::from_entropy(entropy, language.to_owned().into())?;
+        Ok(Self {
+            language,
+            entropy: Entropy(m.entropy().to_vec()),
+            checksum_valid: true,
+            typed_checksum: None,
+        })
+    }
+
+    /// Like [Phrase::from_words], but also accepts a wrong checksum, e.g. to show whether the
+    /// final word is right while it's being typed. See [Phrase::is_checksum_valid].
+    ///
+    /// The words still have to be in the word list and the amount of words has to be valid. With
+    /// a wrong checksum the phrase is displayed as typed, but it can't make a mnemonic or keys.
+    pub fn from_words_unchecked(language: Language, words: &str) -> Result<Self, Error> {
+        match Self::from_words(language.to_owned(), words) {
+            Err(Error::MnemonicError(bip39::ErrorKind::InvalidChecksum)) => {}
+            result => return result,
+        }
+
+        let bip39_language: bip39::Language = language.to_owned().into();
+        let wordmap = bip39_language.wordmap();
+        let words: Vec<&str> = words.split_whitespace().collect();
+        let mnemonic_type = MnemonicType::for_word_count(words.len())?;
+        let mut bits: BitVec<u8, Msb0> = BitVec::with_capacity(mnemonic_type.total_bits());
+        for word in words {
+            let index = u16::from(wordmap.get_bits(word)?);
+            for bit in (0..11).rev() {
+                bits.push(index >> bit & 1 == 1);
+            }
+        }
+        // Keep the typed checksum bits apart from the entropy.
+        let typed_checksum = bits[mnemonic_type.entropy_bits()..].load_be::<u8>();
+        bits.truncate(mnemonic_type.entropy_bits());
+
+        Ok(Self {
+            language,
+            entropy: Entropy(bits.into_vec()),
+            checksum_valid: false,
+            typed_checksum: Some(typed_checksum),
         })
     }
+
+    /// The words of a phrase with a wrong checksum, from the entropy and the typed checksum bits.
+    fn typed_words(&self) -> Result<String, Error> {
+        let bip39_language: bip39::Language = self.language.to_owned().into();
+        let wordlist = bip39_language.wordlist();
+        let mut bits: BitVec<u8, Msb0> = BitVec::from_vec(self.entropy.0.to_owned());
+        let checksum_len = bits.len() / 32;
+        let typed_checksum = self.typed_checksum.unwrap_or_default();
+        for bit in (0..checksum_len).rev() {
+            bits.push(typed_checksum >> bit & 1 == 1);
+        }
+        let words: Vec<&str> = bits
+            .chunks(11)
+            .map(|chunk| wordlist.get_word(chunk.load_be::<u16>().into()))
+            .collect();
+        Ok(words.join(" "))
+    }
+
+    /// The position of each word in the word list, e.g. for a compact numeric backup.
+    ///
+    /// Each index is 11 bits, so below 2048.
+    pub fn to_indices(&self) -> Result<Vec<u16>, Error> {
+        let bip39_language: bip39::Language = self.language.to_owned().into();
+        let wordmap = bip39_language.wordmap();
+        let mnemonic = self.to_mnemonic()?;
+        mnemonic
+            .phrase()
+            .split_whitespace()
+            .map(|word| Ok(u16::from(wordmap.get_bits(word)?)))
+            .collect()
+    }
+
+    /// Reconstruct a phrase from [Phrase::to_indices]. The checksum is validated like
+    /// [Phrase::from_words].
+    pub fn from_indices(language: Language, indices: &[u16]) -> Result<Self, Error> {
+        let bip39_language: bip39::Language = language.to_owned().into();
+        let wordlist = bip39_language.wordlist();
+        let words = indices
+            .iter()
+            .map(|index| {
+                if *index >= 2048 {
+                    return Err(Error::MnemonicError(bip39::ErrorKind::InvalidWord));
+                }
+                Ok(wordlist.get_word((*index).into()))
+            })
+            .collect::<Result<Vec<&str>, Error>>()?;
+        Self::from_words(language, &words.join(" "))
+    }
+
+    /// Whether the checksum in the final word was valid, which is only false when created by
+    /// [Phrase::from_words_unchecked].
+    pub fn is_checksum_valid(&self) -> bool {
+        self.checksum_valid
+    }
 }
 
 impl Display for Phrase {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if !self.checksum_valid {
+            // TODO: remove unwrap
+            return write!(f, "{}", self.typed_words().unwrap());
+        }
         // TODO: remove unwrap
         let mnemonic = self.to_mnemonic().unwrap();
         let p = mnemonic.phrase();
@@ -199,4 +358,110 @@ mod tests {
             "nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d"
         );
     }
+
+    #[test]
+    fn verify_account() {
+        let phrase = Phrase::from_words(
+            Language::English,
+            "edge defense waste choose enrich upon flee junk siren film clown finish \
+            luggage leader kid quick brick print evidence swap drill paddle truly occur",
+        )
+        .unwrap();
+        let expected =
+            Address::from_str("nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d")
+                .unwrap();
+
+        assert!(phrase.verify_account(0, "some password", &expected));
+        let legacy: Address = serde_json::from_str(
+            r#""xrb_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d""#,
+        )
+        .unwrap();
+        assert!(phrase.verify_account(0, "some password", &legacy));
+        assert!(!phrase.verify_account(1, "some password", &expected));
+        assert!(!phrase.verify_account(0, "wrong password", &expected));
+    }
+
+    #[test]
+    fn checksum() {
+        let words = "edge defense waste choose enrich upon flee junk siren film clown finish \
+            luggage leader kid quick brick print evidence swap drill paddle truly";
+        let valid = format!("{} occur", words);
+        let invalid = format!("{} edge", words);
+
+        let phrase = Phrase::from_words_unchecked(Language::English, &valid).unwrap();
+        assert!(phrase.is_checksum_valid());
+        assert!(Phrase::from_words(Language::English, &valid)
+            .unwrap()
+            .is_checksum_valid());
+
+        assert!(Phrase::from_words(Language::English, &invalid).is_err());
+        let unchecked = Phrase::from_words_unchecked(Language::English, &invalid).unwrap();
+        assert!(!unchecked.is_checksum_valid());
+        assert_eq!(unchecked.to_string(), invalid);
+        assert!(unchecked.to_mnemonic().is_err());
+        assert!(unchecked.to_private(0, "").is_err());
+        // The final word only changes the last byte of the entropy.
+        assert_eq!(unchecked.entropy.0.len(), phrase.entropy.0.len());
+        assert_eq!(unchecked.entropy.0[..31], phrase.entropy.0[..31]);
+
+        assert!(Phrase::from_words_unchecked(Language::English, "edge defense").is_err());
+        assert!(
+            Phrase::from_words_unchecked(Language::English, &format!("{} nanoo", words)).is_err()
+        );
+
+        // The wrong checksum survives a round trip, e.g. through a wallet file.
+        let json = serde_json::to_string(&unchecked).unwrap();
+        let decoded: Phrase = serde_json::from_str(&json).unwrap();
+        assert!(!decoded.is_checksum_valid());
+        assert_eq!(decoded.to_string(), invalid);
+        assert!(decoded.to_private(0, "").is_err());
+
+        // Phrases stored without the flag had a valid checksum.
+        let mut legacy: serde_json::Value = serde_json::to_value(&phrase).unwrap();
+        legacy.as_object_mut().unwrap().remove("checksum_valid");
+        let legacy: Phrase = serde_json::from_value(legacy).unwrap();
+        assert!(legacy.is_checksum_valid());
+        assert_eq!(legacy.to_string(), valid);
+    }
+
+    #[test]
+    fn generate() {
+        for word_count in &[12, 15, 18, 21, 24] {
+            let phrase = Phrase::generate(Language::English, *word_count).unwrap();
+            let words = phrase.to_string();
+            assert_eq!(words.split_whitespace().count(), *word_count);
+
+            let parsed = Phrase::from_words(Language::English, &words).unwrap();
+            assert_eq!(
+                parsed.to_private(0, "").unwrap().to_address().unwrap(),
+                phrase.to_private(0, "").unwrap().to_address().unwrap()
+            );
+        }
+
+        let a = Phrase::generate(Language::English, 24).unwrap();
+        let b = Phrase::generate(Language::English, 24).unwrap();
+        assert_ne!(a.to_string(), b.to_string());
+
+        assert!(Phrase::generate(Language::English, 13).is_err());
+        assert!(Phrase::generate(Language::English, 0).is_err());
+    }
+
+    #[test]
+    fn indices() {
+        let words = "edge defense waste choose enrich upon flee junk siren film clown finish \
+            luggage leader kid quick brick print evidence swap drill paddle truly occur";
+        let phrase = Phrase::from_words(Language::English, words).unwrap();
+        let indices = phrase.to_indices().unwrap();
+        assert_eq!(indices.len(), 24);
+        // "edge" is at index 562 of the English word list.
+        assert_eq!(indices[0], 562);
+
+        let restored = Phrase::from_indices(Language::English, &indices).unwrap();
+        assert_eq!(restored.to_string(), phrase.to_string());
+
+        let mut bad_checksum = indices.to_owned();
+        bad_checksum[23] = indices[0];
+        assert!(Phrase::from_indices(Language::English, &bad_checksum).is_err());
+        assert!(Phrase::from_indices(Language::English, &[2048; 24]).is_err());
+    }
 }
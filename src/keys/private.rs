@@ -1,6 +1,6 @@
-use crate::{hexify, Address, Error, Public, Signature};
-use ed25519_dalek::ed25519::signature::Signature as InternalSignature;
-use ed25519_dalek::ExpandedSecretKey;
+use crate::blocks::{BlockHash, Link, Previous, StateBlock};
+use crate::keys::scheme::{NanoScheme, SignatureScheme};
+use crate::{hexify, Address, Error, Public, Raw, Signature};
 use rand::RngCore;
 use std::convert::TryFrom;
 
@@ -34,12 +34,7 @@ impl Private {
     /// # }
     /// ```
     pub fn to_public(&self) -> Result<Public, Error> {
-        Ok(Public::from(self.internal_public()?))
-    }
-
-    pub(crate) fn internal_public(&self) -> Result<ed25519_dalek::PublicKey, Error> {
-        let dalek = self.to_ed25519_dalek()?;
-        Ok(ed25519_dalek::PublicKey::from(&dalek))
+        Public::try_from(&NanoScheme::public(&self.0)?[..])
     }
 
     pub fn to_address(&self) -> Result<Address, Error> {
@@ -47,29 +42,40 @@ impl Private {
     }
 
     pub fn sign(&self, message: &[u8]) -> Result<Signature, Error> {
-        let dalek = self.to_ed25519_dalek()?;
-        let expanded_secret = ExpandedSecretKey::from(&dalek);
-        let internal_signed = expanded_secret.sign(message, &self.internal_public()?);
-        Signature::try_from(internal_signed.as_bytes())
+        NanoScheme::sign(&self.0, message)
+    }
+
+    /// A signed change block moving this account's delegation to `representative`, on top of
+    /// `previous`.
+    ///
+    /// `balance` has to be the balance at `previous` since a change block doesn't move any funds.
+    /// Work is left to the caller.
+    pub fn change_representative(
+        &self,
+        previous: BlockHash,
+        balance: Raw,
+        representative: Public,
+    ) -> Result<StateBlock, Error> {
+        let mut block = StateBlock::new(
+            self.to_public()?,
+            Previous::Block(previous),
+            representative,
+            balance,
+            Link::Nothing,
+        );
+        block.signature = Some(self.sign(block.hash.as_bytes())?);
+        Ok(block)
     }
 
     // Not public because we don't want users to accidentally generate this key.
     fn zero() -> Self {
         Self([0u8; 32])
     }
-
-    fn to_ed25519_dalek(&self) -> Result<ed25519_dalek::SecretKey, Error> {
-        Ok(
-            ed25519_dalek::SecretKey::from_bytes(&self.0).map_err(|e| Error::SignatureError {
-                msg: String::from("Converting to SecretKey"),
-                source: e,
-            })?,
-        )
-    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::Seed;
 
     #[tokio::test]
@@ -80,4 +86,26 @@ mod tests {
         let signature = private.sign(&message).unwrap();
         assert!(public.verify(&message, &signature).is_ok());
     }
+
+    #[test]
+    fn change_representative() {
+        let private = Seed::zero().derive(0);
+        let representative = Seed::zero().derive(1).to_public().unwrap();
+        let previous = BlockHash::try_from([7u8; BlockHash::LEN].as_ref()).unwrap();
+        let block = private
+            .change_representative(
+                previous.to_owned(),
+                Raw::from(1000),
+                representative.to_owned(),
+            )
+            .unwrap();
+        assert_eq!(block.account, private.to_public().unwrap());
+        assert_eq!(block.previous, Previous::Block(previous));
+        assert_eq!(block.representative, representative);
+        assert_eq!(block.balance, Raw::from(1000));
+        assert_eq!(block.link, Link::Nothing);
+        assert_eq!(block.link.as_bytes(), &[0u8; 32]);
+        assert!(block.work.is_none());
+        block.verify_self_signature().unwrap();
+    }
 }
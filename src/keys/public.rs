@@ -5,11 +5,12 @@ use crate::node::Wire;
 use crate::node::Header;
 
 use crate::hexify;
+use crate::keys::scheme::{NanoScheme, SignatureScheme};
 use crate::Error;
 use crate::{encoding, Address, Signature};
 use bitvec::prelude::*;
-use ed25519_dalek::Verifier;
 use serde::{Deserialize, Deserializer, Serializer};
+use std::cmp::Ordering;
 use std::iter::FromIterator;
 use std::str::FromStr;
 
@@ -23,15 +24,6 @@ impl Public {
     pub const LEN: usize = 32;
     const ADDRESS_CHECKSUM_LEN: usize = 5;
 
-    fn dalek_key(&self) -> Result<ed25519_dalek::PublicKey, Error> {
-        Ok(
-            ed25519_dalek::PublicKey::from_bytes(&self.0).map_err(|e| Error::SignatureError {
-                msg: String::from("Converting to PublicKey"),
-                source: e,
-            })?,
-        )
-    }
-
     pub fn to_address(&self) -> Address {
         Address::from(self)
     }
@@ -44,24 +36,16 @@ impl Public {
     }
 
     pub fn verify(&self, message: &[u8], signature: &Signature) -> Result<(), Error> {
-        let result = self.dalek_key();
-
-        match result {
-            Ok(key) => {
-                key.verify(message, &signature.internal())
-                    .map_err(|e| Error::SignatureError {
-                        msg: format!(
-                            "Public verification failed: sig: {:?} message: {:?} key: {:?}",
-                            signature, message, key
-                        ),
-                        source: e,
-                    })
-            }
-            // We're returning false here because someone we can be given a bad public key,
-            // but since we're not checking the key for how valid it is, only the signature,
-            // we just say that it does not pass validation.
-            _ => Err(Error::BadPublicKey),
-        }
+        NanoScheme::verify(&self.0, message, signature)
+    }
+
+    /// Order the same way as the rendered addresses would be sorted, without rendering them.
+    ///
+    /// The base 32 alphabet used for addresses is in ASCII order, and the encoded key is left
+    /// padded to a fixed length, so comparing the key bytes gives the same ordering. The checksum
+    /// only differs when the keys differ, so it never affects the result.
+    pub fn cmp_by_address(&self, other: &Public) -> Ordering {
+        self.0.cmp(&other.0)
     }
 }
 
@@ -106,8 +90,8 @@ pub fn from_address<'de, D>(deserializer: D) -> Result<Public, <D as Deserialize
 where
     D: Deserializer<'de>,
 {
-    let s: &str = Deserialize::deserialize(deserializer)?;
-    Ok(Address::from_str(s)
+    let s: String = Deserialize::deserialize(deserializer)?;
+    Ok(Address::from_str(&s)
         .map_err(serde::de::Error::custom)?
         .to_public())
 }
@@ -115,7 +99,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::Public;
-    use crate::Private;
+    use crate::{Private, Seed};
     use std::convert::TryFrom;
     use std::str::FromStr;
 
@@ -135,6 +119,23 @@ mod tests {
         )
     }
 
+    #[test]
+    fn cmp_by_address() {
+        let mut publics: Vec<Public> = (0..50)
+            .map(|index| Seed::zero().derive(index).to_public().unwrap())
+            .collect();
+        publics.push(Public::from_str(&"0".repeat(64)).unwrap());
+        publics.push(Public::from_str(&"F".repeat(64)).unwrap());
+
+        let mut addresses: Vec<String> =
+            publics.iter().map(|p| p.to_address().to_string()).collect();
+        addresses.sort();
+
+        publics.sort_by(|a, b| a.cmp_by_address(b));
+        let sorted: Vec<String> = publics.iter().map(|p| p.to_address().to_string()).collect();
+        assert_eq!(sorted, addresses);
+    }
+
     #[test]
     fn hex() {
         let s = "19D3D919475DEED4696B5D13018151D1AF88B2BD3BCFF048B45031C1F36D1858";
@@ -0,0 +1,107 @@
+use crate::{Error, Signature};
+use ed25519_dalek::ed25519::signature::Signature as InternalSignature;
+use ed25519_dalek::{ExpandedSecretKey, PublicKey, SecretKey, Verifier};
+use std::convert::TryFrom;
+
+/// How signatures are created and checked for a pair of 32 byte keys.
+///
+/// Nano uses ed25519, but with blake2b as the internal hash instead of SHA-512. Standard ed25519
+/// produces different public keys and signatures from the same private key, so signing always
+/// goes through [NanoScheme] instead of using a specific implementation directly.
+pub(crate) trait SignatureScheme {
+    fn public(private: &[u8; 32]) -> Result<[u8; 32], Error>;
+
+    fn sign(private: &[u8; 32], message: &[u8]) -> Result<Signature, Error>;
+
+    fn verify(public: &[u8; 32], message: &[u8], signature: &Signature) -> Result<(), Error>;
+}
+
+/// ed25519 using blake2b, via the patched `ed25519-dalek` in `external/`.
+pub(crate) struct Ed25519Blake2b;
+
+impl Ed25519Blake2b {
+    fn secret(private: &[u8; 32]) -> Result<SecretKey, Error> {
+        SecretKey::from_bytes(private).map_err(|e| Error::SignatureError {
+            msg: String::from("Converting to SecretKey"),
+            source: e,
+        })
+    }
+
+    fn public_key(public: &[u8; 32]) -> Result<PublicKey, Error> {
+        PublicKey::from_bytes(public).map_err(|e| Error::SignatureError {
+            msg: String::from("Converting to PublicKey"),
+            source: e,
+        })
+    }
+}
+
+impl SignatureScheme for Ed25519Blake2b {
+    fn public(private: &[u8; 32]) -> Result<[u8; 32], Error> {
+        Ok(PublicKey::from(&Self::secret(private)?).to_bytes())
+    }
+
+    fn sign(private: &[u8; 32], message: &[u8]) -> Result<Signature, Error> {
+        let secret = Self::secret(private)?;
+        let public = PublicKey::from(&secret);
+        let signed = ExpandedSecretKey::from(&secret).sign(message, &public);
+        Signature::try_from(signed.as_bytes())
+    }
+
+    fn verify(public: &[u8; 32], message: &[u8], signature: &Signature) -> Result<(), Error> {
+        let key = match Self::public_key(public) {
+            Ok(key) => key,
+            // We're returning false here because someone we can be given a bad public key,
+            // but since we're not checking the key for how valid it is, only the signature,
+            // we just say that it does not pass validation.
+            Err(_) => return Err(Error::BadPublicKey),
+        };
+        key.verify(message, &signature.internal())
+            .map_err(|e| Error::SignatureError {
+                msg: format!(
+                    "Public verification failed: sig: {:?} message: {:?} key: {:?}",
+                    signature, message, key
+                ),
+                source: e,
+            })
+    }
+}
+
+/// The scheme used by [Private](crate::Private) and [Public](crate::Public).
+pub(crate) type NanoScheme = Ed25519Blake2b;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Public;
+    use std::str::FromStr;
+
+    /// The live genesis open block, signed by the reference node implementation.
+    const GENESIS_ACCOUNT: &str =
+        "E89208DD038FBB269987689621D52292AE9C35941A7484756ECCED92A65093BA";
+    const GENESIS_HASH: &str = "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948";
+    const GENESIS_SIGNATURE: &str = "9F0C933C8ADE004D808EA1985FA746A7E95BA2A38F867640F53EC8F180BDFE9E2C1268DEAD7C2664F356E37ABA362BC58E46DBA03E523A7B5A19E4B6EB12BB02";
+
+    #[test]
+    fn reference_signature() {
+        let public = Public::from_str(GENESIS_ACCOUNT).unwrap();
+        let hash = hex::decode(GENESIS_HASH).unwrap();
+        let signature = Signature::from_str(GENESIS_SIGNATURE).unwrap();
+        assert!(public.verify(&hash, &signature).is_ok());
+
+        let mut other = hash.to_owned();
+        other[0] ^= 1;
+        assert!(public.verify(&other, &signature).is_err());
+    }
+
+    #[test]
+    fn blake2b_public_key() {
+        // Standard ed25519 would give:
+        // 3B6A27BCCEB6A42D62A3A8D02A6F0D73653215771DE243A63AC048A18B59DA29
+        let public = NanoScheme::public(&[0u8; 32]).unwrap();
+        assert_eq!(
+            Public::try_from(&public[..]).unwrap(),
+            Public::from_str("19D3D919475DEED4696B5D13018151D1AF88B2BD3BCFF048B45031C1F36D1858")
+                .unwrap()
+        );
+    }
+}
@@ -1,8 +1,9 @@
 use crate::encoding::blake2b;
 use crate::hexify;
-use crate::Private;
+use crate::{Address, Private, Public};
 use bytes::{BufMut, BytesMut};
 use rand::RngCore;
+use std::collections::HashSet;
 use std::convert::TryFrom;
 
 /// 256 bit seed used to derive multiple addresses.
@@ -40,4 +41,79 @@ impl Seed {
         // Expect this to work all the time because it's coming from known correct types.
         Private::try_from(result.as_ref()).expect("conversion from seed")
     }
+
+    /// Find the index of the account in `0..max_index` that derives to the given public key.
+    pub fn find_index(&self, public: &Public, max_index: u32) -> Option<u32> {
+        (0..max_index).find(|index| match self.derive(*index).to_public() {
+            Ok(derived) => &derived == public,
+            Err(_) => false,
+        })
+    }
+
+    /// Check that this seed controls the given address, returning its index if an account in
+    /// `0..max_index` derives to it.
+    pub fn owns(&self, address: &Address, max_index: u32) -> Option<u32> {
+        self.find_index(&address.to_public(), max_index)
+    }
+
+    /// Derive each account in `0..max_index` once, returning the ones found in `known` with their
+    /// index, ordered by index.
+    ///
+    /// Stops early once every known address has been found.
+    pub fn scan_for_addresses(&self, known: &[Address], max_index: u32) -> Vec<(u32, Address)> {
+        let mut remaining: HashSet<Public> = known.iter().map(|a| a.to_public()).collect();
+        let mut found = vec![];
+        for index in 0..max_index {
+            if remaining.is_empty() {
+                break;
+            }
+            let public = match self.derive(index).to_public() {
+                Ok(public) => public,
+                Err(_) => continue,
+            };
+            if remaining.remove(&public) {
+                found.push((index, public.to_address()));
+            }
+        }
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn owns() {
+        let seed =
+            Seed::from_str("0000000000000000000000000000000000000000000000000000000000000001")
+                .unwrap();
+        let address = seed.derive(3).to_address().unwrap();
+        assert_eq!(seed.owns(&address, 10), Some(3));
+        assert_eq!(seed.owns(&address, 3), None);
+
+        let unrelated = Seed::zero().derive(3).to_address().unwrap();
+        assert_eq!(seed.owns(&unrelated, 10), None);
+    }
+
+    #[test]
+    fn scan_for_addresses() {
+        let seed =
+            Seed::from_str("0000000000000000000000000000000000000000000000000000000000000001")
+                .unwrap();
+        let known = vec![
+            seed.derive(7).to_address().unwrap(),
+            Seed::zero().derive(0).to_address().unwrap(),
+            seed.derive(2).to_address().unwrap(),
+        ];
+        assert_eq!(
+            seed.scan_for_addresses(&known, 10),
+            vec![(2, known[2].to_owned()), (7, known[0].to_owned())]
+        );
+        assert_eq!(
+            seed.scan_for_addresses(&known, 5),
+            vec![(2, known[2].to_owned())]
+        );
+    }
 }
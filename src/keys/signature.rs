@@ -1,4 +1,5 @@
-use crate::hexify;
+use crate::blocks::Block;
+use crate::{hexify, Public};
 
 /// A ed25519+blake2 signature that can be generated with [Private](crate::Private) and
 /// checked with [Public](crate::Public).
@@ -17,4 +18,48 @@ impl Signature {
     pub(crate) fn internal(&self) -> ed25519_dalek::Signature {
         ed25519_dalek::Signature::new(self.0)
     }
+
+    /// Whether this is a valid signature by `signer` over the hash of `block`.
+    ///
+    /// The signature stored in `block` isn't used, so this can check a signature from elsewhere,
+    /// e.g. a receipt.
+    pub fn covers_block(&self, block: &Block, signer: &Public) -> bool {
+        match block.hash() {
+            Ok(hash) => signer.verify(hash.as_bytes(), self).is_ok(),
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::{BlockHash, BlockType, Link, Previous, ValidationState};
+    use crate::{Raw, Seed};
+
+    fn open_block(balance: u128) -> Block {
+        let account = Seed::zero().derive(0).to_public().unwrap();
+        Block::new(
+            BlockType::State,
+            account.to_owned(),
+            Previous::Open,
+            account,
+            Raw::from(balance),
+            Link::Source(BlockHash::zero()),
+            ValidationState::Valid,
+        )
+    }
+
+    #[test]
+    fn covers_block() {
+        let private = Seed::zero().derive(0);
+        let signer = private.to_public().unwrap();
+        let block = open_block(100);
+        let signature = private.sign(block.hash().unwrap().as_bytes()).unwrap();
+
+        assert!(signature.covers_block(&block, &signer));
+        assert!(!signature.covers_block(&open_block(200), &signer));
+        let other = Seed::zero().derive(1).to_public().unwrap();
+        assert!(!signature.covers_block(&block, &other));
+    }
 }
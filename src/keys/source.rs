@@ -0,0 +1,70 @@
+use crate::phrase::Language;
+use crate::{Error, Phrase, Private, Seed};
+use std::str::FromStr;
+
+/// Any of the inputs a wallet can derive accounts from, e.g. whatever a user pasted into a CLI.
+#[derive(Debug, Clone)]
+pub enum KeySource {
+    /// Accounts derived with BIP44 from the phrase and password.
+    Phrase(Phrase),
+
+    /// Accounts derived from the seed by index. There's no password.
+    Seed(Seed),
+
+    /// BIP39 entropy of an English phrase, derived like [KeySource::Phrase].
+    Entropy(Vec<u8>),
+
+    /// A seed as hex, derived like [KeySource::Seed].
+    Hex(String),
+}
+
+impl KeySource {
+    /// The private key of account `index`. `password` is ignored for seeds.
+    pub fn to_private(&self, index: u32, password: &str) -> Result<Private, Error> {
+        match self {
+            KeySource::Phrase(phrase) => phrase.to_private(index, password),
+            KeySource::Seed(seed) => Ok(seed.derive(index)),
+            KeySource::Entropy(entropy) => {
+                Phrase::from_entropy(Language::English, entropy)?.to_private(index, password)
+            }
+            KeySource::Hex(hex) => Ok(Seed::from_str(hex)?.derive(index)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equivalent_sources() {
+        let phrase = Phrase::from_words(
+            Language::English,
+            "edge defense waste choose enrich upon flee junk siren film clown finish \
+            luggage leader kid quick brick print evidence swap drill paddle truly occur",
+        )
+        .unwrap();
+        let entropy = phrase.to_mnemonic().unwrap().entropy().to_vec();
+        let from_phrase = KeySource::Phrase(phrase)
+            .to_private(0, "some password")
+            .unwrap();
+        let from_entropy = KeySource::Entropy(entropy)
+            .to_private(0, "some password")
+            .unwrap();
+        assert_eq!(from_phrase.as_bytes(), from_entropy.as_bytes());
+        assert_eq!(
+            from_phrase.to_address().unwrap().to_string(),
+            "nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d"
+        );
+
+        let hex = "1BC5FB0ECB41B07AE3272FE2CB037864382167ECE9ECEFB31237EE555627B891";
+        let seed = KeySource::Seed(Seed::from_str(hex).unwrap());
+        let hex = KeySource::Hex(hex.into());
+        assert_eq!(
+            seed.to_private(3, "").unwrap().as_bytes(),
+            hex.to_private(3, "ignored").unwrap().as_bytes()
+        );
+        assert!(KeySource::Hex("not hex".into()).to_private(0, "").is_err());
+        assert!(KeySource::Entropy(vec![0; 5]).to_private(0, "").is_err());
+    }
+}
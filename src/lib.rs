@@ -58,20 +58,29 @@ mod network;
 mod paths;
 mod pow;
 pub mod rpc;
+#[cfg(any(test, feature = "test_vectors"))]
+pub mod test_vectors;
+#[cfg(feature = "rpc_client")]
+pub mod transaction;
 pub mod units;
 pub mod vanity;
 mod version;
 pub mod wallet;
+pub mod websocket;
 
 pub use errors::{Error, Result};
 pub use keys::address::Address;
+pub use keys::encrypted::{EncryptedKey, KdfParams};
 pub use keys::phrase;
 pub use keys::phrase::Phrase;
 pub use keys::private::Private;
 pub use keys::public::Public;
 pub use keys::seed::Seed;
 pub use keys::signature::Signature;
+pub use keys::source::KeySource;
 pub use network::{Network, DEFAULT_PORT};
-pub use pow::{Difficulty, Subject, Work};
+pub use pow::{work_hex, Difficulty, Subject, Work, WorkCache};
+#[cfg(feature = "rpc_client")]
+pub use transaction::send;
 pub use units::raw::Raw;
 pub use version::Version;
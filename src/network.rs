@@ -1,5 +1,5 @@
 use crate::blocks::{Block, BlockHash, OpenBlock, Previous};
-use crate::Raw;
+use crate::{Public, Raw};
 use anyhow::anyhow;
 use std::convert::TryFrom;
 use std::str::FromStr;
@@ -32,26 +32,85 @@ fn live_genesis_block() -> OpenBlock {
     ).unwrap()
 }
 
+/// The genesis block of the test network, the same as the dev network of the reference node so
+/// its local test setups can be used.
+fn test_genesis_block() -> OpenBlock {
+    serde_json::from_str(
+    r#"
+        {
+            "type": "open",
+            "source": "B0311EA55708D6A53C75CDBF88300259C6D018522FE3D4D0A242E431F9E8B6D0",
+            "representative": "nano_3e3j5tkog48pnny9dmfzj1r16pg8t1e76dz5tmac6iq689wyjfpiij4txtdo",
+            "account": "nano_3e3j5tkog48pnny9dmfzj1r16pg8t1e76dz5tmac6iq689wyjfpiij4txtdo",
+            "work": "7B42A00EE91D5810",
+            "signature": "ECDA914373A2F0CA1296475BAEE40500A7F0A7AD72A5A80C81D7FAB7F6C802B2CC7DB50F5DD0FB25B2EF11761FA7344A158DD5A700B21BD47DE5BD0F63153A02"
+        }
+        "#
+    ).unwrap()
+}
+
 impl Network {
-    pub fn genesis_block(&self) -> Block {
+    /// The open block that created the supply on this network.
+    ///
+    /// The beta network's genesis block isn't known yet, which is an error.
+    pub fn genesis_block(&self) -> anyhow::Result<Block> {
         let open_block = match self {
             Self::Live => live_genesis_block(),
-            _ => todo!(),
+            Self::Test => test_genesis_block(),
+            Self::Beta => return Err(anyhow!("Genesis block of the {} network is unknown", self)),
         };
 
+        Ok(Block::from_open_block(
+            &open_block,
+            &Previous::Open,
+            &self.genesis_balance(),
+        ))
+    }
+
+    /// The balance of the genesis account once opened, which is the full supply.
+    pub fn genesis_balance(&self) -> Raw {
         // Give the genesis block the maximum u128 value.
-        let balance = Raw::max();
+        Raw::max()
+    }
+
+    pub fn genesis_hash(&self) -> anyhow::Result<BlockHash> {
+        let hash = match self {
+            Self::Live => "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+            Self::Test => "04270D7F11C4B2B472F2854C5A59F2A7E84226CE9ED799DE75744BD7D85FC9D9",
+            Self::Beta => return Err(anyhow!("Genesis hash of the {} network is unknown", self)),
+        };
+        Ok(BlockHash::from_str(hash).unwrap())
+    }
 
-        Block::from_open_block(&open_block, &Previous::Open, &balance)
+    /// The account whose key signs the epoch blocks upgrading accounts to epoch `version`.
+    ///
+    /// The test network signs every epoch with its genesis key, like the reference node's dev
+    /// network.
+    pub fn epoch_signer(&self, version: u8) -> anyhow::Result<Public> {
+        let public = match (self, version) {
+            (Self::Live, 1) => "E89208DD038FBB269987689621D52292AE9C35941A7484756ECCED92A65093BA",
+            (Self::Live, 2) => "DD24A9200D4BF8247981E4AC63DBDE38FD2319386970A26D02ECC98C79975DB1",
+            (Self::Test, 1) | (Self::Test, 2) => {
+                "B0311EA55708D6A53C75CDBF88300259C6D018522FE3D4D0A242E431F9E8B6D0"
+            }
+            _ => {
+                return Err(anyhow!(
+                    "Epoch {} signer of the {} network is unknown",
+                    version,
+                    self
+                ))
+            }
+        };
+        Ok(Public::from_str(public).unwrap())
     }
 
-    pub fn genesis_hash(&self) -> BlockHash {
+    /// The blake2b key used when hashing work and subject together to find the difficulty.
+    ///
+    /// All networks currently share unkeyed work hashing, but this allows a test or custom
+    /// network to diverge in the future.
+    pub fn work_key(&self) -> &'static [u8] {
         match self {
-            Self::Live => BlockHash::from_str(
-                "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
-            )
-            .unwrap(),
-            _ => todo!(),
+            Self::Test | Self::Beta | Self::Live => &[],
         }
     }
 
@@ -84,8 +143,22 @@ mod tests {
     #[test]
     fn hash_live_genesis_block() {
         let net = Network::Live;
-        let block = net.genesis_block();
+        let block = net.genesis_block().unwrap();
         let hash = block.hash().unwrap();
-        assert_eq!(hash, &net.genesis_hash());
+        assert_eq!(hash, &net.genesis_hash().unwrap());
+    }
+
+    #[test]
+    fn test_genesis_block() {
+        let net = Network::Test;
+        let block = net.genesis_block().unwrap();
+        assert_eq!(block.hash().unwrap(), &net.genesis_hash().unwrap());
+        block.verify_signature(block.account()).unwrap();
+    }
+
+    #[test]
+    fn unknown_beta_genesis() {
+        assert!(Network::Beta.genesis_block().is_err());
+        assert!(Network::Beta.genesis_hash().is_err());
     }
 }
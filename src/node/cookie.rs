@@ -1,11 +1,14 @@
+use crate::encoding::expect_len;
 use crate::hexify;
 use crate::node::header::Header;
 use crate::node::wire::Wire;
 use anyhow::anyhow;
 use rand::RngCore;
+use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::time::{Duration, Instant};
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 #[repr(C)]
 pub struct Cookie([u8; Cookie::LEN]);
 
@@ -30,6 +33,7 @@ impl Wire for Cookie {
     where
         Self: Sized,
     {
+        expect_len(data.len(), Self::len(None)?, "Cookie")?;
         // TODO: thiserror
         Cookie::try_from(data).map_err(|e| anyhow!("Deserializing cookie {:?}", e))
     }
@@ -39,6 +43,57 @@ impl Wire for Cookie {
     }
 }
 
+/// Cookies that have been used in a handshake recently, so a handshake reusing one can be rejected
+/// as a replay.
+///
+/// Cookies are forgotten once they're older than the window.
+#[derive(Debug)]
+pub struct UsedCookies {
+    window: Duration,
+    used: HashMap<Cookie, Instant>,
+}
+
+impl UsedCookies {
+    pub const DEFAULT_WINDOW: Duration = Duration::from_secs(300);
+
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            used: HashMap::new(),
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    pub fn set_window(&mut self, window: Duration) {
+        self.window = window;
+    }
+
+    /// Record `cookie` as used, failing if it was already used within the window.
+    pub fn check(&mut self, cookie: &Cookie) -> anyhow::Result<()> {
+        self.check_at(cookie, Instant::now())
+    }
+
+    fn check_at(&mut self, cookie: &Cookie, now: Instant) -> anyhow::Result<()> {
+        let window = self.window;
+        self.used
+            .retain(|_, used_at| now.saturating_duration_since(*used_at) < window);
+        if self.used.contains_key(cookie) {
+            return Err(anyhow!("Handshake reused cookie {:?}", cookie));
+        }
+        self.used.insert(cookie.to_owned(), now);
+        Ok(())
+    }
+}
+
+impl Default for UsedCookies {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_WINDOW)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -52,4 +107,22 @@ mod tests {
         assert_eq!(c1.0[0], c2.0[0]);
         assert_eq!(c1.0[31], c2.0[31]);
     }
+
+    #[test]
+    fn replayed_cookie() {
+        let mut used = UsedCookies::new(Duration::from_secs(60));
+        let cookie = Cookie::random();
+        let start = Instant::now();
+
+        used.check_at(&cookie, start).unwrap();
+        assert!(used
+            .check_at(&cookie, start + Duration::from_secs(30))
+            .is_err());
+        used.check_at(&Cookie::random(), start + Duration::from_secs(30))
+            .unwrap();
+
+        // Outside of the window the cookie has been forgotten.
+        used.check_at(&cookie, start + Duration::from_secs(90))
+            .unwrap();
+    }
 }
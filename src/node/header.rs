@@ -207,6 +207,7 @@ impl Extensions {
     // Bit offsets and lengths
     const QUERY: usize = 0;
     const RESPONSE: usize = 1;
+    const ROOT_ONLY: usize = 2;
     const ITEM_COUNT: usize = 12;
     const ITEM_COUNT_BITS: usize = 4;
     const BLOCK_TYPE: usize = 8;
@@ -234,16 +235,37 @@ impl Extensions {
         self.bits()[Self::RESPONSE]
     }
 
+    /// Used by confirm_req to signal that the payload only contains roots, not root/hash pairs.
+    pub fn root_only(&mut self) -> &mut Self {
+        self.mut_bits().set(Self::ROOT_ONLY, true);
+        self
+    }
+
+    pub fn is_root_only(&self) -> bool {
+        self.bits()[Self::ROOT_ONLY]
+    }
+
     pub fn item_count(&self) -> usize {
         self.bits()[Self::ITEM_COUNT..Self::ITEM_COUNT + Self::ITEM_COUNT_BITS].load_be()
     }
 
+    pub fn set_item_count(&mut self, count: usize) -> &mut Self {
+        self.mut_bits()[Self::ITEM_COUNT..Self::ITEM_COUNT + Self::ITEM_COUNT_BITS].store_be(count);
+        self
+    }
+
     pub fn block_type(&self) -> anyhow::Result<BlockType> {
         self.bits()[Self::BLOCK_TYPE..Self::BLOCK_TYPE + Self::BLOCK_TYPE_BITS]
             .load_be::<u8>()
             .try_into()
     }
 
+    pub fn set_block_type(&mut self, block_type: BlockType) -> &mut Self {
+        self.mut_bits()[Self::BLOCK_TYPE..Self::BLOCK_TYPE + Self::BLOCK_TYPE_BITS]
+            .store_be(block_type.as_u8());
+        self
+    }
+
     fn bits(&self) -> &BitSlice<u8, Lsb0> {
         self.0.view_bits()
     }
@@ -262,6 +284,9 @@ impl std::fmt::Debug for Extensions {
         if self.is_response() {
             s.push("Response")
         }
+        if self.is_root_only() {
+            s.push("RootOnly")
+        }
         write!(f, "[{}]", s.join(", "))?;
 
         Ok(())
@@ -369,4 +394,14 @@ mod tests {
             assert_eq!(ext.item_count() as u8, *expected);
         }
     }
+
+    #[test]
+    fn set_item_count_and_block_type() {
+        let ext = *Extensions::new()
+            .set_item_count(10)
+            .set_block_type(BlockType::NotABlock);
+        assert_eq!(ext.item_count(), 10);
+        assert_eq!(ext.block_type().unwrap(), BlockType::NotABlock);
+        assert!(!ext.is_root_only());
+    }
 }
@@ -1,11 +1,11 @@
-use crate::blocks::{Block, BlockHash, BlockType};
+use crate::blocks::{BlockHash, BlockHolder, BlockType};
 use crate::bytes::Bytes;
-use crate::encoding::blake2b;
+use crate::encoding::{blake2b, expect_len};
 use crate::node::header::Header;
 use crate::node::timestamp::Timestamp;
 use crate::node::wire::Wire;
 use crate::{Public, Signature};
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use std::convert::TryFrom;
 
 /// This is a vote on the network by a representative for one or more block hashes.
@@ -23,7 +23,7 @@ pub enum Confirm {
     VoteByHash(Vec<BlockHash>),
 
     // TODO: This looks like it isn't used on the live network.
-    Block(Block),
+    Block(BlockHolder),
 }
 
 impl ConfirmAck {
@@ -45,12 +45,14 @@ impl ConfirmAck {
 
     pub fn verify_signature(&self) -> anyhow::Result<()> {
         self.account
-            .verify(&self.inner_hash(), &self.signature)
+            .verify(&self.inner_hash()?, &self.signature)
             .context("Verify signature on ConfirmAck")
     }
 
     // nano::block_hash nano::vote::hash () const
-    pub fn inner_hash(&self) -> Vec<u8> {
+    /// Fails for a vote on a full block, as its hash isn't known until it's converted to a
+    /// [crate::blocks::Block].
+    pub fn inner_hash(&self) -> anyhow::Result<Vec<u8>> {
         let mut v = Vec::new();
 
         // TODO: Only add this prefix if there's data. See nano::vote::hash()
@@ -62,16 +64,28 @@ impl ConfirmAck {
             }
             v.extend_from_slice(&self.timestamp.to_bytes())
         } else {
-            todo!("handle block hash");
+            return Err(anyhow!("Hashing a vote for a full block is not supported"));
         }
 
-        blake2b(BlockHash::LEN, &v).to_vec()
+        Ok(blake2b(BlockHash::LEN, &v).to_vec())
     }
 }
 
 impl Wire for ConfirmAck {
     fn serialize(&self) -> Vec<u8> {
-        unimplemented!()
+        let mut v = vec![];
+        v.extend_from_slice(self.account.as_bytes());
+        v.extend_from_slice(self.signature.as_bytes());
+        v.extend_from_slice(&self.timestamp.to_bytes());
+        match &self.confirm {
+            Confirm::VoteByHash(hashes) => {
+                for hash in hashes {
+                    v.extend_from_slice(hash.as_bytes());
+                }
+            }
+            Confirm::Block(block) => v.extend_from_slice(&block.serialize()),
+        }
+        v
     }
 
     fn deserialize(header: Option<&Header>, data: &[u8]) -> anyhow::Result<Self>
@@ -80,6 +94,7 @@ impl Wire for ConfirmAck {
     {
         debug_assert!(header.is_some());
         let header = header.unwrap();
+        expect_len(data.len(), Self::len(Some(header))?, "Confirm ack")?;
 
         let mut data = Bytes::new(data);
         let account = Public::try_from(data.slice(Public::LEN)?)?;
@@ -94,9 +109,10 @@ impl Wire for ConfirmAck {
             }
             Confirm::VoteByHash(block_hashes)
         } else {
-            // let block = Block;
-            dbg!("block!!!!!!!", header.ext().block_type().unwrap());
-            todo!()
+            Confirm::Block(BlockHolder::deserialize(
+                Some(header),
+                data.slice(BlockHolder::len(Some(header))?)?,
+            )?)
         };
 
         Ok(Self::new(account, signature, timestamp, confirm))
@@ -109,8 +125,7 @@ impl Wire for ConfirmAck {
         if header.ext().block_type()? == BlockType::NotABlock {
             Ok(Self::VOTE_COMMON_LEN + header.ext().item_count() * BlockHash::LEN)
         } else {
-            todo!("got a block in confirm ack {:#?}", header);
-            // Ok(Self::VOTE_COMMON_LEN + FullBlock::LEN)
+            Ok(Self::VOTE_COMMON_LEN + BlockHolder::len(Some(header))?)
         }
     }
 }
@@ -118,6 +133,10 @@ impl Wire for ConfirmAck {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::blocks::{ChangeBlock, OpenBlock, ReceiveBlock};
+    use crate::network::Network;
+    use crate::node::header::{Extensions, MessageType};
+    use crate::Work;
     use std::str::FromStr;
 
     #[test]
@@ -140,5 +159,59 @@ mod tests {
             Confirm::VoteByHash(vec![hash1, hash2]),
         );
         assert!(confirm_ack.verify_signature().is_ok());
+
+        // Still valid after a round trip through the wire format.
+        let ext = *Extensions::new()
+            .set_item_count(2)
+            .set_block_type(BlockType::NotABlock);
+        let header = Header::new(Network::Live, MessageType::ConfirmAck, ext);
+        let data = confirm_ack.serialize();
+        assert_eq!(data.len(), ConfirmAck::len(Some(&header)).unwrap());
+        let decoded = ConfirmAck::deserialize(Some(&header), &data).unwrap();
+        assert!(decoded.verify_signature().is_ok());
+        assert_eq!(decoded.serialize(), data);
+    }
+
+    #[test]
+    fn block_round_trip() {
+        let signature = Signature::try_from([1u8; Signature::LEN].as_ref()).unwrap();
+        let account =
+            Public::from_str("2994D330022A052DF83E10FCE1B3E140496CDCD7E0C0F2FF6DE2670291B88011")
+                .unwrap();
+        let hash =
+            BlockHash::from_str("C3A3FE56D584CB997199E3B09EC454F62DED3B7EF875D9D7E8E5011AC34C77A5")
+                .unwrap();
+        let work = Some(Work::from_str("8A142E07A10996D5").unwrap());
+        let mut open = OpenBlock::new(hash.to_owned(), account.to_owned(), account.to_owned());
+        open.work = work.to_owned();
+        open.signature = Some(signature.to_owned());
+        let mut change = ChangeBlock::new(hash.to_owned(), account.to_owned());
+        change.work = work.to_owned();
+        change.signature = Some(signature.to_owned());
+        let mut receive = ReceiveBlock::new(hash, account.to_owned());
+        receive.work = work;
+        receive.signature = Some(signature.to_owned());
+        let blocks = vec![
+            (BlockType::Open, BlockHolder::Open(open)),
+            (BlockType::Change, BlockHolder::Change(change)),
+            (BlockType::Receive, BlockHolder::Receive(receive)),
+        ];
+        for (block_type, block) in blocks {
+            let confirm_ack = ConfirmAck::new(
+                account.to_owned(),
+                signature.to_owned(),
+                Timestamp::from_u64(1),
+                Confirm::Block(block.to_owned()),
+            );
+            let ext = *Extensions::new()
+                .set_item_count(1)
+                .set_block_type(block_type);
+            let header = Header::new(Network::Live, MessageType::ConfirmAck, ext);
+            let data = confirm_ack.serialize();
+            assert_eq!(data.len(), ConfirmAck::len(Some(&header)).unwrap());
+            let decoded = ConfirmAck::deserialize(Some(&header), &data).unwrap();
+            assert!(decoded.verify_signature().is_err());
+            assert!(matches!(decoded.confirm, Confirm::Block(decoded) if decoded == block));
+        }
     }
 }
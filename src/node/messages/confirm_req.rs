@@ -7,28 +7,54 @@ use anyhow::Context;
 use std::convert::TryFrom;
 use tracing::info;
 
-/// Requests confirmation of the given block or list of root/hash pairs.
+/// Requests confirmation of the given block, list of root/hash pairs, or list of roots.
 //
 // seq:
 //  - id: reqbyhash
 //    if: _root.header.block_type == enum_blocktype::not_a_block
 //    type: confirm_request_by_hash
+//  - id: reqbyroot
+//    if: _root.header.block_type == enum_blocktype::not_a_block and _root.header.root_only
+//    type: confirm_request_by_root
 //  - id: block
 //    if: _root.header.block_type != enum_blocktype::not_a_block
 //    type: block_selector(_root.header.block_type_int)
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
+#[allow(clippy::enum_variant_names)]
 pub enum ConfirmReq {
     ConfirmReqByHash(Vec<RootHashPair>),
+
+    /// Only the roots are known, e.g. when requesting confirmation of an account's frontier.
+    /// This is signalled with the root only bit in the header extensions.
+    ConfirmReqByRoot(Vec<BlockHash>),
+
     BlockSelector(BlockHolder),
 }
 
 impl ConfirmReq {
     pub const CONFIRM_REQ_BY_HASH_LEN: usize = BlockHash::LEN * 2;
+    pub const CONFIRM_REQ_BY_ROOT_LEN: usize = BlockHash::LEN;
 }
 
 impl Wire for ConfirmReq {
     fn serialize(&self) -> Vec<u8> {
-        unimplemented!()
+        match self {
+            ConfirmReq::ConfirmReqByHash(pairs) => {
+                let mut v = Vec::with_capacity(Self::CONFIRM_REQ_BY_HASH_LEN * pairs.len());
+                for pair in pairs {
+                    v.extend_from_slice(&pair.to_bytes());
+                }
+                v
+            }
+            ConfirmReq::ConfirmReqByRoot(roots) => {
+                let mut v = Vec::with_capacity(Self::CONFIRM_REQ_BY_ROOT_LEN * roots.len());
+                for root in roots {
+                    v.extend_from_slice(root.as_bytes());
+                }
+                v
+            }
+            ConfirmReq::BlockSelector(block) => block.serialize(),
+        }
     }
 
     fn deserialize(header: Option<&Header>, data: &[u8]) -> anyhow::Result<Self>
@@ -40,7 +66,24 @@ impl Wire for ConfirmReq {
 
         let mut bytes = Bytes::new(data);
 
-        if header.ext().block_type()? == BlockType::NotABlock {
+        if header.ext().block_type()? == BlockType::NotABlock && header.ext().is_root_only() {
+            let count = header.ext().item_count();
+            expect_len(
+                data.len(),
+                Self::CONFIRM_REQ_BY_ROOT_LEN * count,
+                "HandleConfirmReq roots",
+            )?;
+
+            let mut roots = Vec::with_capacity(count);
+            for _ in 0..count {
+                let value = bytes
+                    .slice(BlockHash::LEN)
+                    .context("Confirm req slicing root")?;
+                let root = BlockHash::try_from(value).context("Confirm req root from bytes")?;
+                roots.push(root);
+            }
+            Ok(Self::ConfirmReqByRoot(roots))
+        } else if header.ext().block_type()? == BlockType::NotABlock {
             let count = header.ext().item_count() as usize;
             let expected_capacity = RootHashPair::LEN * count;
             expect_len(
@@ -82,7 +125,9 @@ impl Wire for ConfirmReq {
         debug_assert!(header.is_some());
         let header = header.unwrap();
 
-        if header.ext().block_type()? == BlockType::NotABlock {
+        if header.ext().block_type()? == BlockType::NotABlock && header.ext().is_root_only() {
+            Ok(Self::CONFIRM_REQ_BY_ROOT_LEN * header.ext().item_count())
+        } else if header.ext().block_type()? == BlockType::NotABlock {
             Ok(Self::CONFIRM_REQ_BY_HASH_LEN * header.ext().item_count())
         } else {
             BlockHolder::len(Some(header))
@@ -90,7 +135,7 @@ impl Wire for ConfirmReq {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct RootHashPair {
     pub hash: BlockHash,
     pub root: BlockHash,
@@ -98,6 +143,14 @@ pub struct RootHashPair {
 
 impl RootHashPair {
     const LEN: usize = BlockHash::LEN * 2;
+
+    /// The hash followed by the root, the same order as [RootHashPair::try_from].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut v = Vec::with_capacity(Self::LEN);
+        v.extend_from_slice(self.hash.as_bytes());
+        v.extend_from_slice(self.root.as_bytes());
+        v
+    }
 }
 
 impl TryFrom<&[u8]> for RootHashPair {
@@ -111,3 +164,106 @@ impl TryFrom<&[u8]> for RootHashPair {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::StateBlock;
+    use crate::network::Network;
+    use crate::node::header::{Extensions, MessageType};
+    use std::str::FromStr;
+
+    #[test]
+    fn round_trip_by_root() {
+        let roots = vec![
+            BlockHash::from_str("C3A3FE56D584CB997199E3B09EC454F62DED3B7EF875D9D7E8E5011AC34C77A5")
+                .unwrap(),
+            BlockHash::from_str("139E1064D7CCC26495EFB4030015C02CE78556EBE3547192843B0E71C91599FC")
+                .unwrap(),
+        ];
+        let ext = *Extensions::new()
+            .root_only()
+            .set_item_count(roots.len())
+            .set_block_type(BlockType::NotABlock);
+        let header = Header::new(Network::Live, MessageType::ConfirmReq, ext);
+
+        let confirm_req = ConfirmReq::ConfirmReqByRoot(roots);
+        let data = confirm_req.serialize();
+        assert_eq!(data.len(), ConfirmReq::len(Some(&header)).unwrap());
+
+        let decoded = ConfirmReq::deserialize(Some(&header), &data).unwrap();
+        assert_eq!(decoded, confirm_req);
+    }
+
+    #[test]
+    fn round_trip_by_hash() {
+        let pairs = vec![
+            RootHashPair {
+                hash: BlockHash::from_str(
+                    "C3A3FE56D584CB997199E3B09EC454F62DED3B7EF875D9D7E8E5011AC34C77A5",
+                )
+                .unwrap(),
+                root: BlockHash::from_str(
+                    "139E1064D7CCC26495EFB4030015C02CE78556EBE3547192843B0E71C91599FC",
+                )
+                .unwrap(),
+            },
+            RootHashPair {
+                hash: BlockHash::zero(),
+                root: BlockHash::from_str(
+                    "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+                )
+                .unwrap(),
+            },
+        ];
+        let ext = *Extensions::new()
+            .set_item_count(pairs.len())
+            .set_block_type(BlockType::NotABlock);
+        let header = Header::new(Network::Live, MessageType::ConfirmReq, ext);
+
+        let confirm_req = ConfirmReq::ConfirmReqByHash(pairs);
+        let data = confirm_req.serialize();
+        assert_eq!(data.len(), BlockHash::LEN * 2 * 2);
+        assert_eq!(data.len(), ConfirmReq::len(Some(&header)).unwrap());
+        // The hash comes before the root.
+        assert_eq!(data[0], 0xC3);
+        assert_eq!(data[BlockHash::LEN], 0x13);
+
+        let decoded = ConfirmReq::deserialize(Some(&header), &data).unwrap();
+        assert_eq!(decoded, confirm_req);
+    }
+
+    #[test]
+    fn round_trip_block_selector() {
+        let ext = *Extensions::new().set_block_type(BlockType::State);
+        let header = Header::new(Network::Live, MessageType::ConfirmReq, ext);
+        let data: Vec<u8> = (0..StateBlock::LEN).map(|i| i as u8).collect();
+
+        let confirm_req = ConfirmReq::deserialize(Some(&header), &data).unwrap();
+        assert!(matches!(confirm_req, ConfirmReq::BlockSelector(_)));
+        assert_eq!(confirm_req.serialize(), data);
+    }
+
+    #[test]
+    fn root_only_bit_selects_form() {
+        let data = [0u8; BlockHash::LEN * 2];
+        let ext = *Extensions::new()
+            .set_item_count(1)
+            .set_block_type(BlockType::NotABlock);
+        let header = Header::new(Network::Live, MessageType::ConfirmReq, ext);
+        assert!(matches!(
+            ConfirmReq::deserialize(Some(&header), &data).unwrap(),
+            ConfirmReq::ConfirmReqByHash(_)
+        ));
+
+        let ext = *Extensions::new()
+            .root_only()
+            .set_item_count(2)
+            .set_block_type(BlockType::NotABlock);
+        let header = Header::new(Network::Live, MessageType::ConfirmReq, ext);
+        assert!(matches!(
+            ConfirmReq::deserialize(Some(&header), &data).unwrap(),
+            ConfirmReq::ConfirmReqByRoot(_)
+        ));
+    }
+}
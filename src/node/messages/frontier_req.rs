@@ -1,4 +1,5 @@
 use crate::bytes::Bytes;
+use crate::encoding::expect_len;
 use crate::node::header::Header;
 use crate::node::wire::Wire;
 use crate::Public;
@@ -24,6 +25,7 @@ impl Wire for FrontierReq {
     where
         Self: Sized,
     {
+        expect_len(data.len(), Self::len(None)?, "Frontier request")?;
         let mut bytes = Bytes::new(data);
         let start =
             Public::try_from(bytes.slice(Public::LEN)?).expect("frontier req deserializing start");
@@ -1,5 +1,6 @@
 use crate::blocks::BlockHash;
 use crate::bytes::Bytes;
+use crate::encoding::expect_len;
 use crate::node::header::Header;
 use crate::node::wire::Wire;
 use crate::Public;
@@ -26,6 +27,7 @@ impl Wire for FrontierResp {
         Self: Sized,
     {
         debug_assert!(header.is_none());
+        expect_len(data.len(), Self::len(header)?, "Frontier response")?;
         let context = || format!("Deserialize frontier response");
         let mut bytes = Bytes::new(data);
 
@@ -1,4 +1,5 @@
 use crate::bytes::Bytes;
+use crate::encoding::expect_len;
 use crate::node::cookie::Cookie;
 use crate::node::header::Header;
 use crate::node::wire::Wire;
@@ -13,7 +14,14 @@ pub struct Handshake {
 
 impl Wire for Handshake {
     fn serialize(&self) -> Vec<u8> {
-        unimplemented!()
+        let mut v = vec![];
+        if let Some(query) = &self.query {
+            v.extend_from_slice(&query.serialize());
+        }
+        if let Some(response) = &self.response {
+            v.extend_from_slice(&response.serialize());
+        }
+        v
     }
 
     fn deserialize(header: Option<&Header>, data: &[u8]) -> Result<Self, anyhow::Error>
@@ -22,6 +30,7 @@ impl Wire for Handshake {
     {
         debug_assert!(header.is_some());
         let header = header.unwrap();
+        expect_len(data.len(), Self::len(Some(header))?, "Handshake")?;
         let mut bytes = Bytes::new(data);
         let mut s = Self {
             query: None,
@@ -116,6 +125,7 @@ impl Wire for HandshakeResponse {
     where
         Self: Sized,
     {
+        expect_len(data.len(), Self::len(None)?, "Handshake response")?;
         Ok(Self {
             public: Public::try_from(&data[0..Public::LEN])?,
             signature: Signature::try_from(&data[Public::LEN..])?,
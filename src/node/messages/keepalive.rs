@@ -1,4 +1,5 @@
 use crate::bytes::Bytes;
+use crate::encoding::expect_len;
 use crate::node::header::Header;
 use crate::node::peer_info::PeerInfo;
 use crate::node::wire::Wire;
@@ -11,14 +12,21 @@ impl Keepalive {
 }
 
 impl Wire for Keepalive {
+    /// Only the first [Keepalive::PEERS] peers are sent, and missing ones are written as zeros.
     fn serialize(&self) -> Vec<u8> {
-        unimplemented!()
+        let mut v = Vec::with_capacity(PeerInfo::LEN * Keepalive::PEERS);
+        for peer in self.0.iter().take(Keepalive::PEERS) {
+            v.extend_from_slice(&peer.serialize());
+        }
+        v.resize(PeerInfo::LEN * Keepalive::PEERS, 0);
+        v
     }
 
     fn deserialize(header: Option<&Header>, data: &[u8]) -> anyhow::Result<Self>
     where
         Self: Sized,
     {
+        expect_len(data.len(), Self::len(header)?, "Keepalive")?;
         let mut s = Self(vec![]);
         let mut bytes = Bytes::new(data);
         for _ in 0..Keepalive::PEERS {
@@ -36,3 +44,23 @@ impl Wire for Keepalive {
         Ok(PeerInfo::LEN * Keepalive::PEERS)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn round_trip() {
+        let keepalive = Keepalive(vec![
+            PeerInfo::from_str("[::ffff:255.254.253.252]:7075").unwrap(),
+            PeerInfo::from_str("[::ffff:1.2.3.4]:54000").unwrap(),
+        ]);
+        let data = keepalive.serialize();
+        assert_eq!(data.len(), Keepalive::len(None).unwrap());
+
+        let decoded = Keepalive::deserialize(None, &data).unwrap();
+        assert_eq!(decoded.0.len(), 2);
+        assert_eq!(decoded.serialize(), data);
+    }
+}
@@ -7,7 +7,7 @@ pub struct Publish(pub(crate) BlockHolder);
 
 impl Wire for Publish {
     fn serialize(&self) -> Vec<u8> {
-        unimplemented!()
+        self.0.serialize()
     }
 
     fn deserialize(header: Option<&Header>, data: &[u8]) -> anyhow::Result<Self>
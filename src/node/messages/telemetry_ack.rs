@@ -1,13 +1,13 @@
 use crate::blocks::BlockHash;
 use crate::bytes::Bytes;
+use crate::encoding::expect_len;
 use crate::node::header::Header;
 use crate::node::wire::Wire;
 use crate::{Public, Signature};
 use anyhow::Context;
 use std::convert::TryFrom;
-use tracing::warn;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct TelemetryAck {
     signature: Signature,
     node_id: Public,
@@ -35,13 +35,33 @@ impl TelemetryAck {
 
 impl Wire for TelemetryAck {
     fn serialize(&self) -> Vec<u8> {
-        unimplemented!()
+        let mut v = Vec::with_capacity(Self::LEN);
+        v.extend_from_slice(self.signature.as_bytes());
+        v.extend_from_slice(self.node_id.as_bytes());
+        v.extend_from_slice(&self.block_count.to_be_bytes());
+        v.extend_from_slice(&self.cemented_count.to_be_bytes());
+        v.extend_from_slice(&self.unchecked_count.to_be_bytes());
+        v.extend_from_slice(&self.account_count.to_be_bytes());
+        v.extend_from_slice(&self.bandwidth_cap.to_be_bytes());
+        v.extend_from_slice(&self.uptime.to_be_bytes());
+        v.extend_from_slice(&self.peer_count.to_be_bytes());
+        v.push(self.protocol_version);
+        v.extend_from_slice(self.genesis_block.as_bytes());
+        v.push(self.major_version);
+        v.push(self.minor_version);
+        v.push(self.patch_version);
+        v.push(self.prerelease_version);
+        v.push(self.maker);
+        v.extend_from_slice(&self.timestamp);
+        v.extend_from_slice(&self.active_difficulty);
+        v
     }
 
     fn deserialize(_header: Option<&Header>, data: &[u8]) -> Result<Self, anyhow::Error>
     where
         Self: Sized,
     {
+        expect_len(data.len(), Self::len(None)?, "Telemetry ack")?;
         let mut bytes = Bytes::new(data);
 
         let mut s = Self {
@@ -95,8 +115,8 @@ impl Wire for TelemetryAck {
         s.prerelease_version = bytes.u8()?;
         s.maker = bytes.u8()?;
 
-        warn!("TODO: telemetry ack timestamp");
-        warn!("TODO: telemetry ack active_difficulty");
+        s.timestamp.copy_from_slice(bytes.slice(8)?);
+        s.active_difficulty.copy_from_slice(bytes.slice(8)?);
 
         Ok(s)
     }
@@ -108,3 +128,42 @@ impl Wire for TelemetryAck {
         Ok(TelemetryAck::LEN)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn round_trip() {
+        let ack = TelemetryAck {
+            signature: Signature::from_str("721C6CAFD61C2D7ED27643C556F77AE900308BD5AAF458E74310E42773BB45494A138EE0291B6868C360EB983AB5CE8FF2EFF6A66044CBA2B128047ACDBD4402").unwrap(),
+            node_id: Public::from_str(
+                "2994D330022A052DF83E10FCE1B3E140496CDCD7E0C0F2FF6DE2670291B88011",
+            )
+            .unwrap(),
+            block_count: 1,
+            cemented_count: 2,
+            unchecked_count: 3,
+            account_count: 4,
+            bandwidth_cap: 5,
+            uptime: 6,
+            peer_count: 7,
+            protocol_version: 18,
+            genesis_block: BlockHash::from_str(
+                "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+            )
+            .unwrap(),
+            major_version: 21,
+            minor_version: 2,
+            patch_version: 1,
+            prerelease_version: 0,
+            maker: 0,
+            timestamp: 1_600_000_000_000u64.to_be_bytes(),
+            active_difficulty: [0xff, 0xff, 0xff, 0xf8, 0, 0, 0, 0],
+        };
+        let data = ack.serialize();
+        assert_eq!(data.len(), TelemetryAck::LEN);
+        assert_eq!(TelemetryAck::deserialize(None, &data).unwrap(), ack);
+    }
+}
@@ -4,8 +4,10 @@ mod header;
 mod messages;
 mod peer;
 mod peer_info;
+mod peer_registry;
 mod state;
 mod timestamp;
+mod weight;
 mod wire;
 
 use crate::rpc::server::RPCServer;
@@ -144,6 +144,16 @@ impl Peer {
 
                 // If the block is the genesis block, we basically just trust the balance.
                 if !block.is_genesis(&self.network)? {
+                    let source = block.source().with_context(context)?;
+                    self.state
+                        .lock()
+                        .await
+                        .get_block_by_hash(source)
+                        .await
+                        .context("Source block")
+                        .with_context(context)?
+                        .ok_or_else(|| anyhow!("Could not find source block"))
+                        .with_context(context)?;
                     // TODO: Make sure the balance in the open block matches the amount in the
                     // send block.
                 }
@@ -82,6 +82,14 @@ impl Peer {
                 .await
                 .context("Could not lookup cookie for socket addr.")?;
             if cookie.is_none() {
+                // Our cookie is removed once a response is verified with it, so this is also what
+                // happens to a replayed response.
+                if self.validate_handshakes {
+                    return Err(anyhow!(
+                        "Peer {:?} has no cookie. Unexpected or replayed handshake response.",
+                        self.peer_addr
+                    ));
+                }
                 warn!(
                     "Peer {:?} has no cookie. Can't verify handshake.",
                     self.peer_addr
@@ -94,6 +102,14 @@ impl Peer {
                 public
                     .verify(&cookie.as_bytes(), &signature)
                     .context("Invalid signature in handshake response")?;
+                self.used_cookies
+                    .check(cookie)
+                    .context("Replayed handshake response")?;
+                self.state
+                    .lock()
+                    .await
+                    .remove_cookie(&self.peer_addr)
+                    .await?;
             }
         }
 
@@ -320,7 +336,7 @@ impl Peer {
             .work
             .as_ref()
             .ok_or(anyhow!("Send sub-block {} has no work!", &send_block))?
-            .difficulty_block_hash(&send_block.hash)?;
+            .difficulty_block_hash(&self.network, &send_block.hash)?;
         let work_ok = block_difficulty >= Difficulty::new(live_epoch_2_send_threshold);
         if !work_ok {
             info!("Send sub-block {} has insufficient difficulty!", send_block);
@@ -377,6 +393,7 @@ mod tests {
     use std::net::SocketAddr;
     use std::str::FromStr;
     use std::sync::Arc;
+    use std::time::Duration;
     use tokio::sync::Mutex;
 
     fn root_block() -> (StateBlock, Block) {
@@ -464,9 +481,10 @@ mod tests {
 
     #[tokio::test]
     async fn should_retrieve_previous_as_account() {
+        let (_, root_block) = root_block();
         let (_, frontier_block) = frontier_block();
         let frontier = StateBlock::from(frontier_block.clone());
-        let peer = test_peer_with_blocks(&[&frontier_block]).await;
+        let peer = test_peer_with_blocks(&[&root_block, &frontier_block]).await;
 
         let frontier_result = Peer::previous_as_account_info(&peer, &frontier.hash)
             .await
@@ -477,7 +495,8 @@ mod tests {
 
     #[tokio::test]
     async fn should_process_good_send_sub_block_when_block_is_good() {
-        let peer = test_peer_with_blocks(&[]).await;
+        let (_, root_block) = root_block();
+        let peer = test_peer_with_blocks(&[&root_block]).await;
         let good_send_block = good_send_block();
         let good_send_block_hash = good_send_block.hash.clone();
 
@@ -533,4 +552,83 @@ mod tests {
         let block_was_stored = Peer::block_exists(&peer, &frontier.hash).await.unwrap();
         assert_eq!(block_was_stored, false)
     }
+
+    #[tokio::test]
+    async fn replayed_handshake_response() {
+        let mut peer = test_peer_with_blocks(&[]).await;
+        let cookie = Cookie::random();
+        peer.state
+            .lock()
+            .await
+            .set_cookie(peer.peer_addr, cookie.to_owned())
+            .await
+            .unwrap();
+
+        let private = Seed::random().derive(0);
+        let signature = private.sign(cookie.as_bytes()).unwrap();
+        let response = || Handshake {
+            query: None,
+            response: Some(HandshakeResponse::new(
+                private.to_public().unwrap(),
+                signature.to_owned(),
+            )),
+        };
+        let header = Header::new(
+            Network::Test,
+            MessageType::Handshake,
+            *Extensions::new().response(),
+        );
+
+        peer.handle_handshake(&header, response()).await.unwrap();
+        assert!(peer
+            .state
+            .lock()
+            .await
+            .cookie_for_socket_addr(&peer.peer_addr)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(peer.handle_handshake(&header, response()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn reused_cookie_within_window() {
+        let mut peer = test_peer_with_blocks(&[]).await;
+        let cookie = Cookie::random();
+        let private = Seed::random().derive(0);
+        let signature = private.sign(cookie.as_bytes()).unwrap();
+        let response = || Handshake {
+            query: None,
+            response: Some(HandshakeResponse::new(
+                private.to_public().unwrap(),
+                signature.to_owned(),
+            )),
+        };
+        let header = Header::new(
+            Network::Test,
+            MessageType::Handshake,
+            *Extensions::new().response(),
+        );
+
+        peer.state
+            .lock()
+            .await
+            .set_cookie(peer.peer_addr, cookie.to_owned())
+            .await
+            .unwrap();
+        peer.handle_handshake(&header, response()).await.unwrap();
+
+        // Even when the same cookie is handed out again, a response with it is rejected within
+        // the window.
+        peer.state
+            .lock()
+            .await
+            .set_cookie(peer.peer_addr, cookie.to_owned())
+            .await
+            .unwrap();
+        assert!(peer.handle_handshake(&header, response()).await.is_err());
+
+        peer.set_cookie_replay_window(Duration::from_secs(0));
+        peer.handle_handshake(&header, response()).await.unwrap();
+    }
 }
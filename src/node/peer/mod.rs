@@ -5,6 +5,7 @@ mod messages;
 use crate::blocks::Block;
 use crate::encoding::to_hex;
 use crate::network::Network;
+use crate::node::cookie::UsedCookies;
 use crate::node::header::{Extensions, Header, MessageType};
 use crate::node::state::ArcState;
 use crate::node::wire::Wire;
@@ -12,6 +13,7 @@ use crate::{Public, Raw};
 use anyhow::{anyhow, Context};
 use std::fmt::Debug;
 use std::net::SocketAddr;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tracing::{debug, info, instrument, trace};
 
@@ -73,6 +75,9 @@ pub struct Peer {
     peer_tx: mpsc::Sender<Packet>,
 
     last_annotation: Option<String>,
+
+    /// Cookies of handshake responses already accepted, to reject replays.
+    used_cookies: UsedCookies,
 }
 
 impl Peer {
@@ -97,11 +102,18 @@ impl Peer {
             peer_rx: incoming_rx,
             peer_tx: outgoing_tx,
             last_annotation: None,
+            used_cookies: UsedCookies::default(),
         };
 
         (s, incoming_tx, outgoing_rx)
     }
 
+    /// How long a cookie is remembered after a handshake with it, during which another handshake
+    /// response with the same cookie is rejected.
+    pub fn set_cookie_replay_window(&mut self, window: Duration) {
+        self.used_cookies.set_window(window);
+    }
+
     /// Run will loop forever and is expected to be spawned and will quit when the incoming channel
     /// is closed.
     #[instrument(name = "node", skip(self), fields(address = %self.peer_addr))]
@@ -276,6 +288,18 @@ impl Peer {
         }
     }
 
+    /// The total balance of a wallet's accounts. Unopened accounts count as zero.
+    pub async fn wallet_balance(&self, accounts: &[Public]) -> anyhow::Result<Raw> {
+        let mut total = Raw::zero();
+        for account in accounts {
+            let balance = self.account_balance(account).await?;
+            total = total
+                .checked_add(&balance)
+                .ok_or_else(|| anyhow!("Wallet balance overflow adding {:?}", account))?;
+        }
+        Ok(total)
+    }
+
     pub fn network(&self) -> &Network {
         &self.network
     }
@@ -288,10 +312,12 @@ impl Peer {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::blocks::{Block, BlockHash, OpenBlock, Previous, SendBlock};
+    use crate::blocks::{
+        Block, BlockHash, BlockType, Link, OpenBlock, Previous, SendBlock, ValidationState,
+    };
     use crate::network::DEFAULT_PORT;
     use crate::node::state::MemoryState;
-    use crate::Address;
+    use crate::{Address, Seed};
     use std::net::{Ipv4Addr, SocketAddrV4};
     use std::str::FromStr;
     use std::sync::Arc;
@@ -311,7 +337,7 @@ mod tests {
     #[tokio::test]
     async fn genesis() {
         let network = Network::Live;
-        let genesis = network.genesis_block();
+        let genesis = network.genesis_block().unwrap();
 
         let peer = empty_lattice(network).await;
         assert_eq!(
@@ -324,12 +350,55 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn wallet_balance() {
+        let network = Network::Live;
+        let peer = empty_lattice(network).await;
+        let source =
+            BlockHash::from_str("A170D51B94E00371ACE76E35AC81DC9405D5D04D4CEBC399AEACE07AE05DD293")
+                .unwrap();
+
+        let mut accounts = vec![];
+        for (index, balance) in [100u128, 200, 300].iter().enumerate() {
+            let account = Seed::zero().derive(index as u32).to_public().unwrap();
+            let open = Block::new(
+                BlockType::State,
+                account.to_owned(),
+                Previous::Open,
+                account.to_owned(),
+                Raw::from(*balance),
+                Link::Source(source.to_owned()),
+                ValidationState::Valid,
+            );
+            peer.state.lock().await.add_block(&open).await.unwrap();
+            accounts.push(account);
+        }
+        let unopened = Seed::zero().derive(3).to_public().unwrap();
+
+        assert_eq!(
+            peer.wallet_balance(&accounts).await.unwrap(),
+            Raw::from(600)
+        );
+        accounts.push(unopened);
+        assert_eq!(
+            peer.wallet_balance(&accounts).await.unwrap(),
+            Raw::from(600)
+        );
+        assert!(peer
+            .wallet_balance(&[
+                network.genesis_block().unwrap().account().to_owned(),
+                accounts[0].to_owned()
+            ])
+            .await
+            .is_err());
+    }
+
     /// Genesis Account: genesis (Open) -> gen_send (Send)
     /// Landing Account:                -> land_open (Open) -> land_send (Send)
     #[tokio::test]
     async fn send_then_recv_to_new_account() {
         let network = Network::Live;
-        let genesis = network.genesis_block();
+        let genesis = network.genesis_block().unwrap();
 
         let landing_account =
             Address::from_str("nano_13ezf4od79h1tgj9aiu4djzcmmguendtjfuhwfukhuucboua8cpoihmh8byo")
@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+use std::net::{IpAddr, SocketAddr};
+
+/// The set of known peer addresses, merged from handshakes, keepalives, RPC, etc.
+#[derive(Debug, Clone, Default)]
+pub struct PeerRegistry {
+    peers: HashSet<SocketAddr>,
+}
+
+impl PeerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, peer: SocketAddr) {
+        self.peers.insert(peer);
+    }
+
+    pub fn extend(&mut self, peers: &[SocketAddr]) {
+        self.peers.extend(peers.iter().cloned());
+    }
+
+    pub fn peers(&self) -> &HashSet<SocketAddr> {
+        &self.peers
+    }
+
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    /// Clean up the peer set:
+    /// * IPv4 mapped IPv6 addresses (`[::ffff:1.2.3.4]:7075`) are converted to IPv4
+    ///   (`1.2.3.4:7075`) so the same peer isn't stored twice.
+    /// * The node's own endpoint is removed.
+    /// * Unroutable addresses are dropped, i.e. unspecified, multicast, broadcast or port 0.
+    pub fn canonicalize(&mut self, self_endpoint: &SocketAddr) {
+        let self_endpoint = canonical(self_endpoint);
+        self.peers = self
+            .peers
+            .iter()
+            .map(canonical)
+            .filter(|peer| peer != &self_endpoint && is_routable(peer))
+            .collect();
+    }
+}
+
+fn canonical(addr: &SocketAddr) -> SocketAddr {
+    match addr.ip() {
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => SocketAddr::new(IpAddr::V4(v4), addr.port()),
+            None => *addr,
+        },
+        IpAddr::V4(_) => *addr,
+    }
+}
+
+fn is_routable(addr: &SocketAddr) -> bool {
+    if addr.port() == 0 {
+        return false;
+    }
+    match addr.ip() {
+        IpAddr::V4(ip) => !(ip.is_unspecified() || ip.is_multicast() || ip.is_broadcast()),
+        IpAddr::V6(ip) => !(ip.is_unspecified() || ip.is_multicast()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn addr(s: &str) -> SocketAddr {
+        SocketAddr::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn canonicalize() {
+        let mut registry = PeerRegistry::new();
+        registry.extend(&[
+            addr("1.2.3.4:7075"),
+            addr("[::ffff:1.2.3.4]:7075"),
+            addr("[::ffff:5.6.7.8]:7075"),
+            addr("5.6.7.8:7076"),
+            addr("[2001:db8::1]:7075"),
+            addr("[2001:db8::1]:7075"),
+            // Ourselves, in the mapped form.
+            addr("[::ffff:10.0.0.1]:7075"),
+            // Unroutable.
+            addr("0.0.0.0:7075"),
+            addr("[::]:7075"),
+            addr("1.2.3.4:0"),
+            addr("224.0.0.1:7075"),
+            addr("255.255.255.255:7075"),
+        ]);
+        registry.canonicalize(&addr("10.0.0.1:7075"));
+
+        let expected: HashSet<SocketAddr> = [
+            addr("1.2.3.4:7075"),
+            addr("5.6.7.8:7075"),
+            addr("5.6.7.8:7076"),
+            addr("[2001:db8::1]:7075"),
+        ]
+        .iter()
+        .cloned()
+        .collect();
+        assert_eq!(registry.peers(), &expected);
+    }
+}
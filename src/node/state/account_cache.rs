@@ -0,0 +1,96 @@
+use crate::node::state::{AccountReceipt, ArcState};
+use crate::Public;
+use std::collections::HashMap;
+
+/// Caches the frontier, balance and representative of accounts, as an [AccountReceipt], in front
+/// of a state.
+///
+/// Use [AccountCache::warm_up] at startup with the wallet's accounts so the first queries don't
+/// wait on the state. Cached accounts aren't refreshed, so call [AccountCache::invalidate] when
+/// one of their blocks is added.
+pub struct AccountCache {
+    state: ArcState,
+    receipts: HashMap<Public, AccountReceipt>,
+    misses: u64,
+}
+
+impl AccountCache {
+    pub fn new(state: ArcState) -> Self {
+        Self {
+            state,
+            receipts: HashMap::new(),
+            misses: 0,
+        }
+    }
+
+    /// Load `accounts` from the state, replacing anything cached for them.
+    pub async fn warm_up(&mut self, accounts: &[Public]) -> anyhow::Result<()> {
+        let mut state = self.state.lock().await;
+        for account in accounts {
+            let receipt = state.account_receipt(account).await?;
+            self.receipts.insert(account.to_owned(), receipt);
+        }
+        Ok(())
+    }
+
+    pub async fn receipt(&mut self, account: &Public) -> anyhow::Result<AccountReceipt> {
+        if let Some(receipt) = self.receipts.get(account) {
+            return Ok(receipt.to_owned());
+        }
+        self.misses += 1;
+        let receipt = self.state.lock().await.account_receipt(account).await?;
+        self.receipts.insert(account.to_owned(), receipt.to_owned());
+        Ok(receipt)
+    }
+
+    pub fn invalidate(&mut self, account: &Public) {
+        self.receipts.remove(account);
+    }
+
+    /// How many queries had to go to the state, not counting [AccountCache::warm_up].
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::state::test_helpers::{account, genesis_state, send, CountingState};
+    use crate::Network;
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    #[tokio::test]
+    async fn warm_up() {
+        let (memory, genesis) = genesis_state().await;
+        let genesis_account = genesis.account().to_owned();
+        let unopened = account(0);
+        let (counting, calls) = CountingState::new(memory);
+        let state: ArcState = Arc::new(Mutex::new(counting));
+
+        let mut cache = AccountCache::new(state.clone());
+        cache
+            .warm_up(&[genesis_account.to_owned(), unopened.to_owned()])
+            .await
+            .unwrap();
+        assert!(calls.load(Ordering::SeqCst) > 0);
+
+        // Changes to the state aren't seen since nothing is loaded from it again.
+        let send = send(&genesis, &unopened, 100);
+        state.lock().await.add_block(&send).await.unwrap();
+        let added = calls.load(Ordering::SeqCst);
+
+        let receipt = cache.receipt(&genesis_account).await.unwrap();
+        assert_eq!(receipt.frontier.as_ref(), genesis.hash().ok());
+        assert_eq!(receipt.balance, Network::Live.genesis_balance());
+        assert_eq!(cache.receipt(&unopened).await.unwrap().frontier, None);
+        assert_eq!(calls.load(Ordering::SeqCst), added);
+
+        cache.invalidate(&genesis_account);
+        let receipt = cache.receipt(&genesis_account).await.unwrap();
+        assert_eq!(receipt.frontier.as_ref(), send.hash().ok());
+        assert!(calls.load(Ordering::SeqCst) > added);
+    }
+}
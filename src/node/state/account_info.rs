@@ -0,0 +1,84 @@
+use crate::blocks::BlockHash;
+use crate::{Public, Raw};
+
+/// What the node's `account_info` RPC returns for an opened account, from
+/// [super::State::account_info].
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccountInfo {
+    pub frontier: BlockHash,
+
+    /// `None` when the open block was never added, e.g. when importing a pruned ledger.
+    pub open_block: Option<BlockHash>,
+
+    pub representative: Public,
+    pub balance: Raw,
+    pub block_count: u64,
+
+    /// The amount of blocks up to and including the frontier anchor, or 0 without one or when its
+    /// height isn't known, see [super::State::block_height].
+    pub confirmation_height: u64,
+
+    /// The frontier anchor, i.e. the latest confirmed block.
+    pub confirmation_height_frontier: Option<BlockHash>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::network::Network;
+    use crate::node::state::test_helpers::{account, add_genesis, genesis_state, send};
+    use crate::node::state::{MemoryState, State};
+
+    #[tokio::test]
+    async fn matches_individual_queries() {
+        let (mut state, genesis) = genesis_state().await;
+        let genesis_account = genesis.account().to_owned();
+        let genesis_hash = genesis.hash().unwrap().to_owned();
+        state
+            .set_frontier_anchor(&genesis_account, &genesis_hash)
+            .await
+            .unwrap();
+
+        let send = send(&genesis, &genesis_account, 100);
+        state.add_block(&send).await.unwrap();
+
+        let info = state.account_info(&genesis_account).await.unwrap().unwrap();
+        assert_eq!(
+            Some(info.frontier.to_owned()),
+            state
+                .get_latest_block_hash_for_account(&genesis_account)
+                .await
+                .unwrap()
+        );
+        assert_eq!(info.open_block, Some(genesis_hash.to_owned()));
+        assert_eq!(&info.representative, send.representative());
+        assert_eq!(
+            Some(info.balance.to_owned()),
+            state.account_balance(&genesis_account).await.unwrap()
+        );
+        assert_eq!(
+            info.block_count,
+            state.account_block_count(&genesis_account).await.unwrap()
+        );
+        assert_eq!(info.confirmation_height, 1);
+        assert_eq!(
+            info.confirmation_height_frontier,
+            state.frontier_anchor(&genesis_account).await.unwrap()
+        );
+
+        assert_eq!(state.account_info(&account(0)).await.unwrap(), None);
+
+        // The open block and heights are kept when blocks are pruned.
+        let mut pruned = MemoryState::with_capacity(Network::Live, 1);
+        add_genesis(&mut pruned).await;
+        pruned
+            .set_frontier_anchor(&genesis_account, &genesis_hash)
+            .await
+            .unwrap();
+        pruned.add_block(&send).await.unwrap();
+        assert_eq!(pruned.get_block_by_hash(&genesis_hash).await.unwrap(), None);
+        assert_eq!(
+            pruned.account_info(&genesis_account).await.unwrap(),
+            Some(info)
+        );
+    }
+}
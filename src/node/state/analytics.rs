@@ -0,0 +1,241 @@
+use crate::node::state::State;
+use crate::{Public, Raw};
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use std::collections::HashSet;
+
+/// Statistics derived from a [State] through its other methods, e.g. for explorers.
+///
+/// These are implemented for every state, so implementations of [State] don't carry them.
+#[async_trait]
+pub trait StateAnalytics: State {
+    /// Whether any account with a nonzero balance delegates to `representative`.
+    ///
+    /// There's no weight index yet, so this walks every account's frontier.
+    async fn is_active_representative(&mut self, representative: &Public) -> anyhow::Result<bool> {
+        for account in self.accounts_ordered().await? {
+            let frontier = match self.get_latest_block_hash_for_account(&account).await? {
+                Some(frontier) => frontier,
+                None => continue,
+            };
+            let block = match self.get_block_by_hash(&frontier).await? {
+                Some(block) => block,
+                None => continue,
+            };
+            if block.representative() == representative && block.balance().to_u128() > 0 {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// The fraction of the total supply held by the `n` largest accounts, from 0.0 to 1.0.
+    async fn top_n_supply_share(&mut self, n: usize) -> anyhow::Result<f64> {
+        let supply = self.total_supply().await?.to_u128();
+        if supply == 0 {
+            return Ok(0.0);
+        }
+        let held = self
+            .accounts_with_min_balance(Raw::zero())
+            .await?
+            .iter()
+            .take(n)
+            .fold(0u128, |acc, (_, balance)| {
+                acc.saturating_add(balance.to_u128())
+            });
+        Ok(held as f64 / supply as f64)
+    }
+
+    /// The representative `target` delegates to as of its frontier, e.g. to change another
+    /// account's representative to match it.
+    ///
+    /// Fails when `target` has no blocks, or when the representative's account isn't opened since
+    /// it couldn't be voting then.
+    async fn representative_to_match(&mut self, target: &Public) -> anyhow::Result<Public> {
+        let frontier = self
+            .get_latest_block_hash_for_account(target)
+            .await?
+            .with_context(|| format!("Account {:?} has no blocks", target))?;
+        let block = self
+            .get_block_by_hash(&frontier)
+            .await?
+            .with_context(|| format!("Frontier {} of {:?} is unknown", frontier, target))?;
+        let representative = block.representative().to_owned();
+        if self
+            .get_latest_block_hash_for_account(&representative)
+            .await?
+            .is_none()
+        {
+            return Err(anyhow!(
+                "Representative {:?} of {:?} isn't an opened account",
+                representative,
+                target
+            ));
+        }
+        Ok(representative)
+    }
+
+    /// The amount of distinct representatives delegated to by accounts with a nonzero balance,
+    /// e.g. as a decentralization metric.
+    ///
+    /// Like [StateAnalytics::is_active_representative], this walks every account's frontier.
+    async fn representative_count(&mut self) -> anyhow::Result<u64> {
+        let mut representatives = HashSet::new();
+        for account in self.accounts_ordered().await? {
+            let frontier = match self.get_latest_block_hash_for_account(&account).await? {
+                Some(frontier) => frontier,
+                None => continue,
+            };
+            let block = match self.get_block_by_hash(&frontier).await? {
+                Some(block) => block,
+                None => continue,
+            };
+            if block.balance().to_u128() > 0 {
+                representatives.insert(block.representative().to_owned());
+            }
+        }
+        Ok(representatives.len() as u64)
+    }
+
+    /// A simple proxy for how established `account` is, e.g. to rank accounts for an airdrop.
+    ///
+    /// The block count is scaled up by the order of magnitude of the balance in Mnano, i.e.
+    /// `blocks * (1 + log10(1 + mnano))`, so activity counts for more than holdings. Unopened
+    /// accounts score 0.
+    async fn account_age_score(&mut self, account: &Public) -> anyhow::Result<f64> {
+        let blocks = self.account_block_count(account).await? as f64;
+        let balance = self
+            .account_balance(account)
+            .await?
+            .unwrap_or_else(Raw::zero);
+        let mnano = balance.to_u128() as f64 / 1e30;
+        Ok(blocks * (1.0 + (1.0 + mnano).log10()))
+    }
+}
+
+impl<S: State + ?Sized> StateAnalytics for S {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::{Block, BlockType, Link, Previous, ValidationState};
+    use crate::network::Network;
+    use crate::node::state::test_helpers::{account, add_genesis, genesis_state, open, send};
+    use crate::node::state::MemoryState;
+
+    #[tokio::test]
+    async fn is_active_representative() {
+        let (mut state, genesis) = genesis_state().await;
+        let representative = genesis.representative().to_owned();
+        assert!(state
+            .is_active_representative(&representative)
+            .await
+            .unwrap());
+
+        // Account 0 only delegates to account 1 without any balance.
+        state
+            .add_block(&open(&account(0), &account(1), 0, &genesis))
+            .await
+            .unwrap();
+        assert!(!state.is_active_representative(&account(1)).await.unwrap());
+        assert!(!state.is_active_representative(&account(0)).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn top_n_supply_share() {
+        let (mut state, genesis) = genesis_state().await;
+        let supply = state.total_supply().await.unwrap().to_u128();
+
+        // Genesis keeps an eighth, accounts 0 and 1 open with a half and a quarter, and the last
+        // eighth is sent to account 2 which stays unopened.
+        let mut previous = genesis;
+        let mut remaining = supply;
+        for (idx, divisor) in [2u128, 4, 8].iter().enumerate() {
+            let account = account(idx as u32);
+            remaining -= supply / divisor;
+            let send = send(&previous, &account, remaining);
+            state.add_block(&send).await.unwrap();
+            if idx != 2 {
+                let open = open(&account, &account, supply / divisor, &send);
+                state.add_block(&open).await.unwrap();
+            }
+            previous = send;
+        }
+
+        let share = |expected: f64, actual: f64| (expected - actual).abs() < 1e-9;
+        assert!(share(0.0, state.top_n_supply_share(0).await.unwrap()));
+        assert!(share(0.5, state.top_n_supply_share(1).await.unwrap()));
+        assert!(share(0.75, state.top_n_supply_share(2).await.unwrap()));
+        assert!(share(0.875, state.top_n_supply_share(3).await.unwrap()));
+        assert!(share(0.875, state.top_n_supply_share(10).await.unwrap()));
+    }
+
+    #[tokio::test]
+    async fn representative_to_match() {
+        let (mut state, genesis) = genesis_state().await;
+        let genesis_account = genesis.account().to_owned();
+
+        // Account 0 delegates to genesis, account 1 to the unopened account 2.
+        let unopened = account(2);
+        for (index, representative) in &[(0, &genesis_account), (1, &unopened)] {
+            let open = open(&account(*index), representative, 100, &genesis);
+            state.add_block(&open).await.unwrap();
+        }
+
+        assert_eq!(
+            state.representative_to_match(&account(0)).await.unwrap(),
+            genesis_account
+        );
+        assert!(state.representative_to_match(&account(1)).await.is_err());
+        assert!(state.representative_to_match(&unopened).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn representative_count() {
+        let mut state = MemoryState::new(Network::Live);
+        assert_eq!(state.representative_count().await.unwrap(), 0);
+        let genesis = add_genesis(&mut state).await;
+        assert_eq!(state.representative_count().await.unwrap(), 1);
+
+        // Accounts 0 and 1 delegate to account 2, account 3 delegates to account 4 without any
+        // balance.
+        for (index, rep, balance) in &[(0, 2, 100), (1, 2, 50), (3, 4, 0)] {
+            let open = open(&account(*index), &account(*rep), *balance, &genesis);
+            state.add_block(&open).await.unwrap();
+        }
+        assert_eq!(state.representative_count().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn account_age_score() {
+        let (mut state, genesis) = genesis_state().await;
+
+        // The old account has received twice and holds 1000 Mnano.
+        let old_account = account(0);
+        let old = open(&old_account, &old_account, 400 * 10u128.pow(30), &genesis);
+        state.add_block(&old).await.unwrap();
+        let mut previous = old.hash().unwrap().to_owned();
+        for balance in &[700u128, 1000] {
+            let receive = Block::new(
+                BlockType::State,
+                old_account.to_owned(),
+                Previous::Block(previous),
+                old_account.to_owned(),
+                Raw::from(balance * 10u128.pow(30)),
+                Link::Source(genesis.hash().unwrap().to_owned()),
+                ValidationState::Valid,
+            );
+            state.add_block(&receive).await.unwrap();
+            previous = receive.hash().unwrap().to_owned();
+        }
+
+        let new = open(&account(1), &account(1), 1, &genesis);
+        state.add_block(&new).await.unwrap();
+
+        let old_score = state.account_age_score(&old_account).await.unwrap();
+        let new_score = state.account_age_score(new.account()).await.unwrap();
+        assert!(old_score > new_score);
+        assert!(new_score > 0.0);
+        assert_eq!(state.account_age_score(&account(2)).await.unwrap(), 0.0);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::blocks::{Block, BlockHash, Previous};
+use crate::network::Network;
+use crate::Raw;
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+
+/// An account's chain from a trusted checkpoint to its frontier, from
+/// [super::State::balance_proof], so a light client can check the balance without the ledger.
+///
+/// Only the hashes, the links between the blocks and their signatures are checked, so the
+/// verifier still has to trust that the frontier is the latest block, e.g. from a confirmation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceProof {
+    /// Starting with the checkpoint block and ending with the frontier.
+    #[serde(with = "crate::blocks::record::list")]
+    pub blocks: Vec<Block>,
+}
+
+impl BalanceProof {
+    /// The balance at the frontier, if the chain starts at `checkpoint` and every block follows
+    /// the one before it in the same account and is signed by it, or by the epoch signer of
+    /// `network` for epoch blocks.
+    ///
+    /// Each hash is calculated again from the block's fields rather than trusting the cached one.
+    pub fn verify(&self, network: &Network, checkpoint: &BlockHash) -> anyhow::Result<Raw> {
+        self.blocks.first().context("Empty balance proof")?;
+
+        let mut previous: Option<(&Block, BlockHash)> = None;
+        for block in &self.blocks {
+            let hash = block.recompute_hash()?;
+            if block.hash()? != &hash {
+                return Err(anyhow!(
+                    "Block {} has the hash of different fields",
+                    block.hash()?
+                ));
+            }
+            match &previous {
+                None if &hash != checkpoint => {
+                    return Err(anyhow!(
+                        "Balance proof starts at {} instead of {}",
+                        hash,
+                        checkpoint
+                    ));
+                }
+                None => {}
+                Some((previous, previous_hash)) => {
+                    if block.account() != previous.account() {
+                        return Err(anyhow!("Block {} belongs to a different account", hash));
+                    }
+                    if block.previous() != &Previous::Block(previous_hash.to_owned()) {
+                        return Err(anyhow!("Block {} doesn't follow {}", hash, previous_hash));
+                    }
+                }
+            }
+            block
+                .verify_signature(&block.signing_account(network)?)
+                .with_context(|| format!("Verify block {}", hash))?;
+            previous = Some((block, hash));
+        }
+        let (frontier, _) = previous.expect("Checked for blocks");
+        Ok(frontier.balance().to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BalanceProof;
+    use crate::network::Network;
+    use crate::node::state::test_helpers::{genesis_state, open, send};
+    use crate::node::state::State;
+    use crate::{Raw, Seed};
+
+    #[tokio::test]
+    async fn prove_and_verify() {
+        let network = Network::Live;
+        let (mut state, genesis) = genesis_state().await;
+
+        let private = Seed::zero().derive(0);
+        let account = private.to_public().unwrap();
+        let mut chain = vec![open(&account, &account, 1000, &genesis)];
+        for balance in &[600, 250] {
+            chain.push(send(chain.last().unwrap(), genesis.account(), *balance));
+        }
+        for block in &mut chain {
+            block.sign(private.to_owned()).unwrap();
+            state.add_block(block).await.unwrap();
+        }
+
+        let checkpoint = chain[1].hash().unwrap().to_owned();
+        let proof = state.balance_proof(&account, &checkpoint).await.unwrap();
+        assert_eq!(proof.blocks, chain[1..]);
+        assert_eq!(proof.verify(&network, &checkpoint).unwrap(), Raw::from(250));
+
+        // The proof doesn't start at an untrusted checkpoint.
+        assert!(proof.verify(&network, chain[0].hash().unwrap()).is_err());
+
+        let mut forged = proof.to_owned();
+        forged.blocks.remove(0);
+        assert!(forged.verify(&network, &checkpoint).is_err());
+
+        // Sent to a light client and back.
+        let json = serde_json::to_string(&proof).unwrap();
+        let decoded: BalanceProof = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, proof);
+        assert_eq!(
+            decoded.verify(&network, &checkpoint).unwrap(),
+            Raw::from(250)
+        );
+
+        // A balance changed in transit no longer matches the signed hash.
+        let mut tampered: serde_json::Value = serde_json::from_str(&json).unwrap();
+        tampered["blocks"][1]["balance"] = "9999".into();
+        let tampered: BalanceProof = serde_json::from_value(tampered).unwrap();
+        assert!(tampered.verify(&network, &checkpoint).is_err());
+
+        let genesis_hash = genesis.hash().unwrap();
+        assert!(state.balance_proof(&account, genesis_hash).await.is_err());
+    }
+}
@@ -0,0 +1,311 @@
+use crate::blocks::{Block, BlockHash};
+use crate::node::cookie::Cookie;
+use crate::node::state::{AccountReceipt, State};
+use crate::{Public, Raw};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::net::SocketAddr;
+
+/// A mutating operation on a [State].
+///
+/// Replaying the events of a state, in order, into a fresh state reproduces it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum StateEvent {
+    AddBlock {
+        #[serde(with = "crate::blocks::record")]
+        block: Block,
+    },
+    AddUnchecked {
+        #[serde(with = "crate::blocks::record")]
+        block: Block,
+        #[serde(default)]
+        check_work: bool,
+    },
+    SetFrontierAnchor {
+        account: Public,
+        hash: BlockHash,
+    },
+    AddVote {
+        hash: BlockHash,
+        representative: Public,
+    },
+    SetCookie {
+        socket_addr: SocketAddr,
+        cookie: Cookie,
+    },
+    RemoveCookie {
+        socket_addr: SocketAddr,
+    },
+    AddPeers {
+        addresses: Vec<SocketAddr>,
+    },
+}
+
+impl StateEvent {
+    /// Apply this event to `state`, as if the operation was called on it directly.
+    pub async fn apply<S: State + ?Sized>(&self, state: &mut S) -> anyhow::Result<()> {
+        match self {
+            StateEvent::AddBlock { block } => state.add_block(block).await,
+            StateEvent::AddUnchecked { block, check_work } => {
+                state.add_unchecked(block, *check_work).await
+            }
+            StateEvent::SetFrontierAnchor { account, hash } => {
+                state.set_frontier_anchor(account, hash).await
+            }
+            StateEvent::AddVote {
+                hash,
+                representative,
+            } => state.add_vote(hash, representative).await,
+            StateEvent::SetCookie {
+                socket_addr,
+                cookie,
+            } => state.set_cookie(*socket_addr, cookie.to_owned()).await,
+            StateEvent::RemoveCookie { socket_addr } => state.remove_cookie(socket_addr).await,
+            StateEvent::AddPeers { addresses } => state.add_peers(addresses).await,
+        }
+    }
+}
+
+/// Replay `events` in order into `state`.
+pub async fn replay<S: State + ?Sized>(state: &mut S, events: &[StateEvent]) -> anyhow::Result<()> {
+    for event in events {
+        event.apply(state).await?;
+    }
+    Ok(())
+}
+
+/// Where [EventLog] appends events to.
+pub trait StateEventSink: Debug + Send + Sync + 'static {
+    fn append(&mut self, event: StateEvent) -> anyhow::Result<()>;
+}
+
+impl StateEventSink for Vec<StateEvent> {
+    fn append(&mut self, event: StateEvent) -> anyhow::Result<()> {
+        self.push(event);
+        Ok(())
+    }
+}
+
+/// Wraps a [State], appending a [StateEvent] to `sink` for every successful mutating operation.
+#[derive(Debug)]
+pub struct EventLog<S, K> {
+    state: S,
+    sink: K,
+}
+
+impl<S: State, K: StateEventSink> EventLog<S, K> {
+    pub fn new(state: S, sink: K) -> Self {
+        Self { state, sink }
+    }
+
+    pub fn state(&self) -> &S {
+        &self.state
+    }
+
+    pub fn sink(&self) -> &K {
+        &self.sink
+    }
+
+    pub fn into_parts(self) -> (S, K) {
+        (self.state, self.sink)
+    }
+}
+
+#[async_trait]
+impl<S: State, K: StateEventSink> State for EventLog<S, K> {
+    async fn add_block(&mut self, block: &Block) -> anyhow::Result<()> {
+        self.state.add_block(block).await?;
+        self.sink.append(StateEvent::AddBlock {
+            block: block.to_owned(),
+        })
+    }
+
+    async fn add_unchecked(&mut self, block: &Block, check_work: bool) -> anyhow::Result<()> {
+        self.state.add_unchecked(block, check_work).await?;
+        self.sink.append(StateEvent::AddUnchecked {
+            block: block.to_owned(),
+            check_work,
+        })
+    }
+
+    async fn get_block_by_hash(&self, hash: &BlockHash) -> anyhow::Result<Option<Block>> {
+        self.state.get_block_by_hash(hash).await
+    }
+
+    async fn get_latest_block_hash_for_account(
+        &self,
+        account: &Public,
+    ) -> anyhow::Result<Option<BlockHash>> {
+        self.state.get_latest_block_hash_for_account(account).await
+    }
+
+    async fn account_balance(&self, account: &Public) -> anyhow::Result<Option<Raw>> {
+        self.state.account_balance(account).await
+    }
+
+    async fn account_block_count(&self, account: &Public) -> anyhow::Result<u64> {
+        self.state.account_block_count(account).await
+    }
+
+    async fn account_open_block(&self, account: &Public) -> anyhow::Result<Option<BlockHash>> {
+        self.state.account_open_block(account).await
+    }
+
+    async fn block_height(&self, hash: &BlockHash) -> anyhow::Result<Option<u64>> {
+        self.state.block_height(hash).await
+    }
+
+    async fn account_epoch(&self, account: &Public) -> anyhow::Result<u8> {
+        self.state.account_epoch(account).await
+    }
+
+    async fn accounts_with_min_balance(&mut self, min: Raw) -> anyhow::Result<Vec<(Public, Raw)>> {
+        self.state.accounts_with_min_balance(min).await
+    }
+
+    async fn accounts_ordered(&mut self) -> anyhow::Result<Vec<Public>> {
+        self.state.accounts_ordered().await
+    }
+
+    async fn total_supply(&mut self) -> anyhow::Result<Raw> {
+        self.state.total_supply().await
+    }
+
+    async fn total_block_count(&mut self) -> anyhow::Result<u64> {
+        self.state.total_block_count().await
+    }
+
+    async fn estimated_size_bytes(&mut self) -> anyhow::Result<u64> {
+        self.state.estimated_size_bytes().await
+    }
+
+    async fn account_receipt(&mut self, account: &Public) -> anyhow::Result<AccountReceipt> {
+        self.state.account_receipt(account).await
+    }
+
+    async fn account_for_block_hash(
+        &mut self,
+        block_hash: &BlockHash,
+    ) -> anyhow::Result<Option<Public>> {
+        self.state.account_for_block_hash(block_hash).await
+    }
+
+    async fn set_frontier_anchor(
+        &mut self,
+        account: &Public,
+        hash: &BlockHash,
+    ) -> anyhow::Result<()> {
+        self.state.set_frontier_anchor(account, hash).await?;
+        self.sink.append(StateEvent::SetFrontierAnchor {
+            account: account.to_owned(),
+            hash: hash.to_owned(),
+        })
+    }
+
+    async fn frontier_anchor(&self, account: &Public) -> anyhow::Result<Option<BlockHash>> {
+        self.state.frontier_anchor(account).await
+    }
+
+    async fn add_vote(&mut self, hash: &BlockHash, representative: &Public) -> anyhow::Result<()> {
+        self.state.add_vote(hash, representative).await?;
+        self.sink.append(StateEvent::AddVote {
+            hash: hash.to_owned(),
+            representative: representative.to_owned(),
+        })
+    }
+
+    async fn set_cookie(&mut self, socket_addr: SocketAddr, cookie: Cookie) -> anyhow::Result<()> {
+        self.state
+            .set_cookie(socket_addr, cookie.to_owned())
+            .await?;
+        self.sink.append(StateEvent::SetCookie {
+            socket_addr,
+            cookie,
+        })
+    }
+
+    async fn cookie_for_socket_addr(
+        &self,
+        socket_addr: &SocketAddr,
+    ) -> anyhow::Result<Option<Cookie>> {
+        self.state.cookie_for_socket_addr(socket_addr).await
+    }
+
+    async fn remove_cookie(&mut self, socket_addr: &SocketAddr) -> anyhow::Result<()> {
+        self.state.remove_cookie(socket_addr).await?;
+        self.sink.append(StateEvent::RemoveCookie {
+            socket_addr: socket_addr.to_owned(),
+        })
+    }
+
+    async fn add_peers(&mut self, addresses: &[SocketAddr]) -> anyhow::Result<()> {
+        self.state.add_peers(addresses).await?;
+        self.sink.append(StateEvent::AddPeers {
+            addresses: addresses.to_vec(),
+        })
+    }
+
+    async fn peers(&self) -> anyhow::Result<HashSet<SocketAddr>> {
+        self.state.peers().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use crate::node::state::test_helpers::send;
+    use crate::node::state::MemoryState;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn record_and_replay() {
+        let network = Network::Live;
+        let genesis = network.genesis_block().unwrap();
+        let account = genesis.account().to_owned();
+        let send = send(&genesis, &account, 1000);
+        let socket_addr = SocketAddr::from_str("[::1]:7075").unwrap();
+        let cookie = Cookie::random();
+
+        let mut log = EventLog::new(MemoryState::new(network), vec![]);
+        log.add_block(&genesis).await.unwrap();
+        log.add_block(&send).await.unwrap();
+        log.set_cookie(socket_addr, cookie.to_owned())
+            .await
+            .unwrap();
+        log.add_peers(&[socket_addr]).await.unwrap();
+        log.add_vote(send.hash().unwrap(), &account).await.unwrap();
+        let (mut original, events) = log.into_parts();
+        assert_eq!(events.len(), 5);
+
+        let json = serde_json::to_string(&events).unwrap();
+        let events: Vec<StateEvent> = serde_json::from_str(&json).unwrap();
+
+        let mut replayed = MemoryState::new(network);
+        replay(&mut replayed, &events).await.unwrap();
+
+        for block in &[&genesis, &send] {
+            let hash = block.hash().unwrap();
+            assert_eq!(
+                replayed.get_block_by_hash(hash).await.unwrap().as_ref(),
+                Some(*block)
+            );
+            assert_eq!(
+                replayed.get_block_by_hash(hash).await.unwrap(),
+                original.get_block_by_hash(hash).await.unwrap()
+            );
+        }
+        assert_eq!(
+            replayed.account_receipt(&account).await.unwrap(),
+            original.account_receipt(&account).await.unwrap()
+        );
+        assert_eq!(
+            replayed.cookie_for_socket_addr(&socket_addr).await.unwrap(),
+            Some(cookie)
+        );
+        assert_eq!(replayed.peers().await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,375 @@
+use crate::blocks::{Block, BlockHolder, Previous};
+use crate::node::state::State;
+use crate::{Address, Public, Raw};
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+use std::convert::TryFrom;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// An account in a ledger dump, with its blocks in chain order.
+#[derive(Debug, Deserialize)]
+pub struct LedgerAccount {
+    pub account: Address,
+    pub blocks: Vec<BlockHolder>,
+}
+
+/// Reads the accounts of a JSON ledger dump, e.g. `[{"account": "nano_...", "blocks": [...]}]`,
+/// one at a time.
+///
+/// Only a single account is held in memory at once so multi-gigabyte dumps can be imported. The
+/// reader is read byte by byte, so wrap files with a [std::io::BufReader].
+pub struct LedgerReader<R> {
+    reader: R,
+    started: bool,
+    finished: bool,
+}
+
+impl<R: Read> LedgerReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            started: false,
+            finished: false,
+        }
+    }
+
+    fn next_byte(&mut self) -> anyhow::Result<Option<u8>> {
+        let mut byte = [0u8];
+        loop {
+            if self.reader.read(&mut byte)? == 0 {
+                return Ok(None);
+            }
+            if !byte[0].is_ascii_whitespace() {
+                return Ok(Some(byte[0]));
+            }
+        }
+    }
+
+    fn next_account(&mut self) -> anyhow::Result<Option<LedgerAccount>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let expected = if self.started { b',' } else { b'[' };
+        match self.next_byte()? {
+            Some(b']') if self.started => {
+                self.finished = true;
+                return Ok(None);
+            }
+            Some(b) if b == expected => {}
+            other => {
+                return Err(anyhow!(
+                    "Expected {:?} in ledger dump, got {:?}",
+                    expected as char,
+                    other.map(char::from)
+                ))
+            }
+        }
+
+        let first = self.next_byte()?.context("Ledger dump ended early")?;
+        if first == b']' && !self.started {
+            self.finished = true;
+            return Ok(None);
+        }
+        self.started = true;
+
+        // The first byte of the account was already consumed looking for the separator.
+        let first = [first];
+        let mut deserializer =
+            serde_json::Deserializer::from_reader(first.as_ref().chain(&mut self.reader));
+        let account =
+            LedgerAccount::deserialize(&mut deserializer).context("Deserialize ledger account")?;
+        Ok(Some(account))
+    }
+}
+
+impl<R: Read> Iterator for LedgerReader<R> {
+    type Item = anyhow::Result<LedgerAccount>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let account = self.next_account();
+        if account.is_err() {
+            self.finished = true;
+        }
+        account.transpose()
+    }
+}
+
+/// How much of a ledger dump has been imported so far.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportProgress {
+    pub accounts: u64,
+    pub blocks: u64,
+}
+
+/// Import a JSON ledger dump into `state`, returning the amount of blocks imported.
+///
+/// Each block has to belong to its account and be signed by it. Work isn't checked. The previous
+/// block of each block has to be imported already, i.e. earlier in the dump or in `state`.
+pub async fn import_ledger<S: State + ?Sized, R: Read>(
+    state: &mut S,
+    reader: R,
+) -> anyhow::Result<u64> {
+    let progress = import_ledger_with(state, reader, &AtomicBool::new(false), |_| {}).await?;
+    Ok(progress.blocks)
+}
+
+/// Like [import_ledger], calling `on_progress` after each account and stopping once `cancel` is
+/// set, e.g. from a UI.
+///
+/// Cancelling is only checked between accounts, so an account is either fully imported or not at
+/// all. Returns what was imported before stopping.
+pub async fn import_ledger_with<S, R, F>(
+    state: &mut S,
+    reader: R,
+    cancel: &AtomicBool,
+    mut on_progress: F,
+) -> anyhow::Result<ImportProgress>
+where
+    S: State + ?Sized,
+    R: Read,
+    F: FnMut(&ImportProgress),
+{
+    let mut progress = ImportProgress::default();
+    for entry in LedgerReader::new(reader) {
+        if cancel.load(Ordering::SeqCst) {
+            break;
+        }
+        let entry = entry?;
+        let account = Public::try_from(&entry.account)
+            .with_context(|| format!("Invalid account {} in ledger dump", entry.account))?;
+
+        // Every block of the account is verified before any is added, so a bad block doesn't
+        // leave the account half imported.
+        let mut blocks: Vec<Block> = Vec::with_capacity(entry.blocks.len());
+        for holder in entry.blocks {
+            let mut state_block = match holder {
+                BlockHolder::State(state_block) => state_block,
+                other => return Err(anyhow!("Unsupported block in ledger dump: {:?}", other)),
+            };
+            if state_block.account != account {
+                return Err(anyhow!(
+                    "Block {} belongs to {} instead of {}",
+                    state_block.hash,
+                    state_block.account.to_address(),
+                    entry.account
+                ));
+            }
+            state_block
+                .verify_self_signature()
+                .with_context(|| format!("Verify block {}", state_block.hash))?;
+
+            let previous_balance = match &state_block.previous {
+                Previous::Open => Raw::zero(),
+                Previous::Block(previous) => {
+                    let pending = blocks
+                        .iter()
+                        .rev()
+                        .find(|block| block.hash().ok() == Some(previous))
+                        .map(|block| block.balance().to_owned());
+                    match pending {
+                        Some(balance) => balance,
+                        None => state
+                            .get_block_by_hash(previous)
+                            .await?
+                            .map(|block| block.balance().to_owned())
+                            .with_context(|| format!("Previous block {} not imported", previous))?,
+                    }
+                }
+            };
+            let is_send = state_block.balance < previous_balance;
+            let amount = if is_send {
+                previous_balance.checked_sub(&state_block.balance)
+            } else {
+                state_block.balance.checked_sub(&previous_balance)
+            }
+            .unwrap_or_else(Raw::zero);
+            state_block.set_link_type(is_send, amount)?;
+            blocks.push(Block::from_state_block(&state_block));
+        }
+
+        for block in &blocks {
+            state.add_block(block).await?;
+            progress.blocks += 1;
+        }
+        progress.accounts += 1;
+        on_progress(&progress);
+    }
+    Ok(progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::{BlockHash, Link, StateBlock};
+    use crate::encoding::to_hex;
+    use crate::node::state::test_helpers::account;
+    use crate::node::state::MemoryState;
+    use crate::{Network, Seed};
+    use serde_json::{json, Value};
+    use std::cell::Cell;
+    use std::str::FromStr;
+
+    /// Counts how much of the dump has been read.
+    struct CountingReader<'a> {
+        data: &'a [u8],
+        read: &'a Cell<usize>,
+    }
+
+    impl Read for CountingReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = self.data.read(buf)?;
+            self.read.set(self.read.get() + len);
+            Ok(len)
+        }
+    }
+
+    fn signed(index: u32, previous: Previous, balance: u128, link: Link) -> StateBlock {
+        let private = Seed::zero().derive(index);
+        let account = private.to_public().unwrap();
+        let mut block = StateBlock::new(
+            account.to_owned(),
+            previous,
+            account,
+            Raw::from(balance),
+            link,
+        );
+        block.signature = Some(private.sign(block.hash.as_bytes()).unwrap());
+        block
+    }
+
+    fn to_json(block: &StateBlock) -> Value {
+        json!({
+            "type": "state",
+            "account": block.account.to_address().to_string(),
+            "previous": to_hex(&block.previous.to_bytes()),
+            "representative": block.representative.to_address().to_string(),
+            "balance": block.balance.to_string(),
+            "link": to_hex(block.link.as_bytes()),
+            "signature": block.signature.as_ref().unwrap().to_string(),
+        })
+    }
+
+    /// Account 0 opens with 1000 and sends 400 to account 1.
+    fn dump() -> (Value, Value) {
+        let source =
+            BlockHash::from_str("CE898C131AAEE25E05362F247760F8A3ACF34A9796A5AE0D9204E86B0637965E")
+                .unwrap();
+        let open_0 = signed(0, Previous::Open, 1000, Link::Source(source));
+        let send = signed(
+            0,
+            Previous::Block(open_0.hash.to_owned()),
+            600,
+            Link::DestinationAccount(account(1)),
+        );
+        let open_1 = signed(1, Previous::Open, 400, Link::Source(send.hash.to_owned()));
+
+        let account_0 = json!({
+            "account": open_0.account.to_address().to_string(),
+            "blocks": [to_json(&open_0), to_json(&send)],
+        });
+        let account_1 = json!({
+            "account": open_1.account.to_address().to_string(),
+            "blocks": [to_json(&open_1)],
+        });
+        (account_0, account_1)
+    }
+
+    #[tokio::test]
+    async fn import() {
+        let (account_0, account_1) = dump();
+        let dump = serde_json::to_string_pretty(&json!([account_0, account_1])).unwrap();
+
+        let mut state = MemoryState::new(Network::Live);
+        let count = import_ledger(&mut state, dump.as_bytes()).await.unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(
+            state.account_balance(&account(0)).await.unwrap(),
+            Some(Raw::from(600))
+        );
+        assert_eq!(
+            state.account_balance(&account(1)).await.unwrap(),
+            Some(Raw::from(400))
+        );
+
+        let mut state = MemoryState::new(Network::Live);
+        assert_eq!(
+            import_ledger(&mut state, " [ ] ".as_bytes()).await.unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_signature() {
+        let (mut account_0, account_1) = dump();
+        account_0["blocks"][1]["balance"] = json!("900");
+        let dump = serde_json::to_string(&json!([account_0, account_1])).unwrap();
+
+        let mut state = MemoryState::new(Network::Live);
+        assert!(import_ledger(&mut state, dump.as_bytes()).await.is_err());
+        // The open block before the bad one wasn't added either.
+        assert_eq!(state.account_balance(&account(0)).await.unwrap(), None);
+        assert_eq!(state.total_block_count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn rejects_bad_account() {
+        let (mut account_0, account_1) = dump();
+        account_0["account"] =
+            json!("nano_1tgkjkq9r96zd3pg8i3ni1dz6kcntsbsf8aw2xnkmywgpcy6zp7sbju7cydj");
+        let dump = serde_json::to_string(&json!([account_0, account_1])).unwrap();
+
+        let mut state = MemoryState::new(Network::Live);
+        assert!(import_ledger(&mut state, dump.as_bytes()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn cancel() {
+        let (account_0, account_1) = dump();
+        let dump = serde_json::to_string(&json!([account_0, account_1])).unwrap();
+
+        let mut state = MemoryState::new(Network::Live);
+        let cancel = AtomicBool::new(false);
+        let mut reported = vec![];
+        let progress = import_ledger_with(&mut state, dump.as_bytes(), &cancel, |progress| {
+            reported.push(progress.to_owned());
+            cancel.store(true, Ordering::SeqCst);
+        })
+        .await
+        .unwrap();
+
+        let expected = ImportProgress {
+            accounts: 1,
+            blocks: 2,
+        };
+        assert_eq!(progress, expected);
+        assert_eq!(reported, vec![expected]);
+        // The first account is complete and the second one wasn't started.
+        assert_eq!(
+            state.account_balance(&account(0)).await.unwrap(),
+            Some(Raw::from(600))
+        );
+        assert_eq!(state.account_block_count(&account(0)).await.unwrap(), 2);
+        assert_eq!(state.account_balance(&account(1)).await.unwrap(), None);
+    }
+
+    #[test]
+    fn reads_one_account_at_a_time() {
+        let (account_0, account_1) = dump();
+        let first = serde_json::to_string(&account_0).unwrap();
+        let dump = serde_json::to_string(&json!([account_0, account_1])).unwrap();
+
+        let read = Cell::new(0);
+        let mut reader = LedgerReader::new(CountingReader {
+            data: dump.as_bytes(),
+            read: &read,
+        });
+        reader.next().unwrap().unwrap();
+        // Only the opening bracket and the first account have been read.
+        assert_eq!(read.get(), 1 + first.len());
+        reader.next().unwrap().unwrap();
+        assert!(reader.next().is_none());
+        assert_eq!(read.get(), dump.len());
+    }
+}
@@ -1,12 +1,15 @@
-use crate::blocks::{Block, BlockHash};
+use crate::blocks::{epoch_version, Block, BlockHash, Previous};
 use crate::network::Network;
 use crate::node::cookie::Cookie;
-use crate::node::state::State;
-use crate::Public;
-use anyhow::Context;
+use crate::node::peer_registry::PeerRegistry;
+use crate::node::state::{verify_work, State};
+use crate::{Public, Raw};
+use anyhow::{anyhow, Context};
 use async_trait::async_trait;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::mem::size_of;
 use std::net::SocketAddr;
+use std::sync::Mutex;
 
 #[derive(Debug)]
 pub struct MemoryState {
@@ -15,8 +18,60 @@ pub struct MemoryState {
     blocks: HashMap<BlockHash, Block>,
     block_hash_to_account: HashMap<BlockHash, Public>,
     latest_block_hash: HashMap<Public, BlockHash>,
+    balances: HashMap<Public, Raw>,
+    epochs: HashMap<Public, u8>,
+    open_blocks: HashMap<Public, BlockHash>,
+    account_block_counts: HashMap<Public, u64>,
+
+    /// Heights of blocks in their account's chain, kept after the blocks are pruned.
+    heights: HashMap<BlockHash, u64>,
+
+    frontier_anchors: HashMap<Public, BlockHash>,
     votes: HashMap<BlockHash, HashSet<Public>>,
-    peers: HashSet<SocketAddr>,
+
+    /// Every block added, including pruned ones.
+    block_count: u64,
+
+    /// Blocks waiting for their previous block to arrive, keyed by the previous block hash, and
+    /// whether to check their work then.
+    unchecked: HashMap<BlockHash, Vec<(Block, bool)>>,
+
+    peers: PeerRegistry,
+
+    /// The maximum amount of blocks to keep, see [MemoryState::with_capacity].
+    capacity: Option<usize>,
+
+    /// When blocks were last accessed. Only used with a capacity.
+    ///
+    /// This is a mutex because blocks are also accessed through `&self`.
+    recent: Mutex<Recent>,
+}
+
+/// Block hashes ordered by when they were last accessed, using an increasing generation number so
+/// touching a block doesn't need a scan.
+#[derive(Debug, Default)]
+struct Recent {
+    generation: u64,
+    generations: HashMap<BlockHash, u64>,
+    by_generation: BTreeMap<u64, BlockHash>,
+}
+
+impl Recent {
+    fn touch(&mut self, hash: &BlockHash) {
+        self.generation += 1;
+        if let Some(old) = self.generations.insert(hash.to_owned(), self.generation) {
+            self.by_generation.remove(&old);
+        }
+        self.by_generation.insert(self.generation, hash.to_owned());
+    }
+
+    /// Forget and return the least recently accessed block hash.
+    fn pop_oldest(&mut self) -> Option<BlockHash> {
+        let oldest = *self.by_generation.keys().next()?;
+        let hash = self.by_generation.remove(&oldest)?;
+        self.generations.remove(&hash);
+        Some(hash)
+    }
 }
 
 impl MemoryState {
@@ -27,28 +82,154 @@ impl MemoryState {
             blocks: HashMap::new(),
             block_hash_to_account: HashMap::new(),
             latest_block_hash: HashMap::new(),
+            balances: HashMap::new(),
+            epochs: HashMap::new(),
+            open_blocks: HashMap::new(),
+            account_block_counts: HashMap::new(),
+            heights: HashMap::new(),
+            frontier_anchors: HashMap::new(),
             votes: HashMap::new(),
-            peers: HashSet::new(),
+            block_count: 0,
+            unchecked: HashMap::new(),
+            peers: PeerRegistry::new(),
+            capacity: None,
+            recent: Mutex::new(Recent::default()),
         }
     }
-}
 
-#[async_trait]
-impl State for MemoryState {
-    async fn add_block(&mut self, block: &Block) -> anyhow::Result<()> {
+    /// Only keep up to `capacity` blocks, evicting the least recently accessed ones, similar to a
+    /// pruning node. Balances and frontiers of accounts are kept.
+    pub fn with_capacity(network: Network, capacity: usize) -> Self {
+        let mut state = Self::new(network);
+        state.capacity = Some(capacity);
+        state
+    }
+
+    /// Mark a block as the most recently accessed.
+    fn touch(&self, hash: &BlockHash) {
+        if self.capacity.is_none() {
+            return;
+        }
+        self.recent.lock().unwrap().touch(hash);
+    }
+
+    fn insert_block(&mut self, block: &Block) -> anyhow::Result<()> {
+        let mut balance = block.balance().to_owned();
+        if block.previous() == &Previous::Open {
+            // The genesis open block has no send block as a source since it creates the supply.
+            if block.is_genesis(&self.network)? {
+                balance = self.network.genesis_balance();
+            } else {
+                block
+                    .source()
+                    .map_err(|_| anyhow!("Open block requires a source: {:?}", block))?;
+            }
+        }
+
+        self.balances.insert(block.account().to_owned(), balance);
+        let epoch = epoch_version(std::slice::from_ref(block));
+        if epoch > 0 {
+            self.epochs.insert(block.account().to_owned(), epoch);
+        }
         self.blocks.insert(
             block.hash().context("Add block")?.to_owned(),
             block.to_owned(),
         );
-        self.block_hash_to_account
+        let height = match block.previous() {
+            Previous::Open => {
+                self.open_blocks
+                    .insert(block.account().to_owned(), block.hash()?.to_owned());
+                Some(1)
+            }
+            Previous::Block(previous) => self.heights.get(previous).map(|height| height + 1),
+        };
+        if let Some(height) = height {
+            self.heights.insert(block.hash()?.to_owned(), height);
+        }
+        let existing = self
+            .block_hash_to_account
             .insert(block.hash()?.to_owned(), block.account().to_owned());
+        if existing.is_none() {
+            self.block_count += 1;
+            *self
+                .account_block_counts
+                .entry(block.account().to_owned())
+                .or_insert(0) += 1;
+        }
         self.latest_block_hash
             .insert(block.account().to_owned(), block.hash()?.to_owned());
+        self.touch(block.hash()?);
+        self.evict();
+        Ok(())
+    }
+
+    fn evict(&mut self) {
+        let capacity = match self.capacity {
+            Some(c) => c,
+            None => return,
+        };
+        let mut recent = self.recent.lock().unwrap();
+        while self.blocks.len() > capacity {
+            match recent.pop_oldest() {
+                Some(hash) => {
+                    self.blocks.remove(&hash);
+                }
+                None => break,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl State for MemoryState {
+    async fn add_block(&mut self, block: &Block) -> anyhow::Result<()> {
+        block.verify_account()?;
+        if let Previous::Block(previous) = block.previous() {
+            // Pruned blocks are still known through their account.
+            if !self.block_hash_to_account.contains_key(previous) {
+                return self.add_unchecked(block, false).await;
+            }
+        }
+        self.insert_block(block)?;
+
+        // Promote any unchecked blocks that were waiting for this one, and their children.
+        let mut parents = vec![block.hash()?.to_owned()];
+        while let Some(parent) = parents.pop() {
+            for (child, check_work) in self.unchecked.remove(&parent).unwrap_or_default() {
+                if check_work {
+                    let threshold = self
+                        .work_threshold(&child)
+                        .await?
+                        .context("Previous block of a promoted block is unknown")?;
+                    if verify_work(&self.network, &child, &threshold).is_err() {
+                        continue;
+                    }
+                }
+                self.insert_block(&child)?;
+                parents.push(child.hash()?.to_owned());
+            }
+        }
+        Ok(())
+    }
+
+    async fn add_unchecked(&mut self, block: &Block, check_work: bool) -> anyhow::Result<()> {
+        let previous = match block.previous() {
+            Previous::Block(previous) => previous.to_owned(),
+            Previous::Open => return Err(anyhow!("Open blocks can not be unchecked: {:?}", block)),
+        };
+        let waiting = self.unchecked.entry(previous).or_default();
+        if !waiting.iter().any(|(waiting, _)| waiting == block) {
+            waiting.push((block.to_owned(), check_work));
+        }
         Ok(())
     }
 
     async fn get_block_by_hash(&self, hash: &BlockHash) -> anyhow::Result<Option<Block>> {
-        Ok(self.blocks.get(hash).map(|b| b.to_owned()))
+        let block = self.blocks.get(hash).map(|b| b.to_owned());
+        if block.is_some() {
+            self.touch(hash);
+        }
+        Ok(block)
     }
 
     async fn get_latest_block_hash_for_account(
@@ -58,6 +239,77 @@ impl State for MemoryState {
         Ok(self.latest_block_hash.get(account).map(|b| b.to_owned()))
     }
 
+    async fn account_balance(&self, account: &Public) -> anyhow::Result<Option<Raw>> {
+        Ok(self.balances.get(account).map(|b| b.to_owned()))
+    }
+
+    async fn account_block_count(&self, account: &Public) -> anyhow::Result<u64> {
+        Ok(self.account_block_counts.get(account).copied().unwrap_or(0))
+    }
+
+    async fn account_open_block(&self, account: &Public) -> anyhow::Result<Option<BlockHash>> {
+        Ok(self.open_blocks.get(account).map(|h| h.to_owned()))
+    }
+
+    async fn block_height(&self, hash: &BlockHash) -> anyhow::Result<Option<u64>> {
+        Ok(self.heights.get(hash).copied())
+    }
+
+    async fn account_epoch(&self, account: &Public) -> anyhow::Result<u8> {
+        Ok(self.epochs.get(account).copied().unwrap_or(0))
+    }
+
+    async fn accounts_with_min_balance(&mut self, min: Raw) -> anyhow::Result<Vec<(Public, Raw)>> {
+        let mut accounts: Vec<(Public, Raw)> = self
+            .balances
+            .iter()
+            .filter(|(_, balance)| *balance >= &min)
+            .map(|(account, balance)| (account.to_owned(), balance.to_owned()))
+            .collect();
+        accounts.sort_by_key(|(_, balance)| std::cmp::Reverse(balance.to_u128()));
+        Ok(accounts)
+    }
+
+    async fn accounts_ordered(&mut self) -> anyhow::Result<Vec<Public>> {
+        let mut accounts: Vec<Public> = self.balances.keys().cloned().collect();
+        accounts.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+        Ok(accounts)
+    }
+
+    async fn total_supply(&mut self) -> anyhow::Result<Raw> {
+        Ok(self.network.genesis_balance())
+    }
+
+    async fn total_block_count(&mut self) -> anyhow::Result<u64> {
+        Ok(self.block_count)
+    }
+
+    /// Estimated from the amount of entries in each map, ignoring the maps' own overhead.
+    async fn estimated_size_bytes(&mut self) -> anyhow::Result<u64> {
+        let hash = size_of::<BlockHash>();
+        let public = size_of::<Public>();
+        let block = hash + size_of::<Block>();
+        let unchecked: usize = self.unchecked.values().map(|blocks| blocks.len()).sum();
+        let votes: usize = self
+            .votes
+            .values()
+            .map(|votes| hash + votes.len() * public)
+            .sum();
+        let size = self.blocks.len() * block
+            + self.block_hash_to_account.len() * (hash + public)
+            + self.latest_block_hash.len() * (public + hash)
+            + self.balances.len() * (public + size_of::<Raw>())
+            + self.epochs.len() * (public + size_of::<u8>())
+            + self.open_blocks.len() * (public + hash)
+            + self.account_block_counts.len() * (public + size_of::<u64>())
+            + self.heights.len() * (hash + size_of::<u64>())
+            + self.frontier_anchors.len() * (public + hash)
+            + unchecked * block
+            + votes
+            + self.cookies.len() * size_of::<(SocketAddr, Cookie)>();
+        Ok(size as u64)
+    }
+
     async fn account_for_block_hash(
         &mut self,
         block_hash: &BlockHash,
@@ -68,6 +320,20 @@ impl State for MemoryState {
             .map(|a| a.to_owned()))
     }
 
+    async fn set_frontier_anchor(
+        &mut self,
+        account: &Public,
+        hash: &BlockHash,
+    ) -> anyhow::Result<()> {
+        self.frontier_anchors
+            .insert(account.to_owned(), hash.to_owned());
+        Ok(())
+    }
+
+    async fn frontier_anchor(&self, account: &Public) -> anyhow::Result<Option<BlockHash>> {
+        Ok(self.frontier_anchors.get(account).map(|h| h.to_owned()))
+    }
+
     async fn add_vote(&mut self, hash: &BlockHash, representative: &Public) -> anyhow::Result<()> {
         let entry = self
             .votes
@@ -104,14 +370,454 @@ impl State for MemoryState {
         Ok(self.cookies.get(&socket_addr).map(|c| c.to_owned()))
     }
 
+    async fn remove_cookie(&mut self, socket_addr: &SocketAddr) -> anyhow::Result<()> {
+        self.cookies.remove(socket_addr);
+        Ok(())
+    }
+
     async fn add_peers(&mut self, addresses: &[SocketAddr]) -> Result<(), anyhow::Error> {
-        for address in addresses {
-            self.peers.insert(address.to_owned());
-        }
+        self.peers.extend(addresses);
         Ok(())
     }
 
     async fn peers(&self) -> Result<HashSet<SocketAddr>, anyhow::Error> {
-        Ok(self.peers.clone())
+        Ok(self.peers.peers().clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::{BlockType, Link, ValidationState};
+    use crate::node::state::test_helpers::{account, add_genesis, genesis_state, open, send};
+    use crate::{Difficulty, Work};
+    use std::convert::TryFrom;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn genesis_open_seeds_supply() {
+        for network in &[Network::Live, Network::Test] {
+            let mut state = MemoryState::new(*network);
+            let genesis = network.genesis_block().unwrap();
+            state.add_block(&genesis).await.unwrap();
+            assert_eq!(
+                state.account_balance(genesis.account()).await.unwrap(),
+                Some(network.genesis_balance())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn open_without_known_genesis() {
+        // Without a known genesis block, an open block can't be told apart from it.
+        let mut state = MemoryState::new(Network::Beta);
+        let genesis = Network::Live.genesis_block().unwrap();
+        assert!(state.add_block(&genesis).await.is_err());
+        assert_eq!(state.total_block_count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn capacity_evicts_least_recently_accessed() {
+        let network = Network::Live;
+        let mut state = MemoryState::with_capacity(network, 2);
+        let genesis = network.genesis_block().unwrap();
+        let representative = genesis.representative().to_owned();
+
+        let blocks: Vec<Block> = [100u128, 200, 300]
+            .iter()
+            .enumerate()
+            .map(|(idx, balance)| open(&account(idx as u32), &representative, *balance, &genesis))
+            .collect();
+
+        state.add_block(&blocks[0]).await.unwrap();
+        state.add_block(&blocks[1]).await.unwrap();
+        // Access the first block so the second one is the least recently accessed.
+        let hash = blocks[0].hash().unwrap();
+        assert!(state.get_block_by_hash(hash).await.unwrap().is_some());
+        state.add_block(&blocks[2]).await.unwrap();
+
+        for (block, kept) in blocks.iter().zip(&[true, false, true]) {
+            let found = state
+                .get_block_by_hash(block.hash().unwrap())
+                .await
+                .unwrap();
+            assert_eq!(found.is_some(), *kept);
+            assert_eq!(
+                state
+                    .account_balance(block.account())
+                    .await
+                    .unwrap()
+                    .as_ref(),
+                Some(block.balance())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn open_requires_source() {
+        let network = Network::Live;
+        let mut state = MemoryState::new(network);
+        let genesis = network.genesis_block().unwrap();
+        let block = Block::new(
+            BlockType::State,
+            genesis.representative().to_owned(),
+            Previous::Open,
+            genesis.representative().to_owned(),
+            Raw::from(1),
+            Link::Nothing,
+            ValidationState::Valid,
+        );
+        assert!(state.add_block(&block).await.is_err());
+        assert_eq!(state.account_balance(block.account()).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn zero_account() {
+        let (mut state, genesis) = genesis_state().await;
+        let burn = Public::from_str(&"0".repeat(64)).unwrap();
+        let block = Block::new(
+            BlockType::State,
+            burn.to_owned(),
+            Previous::Block(genesis.hash().unwrap().to_owned()),
+            genesis.representative().to_owned(),
+            Raw::from(1),
+            Link::Nothing,
+            ValidationState::Valid,
+        );
+        let err = state.add_block(&block).await.unwrap_err();
+        assert!(err.to_string().contains("zero public key"));
+        assert_eq!(state.account_balance(&burn).await.unwrap(), None);
+        assert!(state.unchecked.is_empty());
+    }
+
+    #[tokio::test]
+    async fn import_frontiers() {
+        let mut state = MemoryState::new(Network::Live);
+        let genesis = Network::Live.genesis_block().unwrap();
+        let account = genesis.account().to_owned();
+        let hash = genesis.hash().unwrap().to_owned();
+
+        state
+            .import_frontiers(&[(account.to_owned(), hash.to_owned())])
+            .await
+            .unwrap();
+        assert_eq!(state.frontier_anchor(&account).await.unwrap(), Some(hash));
+        assert_eq!(
+            state
+                .get_block_by_hash(genesis.hash().unwrap())
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn estimated_size_bytes() {
+        let (mut small, genesis) = genesis_state().await;
+        let (mut large, _) = genesis_state().await;
+        for idx in 0..10 {
+            let open = open(&account(idx), genesis.representative(), 1, &genesis);
+            large.add_block(&open).await.unwrap();
+        }
+
+        let small_size = small.estimated_size_bytes().await.unwrap();
+        assert!(small_size > 0);
+        assert!(large.estimated_size_bytes().await.unwrap() > small_size);
+    }
+
+    #[tokio::test]
+    async fn add_block_checked() {
+        let network = Network::Live;
+        let mut state = MemoryState::new(network);
+        let genesis = network.genesis_block().unwrap();
+
+        let mut no_work = genesis.to_owned();
+        no_work.set_work(Work::zero());
+        assert!(state.add_block_checked(&network, &no_work).await.is_err());
+        assert_eq!(
+            state.account_balance(genesis.account()).await.unwrap(),
+            None
+        );
+
+        state.add_block_checked(&network, &genesis).await.unwrap();
+        assert_eq!(
+            state.account_balance(genesis.account()).await.unwrap(),
+            Some(network.genesis_balance())
+        );
+    }
+
+    #[tokio::test]
+    async fn work_threshold_from_epoch() {
+        let network = Network::Live;
+        let mut state = MemoryState::new(network);
+        let genesis = network.genesis_block().unwrap();
+        let genesis_account = genesis.account().to_owned();
+        assert_eq!(
+            state.work_threshold(&genesis).await.unwrap(),
+            Some(Difficulty::epoch_1())
+        );
+        add_genesis(&mut state).await;
+
+        let unknown = send(
+            &send(&genesis, &genesis_account, 200),
+            &genesis_account,
+            100,
+        );
+        assert_eq!(state.work_threshold(&unknown).await.unwrap(), None);
+        assert_eq!(
+            state
+                .work_threshold(&send(&genesis, &genesis_account, 100))
+                .await
+                .unwrap(),
+            Some(Difficulty::epoch_1())
+        );
+
+        let mut bytes = [0u8; 32];
+        bytes[..14].copy_from_slice(b"epoch v2 block");
+        let upgrade = Block::new(
+            BlockType::State,
+            genesis_account.to_owned(),
+            Previous::Block(genesis.hash().unwrap().to_owned()),
+            genesis.representative().to_owned(),
+            network.genesis_balance(),
+            Link::Source(BlockHash::try_from(bytes.as_ref()).unwrap()),
+            ValidationState::Valid,
+        );
+        state.add_block(&upgrade).await.unwrap();
+        assert_eq!(state.account_epoch(&genesis_account).await.unwrap(), 2);
+        assert_eq!(
+            state
+                .work_threshold(&send(&upgrade, &genesis_account, 100))
+                .await
+                .unwrap(),
+            Some(Difficulty::normal())
+        );
+
+        // Opening with a send from an epoch 2 account.
+        let send = send(&upgrade, &genesis_account, 100);
+        state.add_block(&send).await.unwrap();
+        let open = open(&account(0), &genesis_account, 100, &send);
+        assert_eq!(
+            state.work_threshold(&open).await.unwrap(),
+            Some(Difficulty::receive())
+        );
+    }
+
+    #[tokio::test]
+    async fn promoted_blocks_work_is_checked() {
+        let network = Network::Live;
+        let (mut state, genesis) = genesis_state().await;
+        let account = genesis.account().to_owned();
+
+        let parent = send(&genesis, &account, 100);
+        let mut child = send(&parent, &account, 50);
+        child.set_work(Work::zero());
+
+        // Buffered since the parent isn't known, then dropped once it is.
+        state.add_block_checked(&network, &child).await.unwrap();
+        state.add_block(&parent).await.unwrap();
+        assert!(state.unchecked.is_empty());
+        assert_eq!(
+            state
+                .get_block_by_hash(child.hash().unwrap())
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            state
+                .get_latest_block_hash_for_account(&account)
+                .await
+                .unwrap(),
+            Some(parent.hash().unwrap().to_owned())
+        );
+    }
+
+    #[tokio::test]
+    async fn frontier_changed() {
+        let (mut state, genesis) = genesis_state().await;
+        let account = genesis.account().to_owned();
+        let cached = genesis.hash().unwrap().to_owned();
+        assert!(!state.frontier_changed(&account, &cached).await.unwrap());
+
+        let send = send(&genesis, &account, 100);
+        state.add_block(&send).await.unwrap();
+        assert!(state.frontier_changed(&account, &cached).await.unwrap());
+        assert!(!state
+            .frontier_changed(&account, send.hash().unwrap())
+            .await
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn is_frontier() {
+        let (mut state, genesis) = genesis_state().await;
+        let genesis_account = genesis.account().to_owned();
+        let genesis_hash = genesis.hash().unwrap().to_owned();
+        assert!(state
+            .is_frontier(&genesis_account, &genesis_hash)
+            .await
+            .unwrap());
+
+        let send = send(&genesis, &genesis_account, 100);
+        state.add_block(&send).await.unwrap();
+        assert!(state
+            .is_frontier(&genesis_account, send.hash().unwrap())
+            .await
+            .unwrap());
+        assert!(!state
+            .is_frontier(&genesis_account, &genesis_hash)
+            .await
+            .unwrap());
+
+        assert!(!state.is_frontier(&account(0), &genesis_hash).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn confirmed_balance() {
+        let (mut state, genesis) = genesis_state().await;
+        let account = genesis.account().to_owned();
+        assert_eq!(state.confirmed_balance(&account).await.unwrap(), None);
+
+        state
+            .set_frontier_anchor(&account, genesis.hash().unwrap())
+            .await
+            .unwrap();
+        state
+            .add_block(&send(&genesis, &account, 100))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            state.confirmed_balance(&account).await.unwrap(),
+            Some(Network::Live.genesis_balance())
+        );
+        assert_eq!(
+            state.account_balance(&account).await.unwrap(),
+            Some(Raw::from(100))
+        );
+    }
+
+    #[tokio::test]
+    async fn unchecked_promoted_when_parent_arrives() {
+        let (mut state, genesis) = genesis_state().await;
+        let account = genesis.account().to_owned();
+        let parent = send(&genesis, &account, 100);
+        let child = send(&parent, &account, 50);
+
+        state.add_block(&child).await.unwrap();
+        assert_eq!(
+            state
+                .get_block_by_hash(child.hash().unwrap())
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            state
+                .get_latest_block_hash_for_account(&account)
+                .await
+                .unwrap(),
+            Some(genesis.hash().unwrap().to_owned())
+        );
+
+        state.add_block(&parent).await.unwrap();
+        assert!(state
+            .get_block_by_hash(child.hash().unwrap())
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            state
+                .get_latest_block_hash_for_account(&account)
+                .await
+                .unwrap(),
+            Some(child.hash().unwrap().to_owned())
+        );
+        assert_eq!(
+            state.account_balance(&account).await.unwrap(),
+            Some(Raw::from(50))
+        );
+    }
+
+    #[tokio::test]
+    async fn total_block_count() {
+        let mut state = MemoryState::new(Network::Live);
+        assert_eq!(state.total_block_count().await.unwrap(), 0);
+        let genesis = add_genesis(&mut state).await;
+        assert_eq!(state.total_block_count().await.unwrap(), 1);
+
+        for idx in 1..4 {
+            let block = open(&account(idx), genesis.representative(), 1, &genesis);
+            state.add_block(&block).await.unwrap();
+            // Adding the same block again isn't counted.
+            state.add_block(&block).await.unwrap();
+            assert_eq!(state.total_block_count().await.unwrap(), 1 + idx as u64);
+        }
+    }
+
+    #[tokio::test]
+    async fn accounts_with_min_balance() {
+        let mut state = MemoryState::new(Network::Live);
+        let balances = [50u128, 300, 10, 100, 200];
+        for (idx, balance) in balances.iter().enumerate() {
+            state
+                .balances
+                .insert(account(idx as u32), Raw::from(*balance));
+        }
+
+        let rich = state
+            .accounts_with_min_balance(Raw::from(100))
+            .await
+            .unwrap();
+        let rich: Vec<u128> = rich.iter().map(|(_, b)| b.to_u128()).collect();
+        assert_eq!(rich, vec![300, 200, 100]);
+
+        let top = state
+            .accounts_with_min_balance(Raw::from(300))
+            .await
+            .unwrap();
+        assert_eq!(top, vec![(account(1), Raw::from(300))]);
+        assert!(state
+            .accounts_with_min_balance(Raw::from(301))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn accounts_ordered() {
+        let accounts: Vec<Public> = (0..5).map(account).collect();
+        let mut forwards = MemoryState::new(Network::Live);
+        let mut backwards = MemoryState::new(Network::Live);
+        for account in &accounts {
+            forwards.balances.insert(account.to_owned(), Raw::from(1));
+        }
+        for account in accounts.iter().rev() {
+            backwards.balances.insert(account.to_owned(), Raw::from(1));
+        }
+
+        let ordered = forwards.accounts_ordered().await.unwrap();
+        assert_eq!(ordered, backwards.accounts_ordered().await.unwrap());
+        assert_eq!(ordered.len(), accounts.len());
+        assert!(ordered
+            .windows(2)
+            .all(|pair| pair[0].as_bytes() < pair[1].as_bytes()));
+    }
+
+    #[tokio::test]
+    async fn ledger_hash() {
+        let (mut state, genesis) = genesis_state().await;
+        let expected = state.ledger_hash().await.unwrap();
+        state.assert_ledger_hash(&expected).await.unwrap();
+        assert!(state.assert_ledger_hash(&BlockHash::zero()).await.is_err());
+
+        // The same ledger built separately has the same hash.
+        let (mut other, _) = genesis_state().await;
+        other.assert_ledger_hash(&expected).await.unwrap();
+
+        let send = send(&genesis, genesis.account(), 100);
+        other.add_block(&send).await.unwrap();
+        assert!(other.assert_ledger_hash(&expected).await.is_err());
     }
 }
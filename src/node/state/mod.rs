@@ -1,13 +1,27 @@
+mod account_cache;
+mod account_info;
+pub mod analytics;
+mod balance_proof;
+mod event_log;
+mod ledger_import;
 mod memory;
+mod receipt;
+mod shared;
 mod sled_disk;
 
-use crate::blocks::{Block, BlockHash};
+use crate::blocks::{epoch_version, Block, BlockHash, Previous, Subtype};
+use crate::encoding::Blake2bHasher;
 use crate::node::cookie::Cookie;
-use crate::Public;
+use crate::{Difficulty, Network, Public, Raw, Subject};
+pub use account_info::AccountInfo;
+use anyhow::{anyhow, Context};
 use async_trait::async_trait;
+pub use balance_proof::BalanceProof;
 pub use memory::MemoryState;
+pub use receipt::AccountReceipt;
 pub use sled_disk::SledDiskState;
 use std::collections::HashSet;
+use std::convert::TryFrom;
 use std::fmt::Debug;
 use std::net::SocketAddr;
 use std::sync::Arc;
@@ -20,8 +34,61 @@ pub type ArcState = Arc<Mutex<DynState>>;
 /// it also contains ephemeral information like peers.
 #[async_trait]
 pub trait State: Debug + Sync + Send + 'static {
+    /// Add a block, buffering it as unchecked when its previous block isn't known yet.
     async fn add_block(&mut self, block: &Block) -> anyhow::Result<()>;
 
+    /// Like [State::add_block], but first rejecting the block if its work doesn't meet
+    /// [State::work_threshold], e.g. for blocks received from peers.
+    ///
+    /// When the previous block isn't known yet the threshold can't be worked out, so the block is
+    /// buffered with [State::add_unchecked] and its work is verified once the previous block
+    /// arrives.
+    async fn add_block_checked(&mut self, network: &Network, block: &Block) -> anyhow::Result<()> {
+        let threshold = match self.work_threshold(block).await? {
+            Some(threshold) => threshold,
+            None => return self.add_unchecked(block, true).await,
+        };
+        verify_work(network, block, &threshold)?;
+        self.add_block(block).await
+    }
+
+    /// The work threshold for `block`, from its subtype and the epoch of its account, or `None`
+    /// when its previous block isn't known yet.
+    ///
+    /// Receiving blocks also take the epoch of the sender's account when the source block is
+    /// known. When the previous block was pruned the subtype can't be worked out, so the highest
+    /// threshold of the epoch is used.
+    async fn work_threshold(&mut self, block: &Block) -> anyhow::Result<Option<Difficulty>> {
+        let mut epoch = self
+            .account_epoch(block.account())
+            .await?
+            .max(epoch_version(std::slice::from_ref(block)));
+        let previous = match block.previous() {
+            Previous::Open => None,
+            Previous::Block(previous) => match self.get_block_by_hash(previous).await? {
+                Some(previous) => Some(previous),
+                None if self.account_for_block_hash(previous).await?.is_some() => {
+                    let subtypes = [Subtype::Send, Subtype::Receive];
+                    return Ok(Difficulty::highest_for(&subtypes, epoch));
+                }
+                None => return Ok(None),
+            },
+        };
+        let subtype = block.subtype(previous.as_ref())?;
+        if matches!(subtype, Subtype::Receive | Subtype::Open) {
+            if let Some(sender) = self.account_for_block_hash(block.source()?).await? {
+                epoch = epoch.max(self.account_epoch(&sender).await?);
+            }
+        }
+        Ok(Some(Difficulty::for_subtype(&subtype, epoch)))
+    }
+
+    /// Buffer a block until its previous block is added.
+    ///
+    /// With `check_work` the block is dropped instead when its work doesn't meet
+    /// [State::work_threshold] by then, see [State::add_block_checked].
+    async fn add_unchecked(&mut self, block: &Block, check_work: bool) -> anyhow::Result<()>;
+
     async fn get_block_by_hash(&self, hash: &BlockHash) -> anyhow::Result<Option<Block>>;
 
     async fn get_latest_block_hash_for_account(
@@ -29,11 +96,219 @@ pub trait State: Debug + Sync + Send + 'static {
         account: &Public,
     ) -> anyhow::Result<Option<BlockHash>>;
 
+    /// Whether `hash` is the latest block of `account`.
+    async fn is_frontier(&mut self, account: &Public, hash: &BlockHash) -> anyhow::Result<bool> {
+        Ok(self
+            .get_latest_block_hash_for_account(account)
+            .await?
+            .as_ref()
+            == Some(hash))
+    }
+
+    /// Whether the latest block of `account` is no longer `since`, e.g. a frontier cached when the
+    /// account was last processed, so unchanged accounts can be skipped when polling.
+    async fn frontier_changed(
+        &mut self,
+        account: &Public,
+        since: &BlockHash,
+    ) -> anyhow::Result<bool> {
+        Ok(!self.is_frontier(account, since).await?)
+    }
+
+    async fn account_balance(&self, account: &Public) -> anyhow::Result<Option<Raw>>;
+
+    async fn account_block_count(&self, account: &Public) -> anyhow::Result<u64>;
+
+    /// The open block of `account`, kept even if the block itself has been pruned.
+    async fn account_open_block(&self, account: &Public) -> anyhow::Result<Option<BlockHash>>;
+
+    /// The position of a block in its account's chain, starting at 1 for the open block.
+    ///
+    /// `None` when the block is unknown, or was added without the blocks before it, e.g. from a
+    /// pruned ledger.
+    async fn block_height(&self, hash: &BlockHash) -> anyhow::Result<Option<u64>>;
+
+    /// The epoch `account` has been upgraded to, or 0 when it isn't opened, see [epoch_version].
+    async fn account_epoch(&self, account: &Public) -> anyhow::Result<u8>;
+
+    /// Accounts holding at least `min`, sorted by balance from highest to lowest, e.g. for a rich
+    /// list.
+    async fn accounts_with_min_balance(&mut self, min: Raw) -> anyhow::Result<Vec<(Public, Raw)>>;
+
+    /// Every known account, ordered by public key bytes so that iterating is reproducible, e.g.
+    /// for dumps and hashing the ledger.
+    async fn accounts_ordered(&mut self) -> anyhow::Result<Vec<Public>>;
+
+    /// The amount created by the genesis block.
+    async fn total_supply(&mut self) -> anyhow::Result<Raw>;
+
+    /// The amount of blocks across all accounts, maintained as blocks are added.
+    async fn total_block_count(&mut self) -> anyhow::Result<u64>;
+
+    /// Roughly how many bytes the state takes up, on disk or in memory, e.g. to monitor growth.
+    async fn estimated_size_bytes(&mut self) -> anyhow::Result<u64>;
+
+    /// A hash of every account with its frontier, in [State::accounts_ordered] order, so two
+    /// ledgers can be compared without comparing every block.
+    async fn ledger_hash(&mut self) -> anyhow::Result<BlockHash> {
+        let mut hasher = Blake2bHasher::new(BlockHash::LEN);
+        for account in self.accounts_ordered().await? {
+            let frontier = self
+                .get_latest_block_hash_for_account(&account)
+                .await?
+                .with_context(|| format!("Account {:?} has no frontier", account))?;
+            hasher.update(account.as_bytes());
+            hasher.update(frontier.as_bytes());
+        }
+        Ok(BlockHash::try_from(hasher.finalize().as_ref())?)
+    }
+
+    /// Fail unless [State::ledger_hash] is `expected`, e.g. a hash published by several nodes, to
+    /// check a ledger after importing it.
+    async fn assert_ledger_hash(&mut self, expected: &BlockHash) -> anyhow::Result<()> {
+        let hash = self.ledger_hash().await?;
+        if &hash != expected {
+            return Err(anyhow!(
+                "Ledger hash is {} but {} was expected",
+                hash,
+                expected
+            ));
+        }
+        Ok(())
+    }
+
+    /// Package the account's balance, frontier, representative and block count together.
+    async fn account_receipt(&mut self, account: &Public) -> anyhow::Result<AccountReceipt> {
+        let frontier = self.get_latest_block_hash_for_account(account).await?;
+        let representative = match &frontier {
+            Some(hash) => self
+                .get_block_by_hash(hash)
+                .await?
+                .map(|block| block.representative().to_address()),
+            None => None,
+        };
+        Ok(AccountReceipt {
+            address: account.to_address(),
+            balance: self
+                .account_balance(account)
+                .await?
+                .unwrap_or_else(Raw::zero),
+            frontier,
+            representative,
+            block_count: self.account_block_count(account).await?,
+        })
+    }
+
+    /// The frontier, open block, representative, balance, block count and confirmation height of
+    /// `account` together, like the node's `account_info` RPC.
+    ///
+    /// `None` when the account hasn't been opened.
+    async fn account_info(&mut self, account: &Public) -> anyhow::Result<Option<AccountInfo>> {
+        let frontier = match self.get_latest_block_hash_for_account(account).await? {
+            Some(frontier) => frontier,
+            None => return Ok(None),
+        };
+        let frontier_block = self
+            .get_block_by_hash(&frontier)
+            .await?
+            .with_context(|| format!("Frontier {} of {:?} is unknown", frontier, account))?;
+        let block_count = self.account_block_count(account).await?;
+        let anchor = self.frontier_anchor(account).await?;
+        let open_block = self.account_open_block(account).await?;
+        let confirmation_height = match &anchor {
+            Some(anchor) => self.block_height(anchor).await?.unwrap_or(0),
+            None => 0,
+        };
+
+        Ok(Some(AccountInfo {
+            frontier,
+            open_block,
+            representative: frontier_block.representative().to_owned(),
+            balance: frontier_block.balance().to_owned(),
+            block_count,
+            confirmation_height,
+            confirmation_height_frontier: anchor,
+        }))
+    }
+
+    /// The chain of `account` from `checkpoint` to its frontier, see [BalanceProof].
+    ///
+    /// Fails when `checkpoint` isn't in the account's chain or a block in between was pruned.
+    async fn balance_proof(
+        &mut self,
+        account: &Public,
+        checkpoint: &BlockHash,
+    ) -> anyhow::Result<BalanceProof> {
+        let mut hash = self
+            .get_latest_block_hash_for_account(account)
+            .await?
+            .with_context(|| format!("Account {:?} has no blocks", account))?;
+        let mut blocks = vec![];
+        loop {
+            let block = self
+                .get_block_by_hash(&hash)
+                .await?
+                .with_context(|| format!("Block {} is unknown", hash))?;
+            let previous = block.previous().to_owned();
+            blocks.push(block);
+            if &hash == checkpoint {
+                break;
+            }
+            hash = match previous {
+                Previous::Block(previous) => previous,
+                Previous::Open => {
+                    return Err(anyhow!(
+                        "Checkpoint {} isn't in the chain of {:?}",
+                        checkpoint,
+                        account
+                    ))
+                }
+            };
+        }
+        blocks.reverse();
+        Ok(BalanceProof { blocks })
+    }
+
     async fn account_for_block_hash(
         &mut self,
         block_hash: &BlockHash,
     ) -> anyhow::Result<Option<Public>>;
 
+    /// Record `hash` as a known frontier of `account`, without having the block itself.
+    async fn set_frontier_anchor(
+        &mut self,
+        account: &Public,
+        hash: &BlockHash,
+    ) -> anyhow::Result<()>;
+
+    async fn frontier_anchor(&self, account: &Public) -> anyhow::Result<Option<BlockHash>>;
+
+    /// The balance of `account` as of its frontier anchor, i.e. excluding blocks which aren't
+    /// confirmed yet. Compare with [State::account_balance] to show a pending confirmation amount.
+    ///
+    /// `None` when no block of the account has been confirmed.
+    async fn confirmed_balance(&self, account: &Public) -> anyhow::Result<Option<Raw>> {
+        let anchor = match self.frontier_anchor(account).await? {
+            Some(anchor) => anchor,
+            None => return Ok(None),
+        };
+        let block = self.get_block_by_hash(&anchor).await?.with_context(|| {
+            format!("Confirmed frontier {} of {:?} is unknown", anchor, account)
+        })?;
+        Ok(Some(block.balance().to_owned()))
+    }
+
+    /// Bulk import frontiers, e.g. from the `frontiers` RPC, as confirmation height anchors.
+    ///
+    /// This is a lightweight alternative to importing every block when only a frontier index is
+    /// needed.
+    async fn import_frontiers(&mut self, frontiers: &[(Public, BlockHash)]) -> anyhow::Result<()> {
+        for (account, hash) in frontiers {
+            self.set_frontier_anchor(account, hash).await?;
+        }
+        Ok(())
+    }
+
     async fn add_vote(&mut self, hash: &BlockHash, representative: &Public) -> anyhow::Result<()>;
 
     async fn set_cookie(&mut self, socket_addr: SocketAddr, cookie: Cookie) -> anyhow::Result<()>;
@@ -43,7 +318,176 @@ pub trait State: Debug + Sync + Send + 'static {
         socket_addr: &SocketAddr,
     ) -> anyhow::Result<Option<Cookie>>;
 
+    /// Forget the cookie sent to `socket_addr`, e.g. once a handshake response has been verified
+    /// with it, so the same response can't be replayed.
+    async fn remove_cookie(&mut self, socket_addr: &SocketAddr) -> anyhow::Result<()>;
+
     async fn add_peers(&mut self, addresses: &[SocketAddr]) -> anyhow::Result<()>;
 
     async fn peers(&self) -> anyhow::Result<HashSet<SocketAddr>>;
 }
+
+/// Fail unless the work of `block` meets `threshold`.
+fn verify_work(network: &Network, block: &Block, threshold: &Difficulty) -> anyhow::Result<()> {
+    let subject = match block.previous() {
+        Previous::Open => Subject::Public(block.account().to_owned()),
+        Previous::Block(previous) => Subject::Hash(previous.to_owned()),
+    };
+    let hash = block.hash()?;
+    let work = block
+        .work()
+        .with_context(|| format!("Block {} has no work", hash))?;
+    if !work.verify(network, &subject, threshold)? {
+        return Err(anyhow!(
+            "Block {} has insufficient work {:?}, needs {:?}",
+            hash,
+            work,
+            threshold
+        ));
+    }
+    Ok(())
+}
+
+/// Fixtures shared by the tests of the states and what's built on them.
+#[cfg(test)]
+pub(crate) mod test_helpers {
+    use super::{AccountInfo, AccountReceipt, BalanceProof, MemoryState, State};
+    use crate::blocks::{Block, BlockHash, BlockType, Link, Previous, ValidationState};
+    use crate::node::cookie::Cookie;
+    use crate::{Difficulty, Network, Public, Raw, Seed};
+    use async_trait::async_trait;
+    use std::collections::HashSet;
+    use std::net::SocketAddr;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+
+    /// Add the live genesis block to `state`, returning it.
+    pub async fn add_genesis<S: State + ?Sized>(state: &mut S) -> Block {
+        let genesis = Network::Live.genesis_block().unwrap();
+        state.add_block(&genesis).await.unwrap();
+        genesis
+    }
+
+    /// A live network state with only the genesis block, and that block.
+    pub async fn genesis_state() -> (MemoryState, Block) {
+        let mut state = MemoryState::new(Network::Live);
+        let genesis = add_genesis(&mut state).await;
+        (state, genesis)
+    }
+
+    /// Account `index` of the zero seed.
+    pub fn account(index: u32) -> Public {
+        Seed::zero().derive(index).to_public().unwrap()
+    }
+
+    /// An unsigned state block after `previous` in the same account, keeping its representative
+    /// and sending to `destination` down to `balance`.
+    pub fn send(previous: &Block, destination: &Public, balance: u128) -> Block {
+        Block::new(
+            BlockType::State,
+            previous.account().to_owned(),
+            Previous::Block(previous.hash().unwrap().to_owned()),
+            previous.representative().to_owned(),
+            Raw::from(balance),
+            Link::DestinationAccount(destination.to_owned()),
+            ValidationState::Valid,
+        )
+    }
+
+    /// An unsigned state block opening `account` with `balance`, received from `source` and
+    /// delegating to `representative`.
+    pub fn open(account: &Public, representative: &Public, balance: u128, source: &Block) -> Block {
+        Block::new(
+            BlockType::State,
+            account.to_owned(),
+            Previous::Open,
+            representative.to_owned(),
+            Raw::from(balance),
+            Link::Source(source.hash().unwrap().to_owned()),
+            ValidationState::Valid,
+        )
+    }
+
+    /// Wraps a state, counting every call reaching it, e.g. to check what a cache in front of it
+    /// still asks for.
+    #[derive(Debug)]
+    pub struct CountingState<S> {
+        inner: S,
+        calls: Arc<AtomicU64>,
+    }
+
+    impl<S> CountingState<S> {
+        /// The counter is returned separately so it can still be read once the state is shared.
+        pub fn new(inner: S) -> (Self, Arc<AtomicU64>) {
+            let calls = Arc::new(AtomicU64::new(0));
+            let state = Self {
+                inner,
+                calls: calls.clone(),
+            };
+            (state, calls)
+        }
+    }
+
+    /// Implement [State] for [CountingState], forwarding every method, including the provided
+    /// ones, to the inner state.
+    macro_rules! counting_state {
+        (
+            ref { $($r:ident($($ra:ident: $rt:ty),*) -> $rr:ty;)* }
+            mut { $($m:ident($($ma:ident: $mt:ty),*) -> $mr:ty;)* }
+        ) => {
+            #[async_trait]
+            impl<S: State> State for CountingState<S> {
+                $(async fn $r(&self, $($ra: $rt),*) -> anyhow::Result<$rr> {
+                    self.calls.fetch_add(1, Ordering::SeqCst);
+                    self.inner.$r($($ra),*).await
+                })*
+
+                $(async fn $m(&mut self, $($ma: $mt),*) -> anyhow::Result<$mr> {
+                    self.calls.fetch_add(1, Ordering::SeqCst);
+                    self.inner.$m($($ma),*).await
+                })*
+            }
+        };
+    }
+
+    counting_state! {
+        ref {
+            get_block_by_hash(hash: &BlockHash) -> Option<Block>;
+            get_latest_block_hash_for_account(account: &Public) -> Option<BlockHash>;
+            account_balance(account: &Public) -> Option<Raw>;
+            account_block_count(account: &Public) -> u64;
+            account_open_block(account: &Public) -> Option<BlockHash>;
+            block_height(hash: &BlockHash) -> Option<u64>;
+            account_epoch(account: &Public) -> u8;
+            frontier_anchor(account: &Public) -> Option<BlockHash>;
+            confirmed_balance(account: &Public) -> Option<Raw>;
+            cookie_for_socket_addr(socket_addr: &SocketAddr) -> Option<Cookie>;
+            peers() -> HashSet<SocketAddr>;
+        }
+        mut {
+            add_block(block: &Block) -> ();
+            add_block_checked(network: &Network, block: &Block) -> ();
+            work_threshold(block: &Block) -> Option<Difficulty>;
+            add_unchecked(block: &Block, check_work: bool) -> ();
+            is_frontier(account: &Public, hash: &BlockHash) -> bool;
+            frontier_changed(account: &Public, since: &BlockHash) -> bool;
+            accounts_with_min_balance(min: Raw) -> Vec<(Public, Raw)>;
+            accounts_ordered() -> Vec<Public>;
+            total_supply() -> Raw;
+            total_block_count() -> u64;
+            estimated_size_bytes() -> u64;
+            ledger_hash() -> BlockHash;
+            assert_ledger_hash(expected: &BlockHash) -> ();
+            account_receipt(account: &Public) -> AccountReceipt;
+            account_info(account: &Public) -> Option<AccountInfo>;
+            balance_proof(account: &Public, checkpoint: &BlockHash) -> BalanceProof;
+            account_for_block_hash(block_hash: &BlockHash) -> Option<Public>;
+            set_frontier_anchor(account: &Public, hash: &BlockHash) -> ();
+            import_frontiers(frontiers: &[(Public, BlockHash)]) -> ();
+            add_vote(hash: &BlockHash, representative: &Public) -> ();
+            set_cookie(socket_addr: SocketAddr, cookie: Cookie) -> ();
+            remove_cookie(socket_addr: &SocketAddr) -> ();
+            add_peers(addresses: &[SocketAddr]) -> ();
+        }
+    }
+}
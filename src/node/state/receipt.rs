@@ -0,0 +1,46 @@
+use crate::blocks::BlockHash;
+use crate::{Address, Raw};
+use serde::{Deserialize, Serialize};
+
+/// A portable snapshot of a single account, suitable for sharing or auditing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountReceipt {
+    pub address: Address,
+    pub balance: Raw,
+
+    /// `None` when the account hasn't been opened.
+    pub frontier: Option<BlockHash>,
+
+    /// `None` when the frontier block isn't in the state.
+    pub representative: Option<Address>,
+
+    pub block_count: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use crate::node::state::test_helpers::genesis_state;
+    use crate::node::state::State;
+
+    #[tokio::test]
+    async fn round_trip() {
+        let (mut state, genesis) = genesis_state().await;
+        let receipt = state.account_receipt(genesis.account()).await.unwrap();
+        assert_eq!(
+            receipt,
+            AccountReceipt {
+                address: genesis.account().to_address(),
+                balance: Network::Live.genesis_balance(),
+                frontier: Some(genesis.hash().unwrap().to_owned()),
+                representative: Some(genesis.representative().to_address()),
+                block_count: 1,
+            }
+        );
+
+        let json = serde_json::to_string(&receipt).unwrap();
+        let decoded: AccountReceipt = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, receipt);
+    }
+}
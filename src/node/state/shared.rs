@@ -0,0 +1,156 @@
+use crate::blocks::{Block, Previous};
+use crate::node::state::ArcState;
+use crate::Public;
+use anyhow::anyhow;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Adds blocks to a shared state from several tasks, e.g. one per peer.
+///
+/// Checking that a block follows its account's frontier and adding it happen while holding a lock
+/// for that account, so two blocks of the same account can't both be added on top of the same
+/// frontier. Blocks of different accounts don't wait on each other apart from the state itself.
+pub struct SharedState {
+    state: ArcState,
+    accounts: std::sync::Mutex<HashMap<Public, Arc<Mutex<()>>>>,
+}
+
+impl SharedState {
+    pub fn new(state: ArcState) -> Self {
+        Self {
+            state,
+            accounts: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn state(&self) -> &ArcState {
+        &self.state
+    }
+
+    /// Add `block`, failing if it doesn't follow the current frontier of its account, i.e. it's a
+    /// fork or its previous block hasn't been added yet.
+    pub async fn add_block(&self, block: &Block) -> anyhow::Result<()> {
+        let account = block.account();
+        let lock = self.account_lock(account);
+        let guard = lock.lock().await;
+
+        let result = self.add_block_locked(block).await;
+
+        drop(guard);
+        let mut accounts = self.accounts.lock().unwrap();
+        // Only the map and this call hold the lock, so nobody else is waiting on it.
+        if Arc::strong_count(&lock) == 2 {
+            accounts.remove(account);
+        }
+        result
+    }
+
+    async fn add_block_locked(&self, block: &Block) -> anyhow::Result<()> {
+        let account = block.account();
+        let frontier = self
+            .state
+            .lock()
+            .await
+            .get_latest_block_hash_for_account(account)
+            .await?;
+        match (block.previous(), &frontier) {
+            (Previous::Open, None) => {}
+            (Previous::Block(previous), Some(frontier)) if previous == frontier => {}
+            _ => {
+                return Err(anyhow!(
+                    "Block {} does not follow the frontier {:?} of {:?}",
+                    block.hash()?,
+                    frontier,
+                    account
+                ))
+            }
+        }
+        self.state.lock().await.add_block(block).await
+    }
+
+    fn account_lock(&self, account: &Public) -> Arc<Mutex<()>> {
+        self.accounts
+            .lock()
+            .unwrap()
+            .entry(account.to_owned())
+            .or_default()
+            .to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::node::state::test_helpers::{account, genesis_state, open, send};
+    use crate::node::state::MemoryState;
+    use crate::Network;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn same_account_is_serialized() {
+        let (memory, genesis) = genesis_state().await;
+        let account = genesis.account().to_owned();
+        let shared = Arc::new(SharedState::new(Arc::new(Mutex::new(memory))));
+
+        // Every send is built on the genesis block, so only one of them can be added.
+        let mut tasks = vec![];
+        for balance in 0..10u128 {
+            let send = send(&genesis, &account, balance);
+            let shared = shared.clone();
+            tasks.push(tokio::spawn(async move {
+                shared.add_block(&send).await.map(|_| send)
+            }));
+        }
+        let mut added = vec![];
+        for task in tasks {
+            if let Ok(block) = task.await.unwrap() {
+                added.push(block);
+            }
+        }
+
+        assert_eq!(added.len(), 1);
+        let state = shared.state().lock().await;
+        assert_eq!(
+            state
+                .get_latest_block_hash_for_account(&account)
+                .await
+                .unwrap()
+                .as_ref(),
+            Some(added[0].hash().unwrap())
+        );
+        assert_eq!(state.account_block_count(&account).await.unwrap(), 2);
+        drop(state);
+        assert!(shared.accounts.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn different_accounts() {
+        let network = Network::Live;
+        let genesis = network.genesis_block().unwrap();
+        let shared = Arc::new(SharedState::new(Arc::new(Mutex::new(MemoryState::new(
+            network,
+        )))));
+
+        let mut tasks = vec![];
+        for index in 0..5 {
+            let account = account(index);
+            let open = open(&account, &account, 100, &genesis);
+            let shared = shared.clone();
+            tasks.push(tokio::spawn(async move { shared.add_block(&open).await }));
+        }
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+        assert_eq!(
+            shared
+                .state()
+                .lock()
+                .await
+                .accounts_ordered()
+                .await
+                .unwrap()
+                .len(),
+            5
+        );
+    }
+}
@@ -1,8 +1,10 @@
-use crate::blocks::{Block, BlockHash};
+use crate::blocks::record::BlockRecord;
+use crate::blocks::{epoch_version, Block, BlockHash, Previous};
 use crate::network::Network;
 use crate::node::cookie::Cookie;
-use crate::node::state::State;
-use crate::Public;
+use crate::node::state::{verify_work, State};
+use crate::{Public, Raw};
+use anyhow::{anyhow, Context};
 use async_trait::async_trait;
 use std::collections::HashSet;
 use std::convert::TryFrom;
@@ -15,6 +17,25 @@ pub struct SledDiskState {
     db: sled::Db,
     cookies: sled::Tree,
     peers: sled::Tree,
+    counters: sled::Tree,
+
+    /// Blocks keyed by hash, as [BlockRecord] JSON.
+    blocks: sled::Tree,
+
+    /// Balances keyed by account, so iterating is in public key order.
+    accounts: sled::Tree,
+    frontiers: sled::Tree,
+    block_accounts: sled::Tree,
+    account_block_counts: sled::Tree,
+    open_blocks: sled::Tree,
+    heights: sled::Tree,
+    epochs: sled::Tree,
+
+    /// Frontier anchors keyed by account, see [State::set_frontier_anchor].
+    anchors: sled::Tree,
+
+    /// Blocks waiting for their previous block, see [SledDiskState::add_unchecked].
+    unchecked: sled::Tree,
 }
 
 impl SledDiskState {
@@ -22,39 +43,295 @@ impl SledDiskState {
         let path = format!("{:?}.db", network).to_ascii_lowercase();
         let db: sled::Db =
             sled::open(&path).unwrap_or_else(|_| panic!("Could not open database: {}", &path));
+        Self::with_db(network, db)
+    }
+
+    fn with_db(network: Network, db: sled::Db) -> Self {
         let cookies = db.open_tree("cookies").unwrap();
         let peers = db.open_tree("peers").unwrap();
+        let counters = db.open_tree("counters").unwrap();
+        let blocks = db.open_tree("blocks").unwrap();
+        let accounts = db.open_tree("accounts").unwrap();
+        let frontiers = db.open_tree("frontiers").unwrap();
+        let block_accounts = db.open_tree("block_accounts").unwrap();
+        let account_block_counts = db.open_tree("account_block_counts").unwrap();
+        let open_blocks = db.open_tree("open_blocks").unwrap();
+        let heights = db.open_tree("heights").unwrap();
+        let epochs = db.open_tree("epochs").unwrap();
+        let anchors = db.open_tree("anchors").unwrap();
+        let unchecked = db.open_tree("unchecked").unwrap();
         Self {
             network,
             db,
             cookies,
             peers,
+            counters,
+            blocks,
+            accounts,
+            frontiers,
+            block_accounts,
+            account_block_counts,
+            open_blocks,
+            heights,
+            epochs,
+            anchors,
+            unchecked,
+        }
+    }
+
+    const BLOCK_COUNT: &'static str = "blocks";
+
+    /// Returns the new total after bumping the stored block counter, so it doesn't need a scan.
+    ///
+    /// A corrupt counter is left as is and returned as an error.
+    fn increment_block_count(&self) -> anyhow::Result<u64> {
+        Self::increment(&self.counters, Self::BLOCK_COUNT.as_bytes())
+    }
+
+    /// Bump the counter stored at `key` in `tree`, like [SledDiskState::increment_block_count].
+    fn increment(tree: &sled::Tree, key: &[u8]) -> anyhow::Result<u64> {
+        let mut result = Ok(0);
+        tree.update_and_fetch(key, |old| match old.map(Self::decode_count).transpose() {
+            Ok(count) => {
+                let count = count.unwrap_or(0) + 1;
+                result = Ok(count);
+                Some(count.to_be_bytes().to_vec())
+            }
+            Err(err) => {
+                result = Err(err);
+                old.map(|old| old.to_vec())
+            }
+        })?;
+        result
+    }
+
+    fn decode_count(bytes: &[u8]) -> anyhow::Result<u64> {
+        let buf = <[u8; 8]>::try_from(bytes)
+            .map_err(|_| anyhow!("Block count should be 8 bytes, got {}", bytes.len()))?;
+        Ok(u64::from_be_bytes(buf))
+    }
+
+    fn encode_block(block: &Block) -> anyhow::Result<Vec<u8>> {
+        Ok(serde_json::to_vec(&BlockRecord::from(block))?)
+    }
+
+    fn decode_block(bytes: &[u8]) -> anyhow::Result<Block> {
+        Block::try_from(serde_json::from_slice::<BlockRecord>(bytes)?)
+    }
+
+    fn insert_block(&self, block: &Block) -> anyhow::Result<()> {
+        let hash = block.hash().context("Add block")?;
+        let account = block.account().as_bytes();
+        self.blocks
+            .insert(hash.as_bytes(), Self::encode_block(block)?)?;
+        let epoch = epoch_version(std::slice::from_ref(block));
+        if epoch > 0 {
+            self.epochs.insert(account, vec![epoch])?;
+        }
+        self.accounts.insert(account, block.balance().to_vec())?;
+        self.frontiers.insert(account, hash.as_bytes())?;
+        let height = match block.previous() {
+            Previous::Open => {
+                self.open_blocks.insert(account, hash.as_bytes())?;
+                Some(1)
+            }
+            Previous::Block(previous) => self.get_height(previous)?.map(|height| height + 1),
+        };
+        if let Some(height) = height {
+            self.heights
+                .insert(hash.as_bytes(), height.to_be_bytes().to_vec())?;
         }
+        if self
+            .block_accounts
+            .insert(hash.as_bytes(), account)?
+            .is_none()
+        {
+            self.increment_block_count()?;
+            Self::increment(&self.account_block_counts, account)?;
+        }
+        Ok(())
+    }
+
+    fn decode_unchecked(bytes: &[u8]) -> anyhow::Result<(bool, Block)> {
+        let (check_work, block) = bytes.split_first().context("Empty unchecked block")?;
+        Ok((*check_work != 0, Self::decode_block(block)?))
+    }
+
+    fn get_height(&self, hash: &BlockHash) -> anyhow::Result<Option<u64>> {
+        self.heights
+            .get(hash.as_bytes())?
+            .map(|height| Self::decode_count(&height))
+            .transpose()
     }
 }
 
 #[async_trait]
 impl State for SledDiskState {
-    async fn add_block(&mut self, _block_holder: &Block) -> anyhow::Result<()> {
-        unimplemented!()
+    async fn add_block(&mut self, block: &Block) -> anyhow::Result<()> {
+        block.verify_account()?;
+        if let Previous::Block(previous) = block.previous() {
+            if !self.block_accounts.contains_key(previous.as_bytes())? {
+                return self.add_unchecked(block, false).await;
+            }
+        }
+        self.insert_block(block)?;
+
+        // Promote any unchecked blocks that were waiting for this one, and their children.
+        let mut parents = vec![block.hash()?.to_owned()];
+        while let Some(parent) = parents.pop() {
+            for entry in self.unchecked.scan_prefix(parent.as_bytes()) {
+                let (key, value) = entry?;
+                self.unchecked.remove(key)?;
+                let (check_work, child) = Self::decode_unchecked(&value)?;
+                if check_work {
+                    let threshold = self
+                        .work_threshold(&child)
+                        .await?
+                        .context("Previous block of a promoted block is unknown")?;
+                    if verify_work(&self.network, &child, &threshold).is_err() {
+                        continue;
+                    }
+                }
+                self.insert_block(&child)?;
+                parents.push(child.hash()?.to_owned());
+            }
+        }
+        Ok(())
     }
 
-    async fn get_block_by_hash(&self, _hash: &BlockHash) -> anyhow::Result<Option<Block>> {
-        unimplemented!()
+    /// Unchecked blocks are keyed by their previous block hash followed by their own hash, so the
+    /// children of a block can be found with a prefix scan.
+    async fn add_unchecked(&mut self, block: &Block, check_work: bool) -> anyhow::Result<()> {
+        let previous = match block.previous() {
+            Previous::Block(previous) => previous,
+            Previous::Open => return Err(anyhow!("Open blocks can not be unchecked: {:?}", block)),
+        };
+        let mut key = previous.as_bytes().to_vec();
+        key.extend_from_slice(block.hash().context("Add unchecked")?.as_bytes());
+        let mut value = vec![check_work as u8];
+        value.extend(Self::encode_block(block)?);
+        self.unchecked.insert(key, value)?;
+        Ok(())
+    }
+
+    async fn get_block_by_hash(&self, hash: &BlockHash) -> anyhow::Result<Option<Block>> {
+        self.blocks
+            .get(hash.as_bytes())?
+            .map(|block| Self::decode_block(&block))
+            .transpose()
     }
 
     async fn get_latest_block_hash_for_account(
         &self,
-        _account: &Public,
+        account: &Public,
     ) -> anyhow::Result<Option<BlockHash>> {
-        unimplemented!()
+        Ok(match self.frontiers.get(account.as_bytes())? {
+            Some(hash) => Some(BlockHash::try_from(hash.as_ref())?),
+            None => None,
+        })
+    }
+
+    async fn account_balance(&self, account: &Public) -> anyhow::Result<Option<Raw>> {
+        Ok(match self.accounts.get(account.as_bytes())? {
+            Some(balance) => Some(Raw::try_from(balance.as_ref())?),
+            None => None,
+        })
+    }
+
+    async fn account_block_count(&self, account: &Public) -> anyhow::Result<u64> {
+        Ok(self
+            .account_block_counts
+            .get(account.as_bytes())?
+            .map(|v| Self::decode_count(&v))
+            .transpose()?
+            .unwrap_or(0))
+    }
+
+    async fn account_open_block(&self, account: &Public) -> anyhow::Result<Option<BlockHash>> {
+        Ok(match self.open_blocks.get(account.as_bytes())? {
+            Some(hash) => Some(BlockHash::try_from(hash.as_ref())?),
+            None => None,
+        })
+    }
+
+    async fn block_height(&self, hash: &BlockHash) -> anyhow::Result<Option<u64>> {
+        self.get_height(hash)
+    }
+
+    async fn account_epoch(&self, account: &Public) -> anyhow::Result<u8> {
+        Ok(match self.epochs.get(account.as_bytes())? {
+            Some(epoch) => *epoch
+                .first()
+                .with_context(|| format!("Empty epoch for {:?}", account))?,
+            None => 0,
+        })
+    }
+
+    /// This has to scan every account's balance, as sled only orders keys and not values, so it's
+    /// linear in the amount of accounts. Explorers needing this often should keep their own index.
+    async fn accounts_with_min_balance(&mut self, min: Raw) -> anyhow::Result<Vec<(Public, Raw)>> {
+        let mut accounts = vec![];
+        for entry in self.accounts.iter() {
+            let (account, balance) = entry?;
+            let balance = Raw::try_from(balance.as_ref())?;
+            if balance >= min {
+                accounts.push((Public::try_from(account.as_ref())?, balance));
+            }
+        }
+        accounts.sort_by_key(|(_, balance)| std::cmp::Reverse(balance.to_u128()));
+        Ok(accounts)
+    }
+
+    /// Sled already keeps the keys of the accounts tree in byte order.
+    async fn accounts_ordered(&mut self) -> anyhow::Result<Vec<Public>> {
+        self.accounts
+            .iter()
+            .keys()
+            .map(|account| Ok(Public::try_from(account?.as_ref())?))
+            .collect()
+    }
+
+    async fn total_supply(&mut self) -> anyhow::Result<Raw> {
+        Ok(self.network.genesis_balance())
+    }
+
+    async fn total_block_count(&mut self) -> anyhow::Result<u64> {
+        Ok(self
+            .counters
+            .get(Self::BLOCK_COUNT)?
+            .map(|v| Self::decode_count(&v))
+            .transpose()?
+            .unwrap_or(0))
+    }
+
+    async fn estimated_size_bytes(&mut self) -> anyhow::Result<u64> {
+        Ok(self.db.size_on_disk()?)
     }
 
     async fn account_for_block_hash(
         &mut self,
-        _block_hash: &BlockHash,
+        block_hash: &BlockHash,
     ) -> Result<Option<Public>, anyhow::Error> {
-        unimplemented!()
+        Ok(match self.block_accounts.get(block_hash.as_bytes())? {
+            Some(account) => Some(Public::try_from(account.as_ref())?),
+            None => None,
+        })
+    }
+
+    async fn set_frontier_anchor(
+        &mut self,
+        account: &Public,
+        hash: &BlockHash,
+    ) -> anyhow::Result<()> {
+        self.anchors.insert(account.as_bytes(), hash.as_bytes())?;
+        Ok(())
+    }
+
+    async fn frontier_anchor(&self, account: &Public) -> anyhow::Result<Option<BlockHash>> {
+        Ok(match self.anchors.get(account.as_bytes())? {
+            Some(hash) => Some(BlockHash::try_from(hash.as_ref())?),
+            None => None,
+        })
     }
 
     async fn add_vote(
@@ -82,6 +359,11 @@ impl State for SledDiskState {
         })
     }
 
+    async fn remove_cookie(&mut self, socket_addr: &SocketAddr) -> anyhow::Result<()> {
+        self.cookies.remove(format!("{}", socket_addr))?;
+        Ok(())
+    }
+
     async fn add_peers(&mut self, _addresses: &[SocketAddr]) -> Result<(), anyhow::Error> {
         unimplemented!()
     }
@@ -90,3 +372,226 @@ impl State for SledDiskState {
         unimplemented!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::{BlockType, Link, ValidationState};
+    use crate::node::state::test_helpers::{account, add_genesis, open, send};
+    use crate::Work;
+
+    fn temporary(network: Network) -> SledDiskState {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        SledDiskState::with_db(network, db)
+    }
+
+    #[tokio::test]
+    async fn block_count() {
+        let network = Network::Live;
+        let mut state = temporary(network);
+        assert_eq!(state.total_block_count().await.unwrap(), 0);
+
+        let genesis = add_genesis(&mut state).await;
+        let account = genesis.account().to_owned();
+        state.add_block(&genesis).await.unwrap();
+        assert_eq!(state.total_block_count().await.unwrap(), 1);
+
+        let send = send(&genesis, &account, 100);
+        state.add_block(&send).await.unwrap();
+        assert_eq!(state.total_block_count().await.unwrap(), 2);
+        assert_eq!(
+            state.account_balance(&account).await.unwrap(),
+            Some(Raw::from(100))
+        );
+        assert_eq!(
+            state
+                .get_latest_block_hash_for_account(&account)
+                .await
+                .unwrap(),
+            Some(send.hash().unwrap().to_owned())
+        );
+        assert_eq!(
+            state
+                .account_for_block_hash(genesis.hash().unwrap())
+                .await
+                .unwrap(),
+            Some(account.to_owned())
+        );
+        assert_eq!(
+            state.account_open_block(&account).await.unwrap().as_ref(),
+            genesis.hash().ok()
+        );
+        assert_eq!(
+            state.block_height(send.hash().unwrap()).await.unwrap(),
+            Some(2)
+        );
+
+        state
+            .counters
+            .insert(SledDiskState::BLOCK_COUNT, vec![1u8; 3])
+            .unwrap();
+        assert!(state.total_block_count().await.is_err());
+        assert!(state.increment_block_count().is_err());
+    }
+
+    #[tokio::test]
+    async fn account_block_count() {
+        let network = Network::Live;
+        let mut state = temporary(network);
+        let genesis = network.genesis_block().unwrap();
+        let genesis_account = genesis.account().to_owned();
+        assert_eq!(
+            state.account_block_count(&genesis_account).await.unwrap(),
+            0
+        );
+
+        state.add_block(&genesis).await.unwrap();
+        state.add_block(&genesis).await.unwrap();
+        assert_eq!(
+            state.account_block_count(&genesis_account).await.unwrap(),
+            1
+        );
+
+        let send = send(&genesis, &genesis_account, 100);
+        state.add_block(&send).await.unwrap();
+        assert_eq!(
+            state.account_block_count(&genesis_account).await.unwrap(),
+            2
+        );
+        assert_eq!(state.account_block_count(&account(0)).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn add_block_checked() {
+        let network = Network::Live;
+        let mut state = temporary(network);
+        let genesis = network.genesis_block().unwrap();
+        let account = genesis.account().to_owned();
+        state.add_block_checked(&network, &genesis).await.unwrap();
+        assert_eq!(
+            state
+                .get_block_by_hash(genesis.hash().unwrap())
+                .await
+                .unwrap(),
+            Some(genesis.to_owned())
+        );
+        assert_eq!(state.account_epoch(&account).await.unwrap(), 0);
+
+        let mut send = send(&genesis, &account, 100);
+        send.set_work(Work::zero());
+        assert!(state.add_block_checked(&network, &send).await.is_err());
+        assert_eq!(
+            state.get_block_by_hash(send.hash().unwrap()).await.unwrap(),
+            None
+        );
+
+        let mut bytes = [0u8; 32];
+        bytes[..14].copy_from_slice(b"epoch v2 block");
+        let upgrade = Block::new(
+            BlockType::State,
+            account.to_owned(),
+            Previous::Block(genesis.hash().unwrap().to_owned()),
+            genesis.representative().to_owned(),
+            network.genesis_balance(),
+            Link::Source(BlockHash::try_from(bytes.as_ref()).unwrap()),
+            ValidationState::Valid,
+        );
+        state.add_block(&upgrade).await.unwrap();
+        assert_eq!(state.account_epoch(&account).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn unchecked_promoted_when_parent_arrives() {
+        let network = Network::Live;
+        let mut state = temporary(network);
+        let genesis = network.genesis_block().unwrap();
+        let account = genesis.account().to_owned();
+        let parent = send(&genesis, &account, 200);
+        let child = send(&parent, &account, 100);
+
+        state.add_block(&genesis).await.unwrap();
+        state.add_block(&child).await.unwrap();
+        assert_eq!(
+            state
+                .get_block_by_hash(child.hash().unwrap())
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(state.unchecked.len(), 1);
+
+        state.add_block(&parent).await.unwrap();
+        assert_eq!(
+            state
+                .get_block_by_hash(child.hash().unwrap())
+                .await
+                .unwrap(),
+            Some(child.to_owned())
+        );
+        assert_eq!(
+            state
+                .get_latest_block_hash_for_account(&account)
+                .await
+                .unwrap(),
+            Some(child.hash().unwrap().to_owned())
+        );
+        assert!(state.unchecked.is_empty());
+        assert!(state.add_unchecked(&genesis, false).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn frontier_anchors() {
+        let network = Network::Live;
+        let mut state = temporary(network);
+        let genesis = network.genesis_block().unwrap();
+        let genesis_account = genesis.account().to_owned();
+        let hash = genesis.hash().unwrap().to_owned();
+        assert_eq!(state.frontier_anchor(&genesis_account).await.unwrap(), None);
+
+        let other = account(0);
+        state
+            .import_frontiers(&[(genesis_account.to_owned(), hash.to_owned())])
+            .await
+            .unwrap();
+        assert_eq!(
+            state.frontier_anchor(&genesis_account).await.unwrap(),
+            Some(hash.to_owned())
+        );
+        assert_eq!(state.frontier_anchor(&other).await.unwrap(), None);
+
+        state.add_block(&genesis).await.unwrap();
+        let info = state.account_info(&genesis_account).await.unwrap().unwrap();
+        assert_eq!(info.confirmation_height, 1);
+        assert_eq!(info.confirmation_height_frontier, Some(hash));
+    }
+
+    #[tokio::test]
+    async fn accounts_with_min_balance() {
+        let network = Network::Live;
+        let mut state = temporary(network);
+        let genesis = add_genesis(&mut state).await;
+        for (index, balance) in &[(0, 100), (1, 50), (2, 10)] {
+            let open = open(&account(*index), genesis.account(), *balance, &genesis);
+            state.add_block(&open).await.unwrap();
+        }
+
+        let accounts = state
+            .accounts_with_min_balance(Raw::from(50))
+            .await
+            .unwrap();
+        assert_eq!(
+            accounts,
+            vec![
+                (genesis.account().to_owned(), network.genesis_balance()),
+                (account(0), Raw::from(100)),
+                (account(1), Raw::from(50)),
+            ]
+        );
+
+        let ordered = state.accounts_ordered().await.unwrap();
+        assert_eq!(ordered.len(), 4);
+        assert!(ordered
+            .windows(2)
+            .all(|pair| pair[0].as_bytes() < pair[1].as_bytes()));
+    }
+}
@@ -0,0 +1,225 @@
+use crate::{Public, Raw, Version};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+/// Estimate the online voting weight of the network from votes observed over a recent window.
+///
+/// Each vote is a representative and the timestamp (or sequence) it was seen with. A
+/// representative is only counted once no matter how many votes it sent, and representatives
+/// without a known weight are ignored.
+pub fn online_weight(votes: &[(Public, u64)], rep_weights: &HashMap<Public, Raw>) -> Raw {
+    let mut seen = HashSet::new();
+    let mut total = Raw::zero();
+    for (rep, _) in votes {
+        if !seen.insert(rep) {
+            continue;
+        }
+        if let Some(weight) = rep_weights.get(rep) {
+            total = total.checked_add(weight).unwrap_or_else(Raw::max);
+        }
+    }
+    total
+}
+
+/// The protocol version that controls consensus, i.e. the weight weighted median of each peer's
+/// version weighted by its representative weight.
+///
+/// When this reaches a new version, at least half of the online weight has upgraded. Returns
+/// `None` when there's no weight at all.
+pub fn weighted_median_version(versions: &[(Version, Raw)]) -> Option<Version> {
+    let mut versions = versions.to_vec();
+    versions.sort_by_key(|(version, _)| *version as u8);
+
+    let total = versions.iter().fold(0u128, |acc, (_, weight)| {
+        acc.saturating_add(weight.to_u128())
+    });
+    if total == 0 {
+        return None;
+    }
+
+    let mut cumulative = 0u128;
+    for (version, weight) in versions {
+        cumulative = cumulative.saturating_add(weight.to_u128());
+        // Comparing halves instead of doubling to avoid an overflow.
+        if cumulative >= total / 2 + total % 2 {
+            return Some(version);
+        }
+    }
+    None
+}
+
+/// Score an account from 0.0 to 1.0 by how well it's delegated and how much it holds.
+///
+/// Half of the score is from the representative being in `trusted`, and the other half is the
+/// balance relative to `max_balance`, e.g. the largest balance among the accounts being compared.
+pub fn trust_score(
+    balance: &Raw,
+    representative: &Public,
+    trusted: &HashSet<Public>,
+    max_balance: &Raw,
+) -> f64 {
+    let delegation = if trusted.contains(representative) {
+        1.0
+    } else {
+        0.0
+    };
+    let holdings = if max_balance.to_u128() == 0 {
+        0.0
+    } else {
+        (balance.to_u128() as f64 / max_balance.to_u128() as f64).min(1.0)
+    };
+    (delegation + holdings) / 2.0
+}
+
+/// A representative which already holds too much of the network weight, and the wallet
+/// accounts delegating to it. See [concentration_warnings].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConcentrationWarning {
+    pub representative: Public,
+
+    /// The representative's share of the total weight, from 0.0 to 1.0.
+    pub share: f64,
+
+    pub accounts: Vec<Public>,
+}
+
+/// Warn about wallet accounts delegating to representatives holding more than `max_share` (from
+/// 0.0 to 1.0) of `total_weight`, e.g. the online weight, so they can pick a smaller one instead.
+///
+/// `delegations` are `(account, representative)` pairs of the wallet. Warnings are sorted by share,
+/// highest first.
+pub fn concentration_warnings(
+    delegations: &[(Public, Public)],
+    rep_weights: &HashMap<Public, Raw>,
+    total_weight: &Raw,
+    max_share: f64,
+) -> Vec<ConcentrationWarning> {
+    if total_weight.to_u128() == 0 {
+        return vec![];
+    }
+
+    let mut warnings: Vec<ConcentrationWarning> = vec![];
+    for (account, rep) in delegations {
+        if let Some(warning) = warnings.iter_mut().find(|w| &w.representative == rep) {
+            warning.accounts.push(account.to_owned());
+            continue;
+        }
+        let weight = match rep_weights.get(rep) {
+            Some(weight) => weight,
+            None => continue,
+        };
+        let share = weight.to_u128() as f64 / total_weight.to_u128() as f64;
+        if share > max_share {
+            warnings.push(ConcentrationWarning {
+                representative: rep.to_owned(),
+                share,
+                accounts: vec![account.to_owned()],
+            });
+        }
+    }
+    warnings.sort_by(|a, b| b.share.partial_cmp(&a.share).unwrap_or(Ordering::Equal));
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Seed;
+
+    fn rep(index: u32) -> Public {
+        Seed::zero().derive(index).to_public().unwrap()
+    }
+
+    #[test]
+    fn subset_of_reps_voting() {
+        let mut rep_weights = HashMap::new();
+        rep_weights.insert(rep(0), Raw::from(1000));
+        rep_weights.insert(rep(1), Raw::from(200));
+        rep_weights.insert(rep(2), Raw::from(30));
+        rep_weights.insert(rep(3), Raw::from(4));
+
+        // Rep 1 voted twice and rep 9 has no known weight.
+        let votes = vec![
+            (rep(0), 1),
+            (rep(1), 2),
+            (rep(1), 3),
+            (rep(3), 4),
+            (rep(9), 5),
+        ];
+        assert_eq!(online_weight(&votes, &rep_weights), Raw::from(1004 + 200));
+        assert_eq!(online_weight(&[], &rep_weights), Raw::zero());
+    }
+
+    #[test]
+    fn weighted_median() {
+        let versions = vec![
+            (Version::V18, Raw::from(30)),
+            (Version::V19, Raw::from(40)),
+            (Version::V18, Raw::from(5)),
+            (Version::V19, Raw::from(40)),
+        ];
+        assert_eq!(weighted_median_version(&versions), Some(Version::V19));
+
+        // Many V19 peers, but V18 still holds most of the weight.
+        let versions = vec![
+            (Version::V19, Raw::from(10)),
+            (Version::V19, Raw::from(10)),
+            (Version::V19, Raw::from(10)),
+            (Version::V18, Raw::from(31)),
+        ];
+        assert_eq!(weighted_median_version(&versions), Some(Version::V18));
+
+        assert_eq!(weighted_median_version(&[]), None);
+        assert_eq!(
+            weighted_median_version(&[(Version::V19, Raw::zero())]),
+            None
+        );
+    }
+
+    #[test]
+    fn trust() {
+        let mut trusted = HashSet::new();
+        trusted.insert(rep(0));
+        let max = Raw::from(1000);
+
+        let well_delegated = trust_score(&Raw::from(500), &rep(0), &trusted, &max);
+        let poorly_delegated = trust_score(&Raw::from(500), &rep(1), &trusted, &max);
+        assert!((well_delegated - 0.75).abs() < f64::EPSILON);
+        assert!((poorly_delegated - 0.25).abs() < f64::EPSILON);
+
+        assert_eq!(trust_score(&max, &rep(0), &trusted, &max), 1.0);
+        assert_eq!(
+            trust_score(&Raw::zero(), &rep(1), &trusted, &Raw::zero()),
+            0.0
+        );
+    }
+
+    #[test]
+    fn concentration() {
+        let mut rep_weights = HashMap::new();
+        rep_weights.insert(rep(0), Raw::from(400));
+        rep_weights.insert(rep(1), Raw::from(150));
+        rep_weights.insert(rep(2), Raw::from(50));
+        let total = Raw::from(1000);
+
+        let delegations = vec![
+            (rep(10), rep(0)),
+            (rep(11), rep(2)),
+            (rep(12), rep(0)),
+            (rep(13), rep(1)),
+            (rep(14), rep(9)),
+        ];
+        let warnings = concentration_warnings(&delegations, &rep_weights, &total, 0.1);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].representative, rep(0));
+        assert_eq!(warnings[0].accounts, vec![rep(10), rep(12)]);
+        assert!((warnings[0].share - 0.4).abs() < f64::EPSILON);
+        assert_eq!(warnings[1].representative, rep(1));
+        assert_eq!(warnings[1].accounts, vec![rep(13)]);
+
+        let warnings = concentration_warnings(&delegations, &rep_weights, &total, 0.2);
+        assert_eq!(warnings.len(), 1);
+        assert!(concentration_warnings(&delegations, &rep_weights, &total, 0.5).is_empty());
+        assert!(concentration_warnings(&delegations, &rep_weights, &Raw::zero(), 0.1).is_empty());
+    }
+}
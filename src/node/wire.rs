@@ -15,3 +15,110 @@ pub trait Wire: Debug {
     where
         Self: Sized;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::{BlockHolder, BlockType, SendBlock, StateBlock};
+    use crate::node::cookie::Cookie;
+    use crate::node::header::{Extensions, MessageType};
+    use crate::node::messages::confirm_ack::ConfirmAck;
+    use crate::node::messages::confirm_req::ConfirmReq;
+    use crate::node::messages::frontier_req::FrontierReq;
+    use crate::node::messages::frontier_resp::FrontierResp;
+    use crate::node::messages::handshake::{Handshake, HandshakeQuery, HandshakeResponse};
+    use crate::node::messages::keepalive::Keepalive;
+    use crate::node::messages::publish::Publish;
+    use crate::node::messages::telemetry_ack::TelemetryAck;
+    use crate::node::peer_info::PeerInfo;
+    use crate::Network;
+
+    fn header(message_type: MessageType, ext: &mut Extensions) -> Header {
+        Header::new(Network::Live, message_type, *ext)
+    }
+
+    /// Every length shorter than expected should be an error instead of a panic.
+    fn assert_rejects_truncated<T: Wire>(header: Option<&Header>) {
+        let len = T::len(header).unwrap();
+        for short in 0..len {
+            let data = vec![1u8; short];
+            assert!(
+                T::deserialize(header, &data).is_err(),
+                "{} accepted {} of {} bytes",
+                std::any::type_name::<T>(),
+                short,
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn truncated_input() {
+        assert_rejects_truncated::<Header>(None);
+        assert_rejects_truncated::<PeerInfo>(None);
+        assert_rejects_truncated::<Cookie>(None);
+        assert_rejects_truncated::<FrontierReq>(None);
+        assert_rejects_truncated::<FrontierResp>(None);
+        assert_rejects_truncated::<TelemetryAck>(None);
+        assert_rejects_truncated::<HandshakeQuery>(None);
+        assert_rejects_truncated::<HandshakeResponse>(None);
+
+        let h = header(MessageType::Handshake, Extensions::new().query().response());
+        assert_rejects_truncated::<Handshake>(Some(&h));
+        let h = header(MessageType::Handshake, Extensions::new().response());
+        assert_rejects_truncated::<Handshake>(Some(&h));
+
+        let h = header(MessageType::Keepalive, &mut Extensions::new());
+        assert_rejects_truncated::<Keepalive>(Some(&h));
+
+        let h = header(
+            MessageType::ConfirmAck,
+            Extensions::new()
+                .set_block_type(BlockType::NotABlock)
+                .set_item_count(2),
+        );
+        assert_rejects_truncated::<ConfirmAck>(Some(&h));
+
+        let h = header(
+            MessageType::ConfirmReq,
+            Extensions::new()
+                .set_block_type(BlockType::NotABlock)
+                .set_item_count(2),
+        );
+        assert_rejects_truncated::<ConfirmReq>(Some(&h));
+        let h = header(
+            MessageType::ConfirmReq,
+            Extensions::new()
+                .set_block_type(BlockType::NotABlock)
+                .set_item_count(2)
+                .root_only(),
+        );
+        assert_rejects_truncated::<ConfirmReq>(Some(&h));
+
+        for block_type in vec![
+            BlockType::State,
+            BlockType::Send,
+            BlockType::Receive,
+            BlockType::Open,
+            BlockType::Change,
+        ] {
+            let h = header(
+                MessageType::Publish,
+                Extensions::new().set_block_type(block_type),
+            );
+            assert_rejects_truncated::<Publish>(Some(&h));
+            assert_rejects_truncated::<BlockHolder>(Some(&h));
+        }
+
+        let h = header(
+            MessageType::Publish,
+            Extensions::new().set_block_type(BlockType::State),
+        );
+        assert_rejects_truncated::<StateBlock>(Some(&h));
+        let h = header(
+            MessageType::Publish,
+            Extensions::new().set_block_type(BlockType::Send),
+        );
+        assert_rejects_truncated::<SendBlock>(Some(&h));
+    }
+}
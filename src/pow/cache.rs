@@ -0,0 +1,117 @@
+use crate::blocks::BlockHash;
+use crate::pow::difficulty::Difficulty;
+use crate::pow::work::{Subject, Work};
+use crate::Network;
+use anyhow::Context;
+use std::collections::HashMap;
+use std::path::Path;
+use tokio::fs::File;
+
+/// Precomputed [Work] by root, i.e. the previous block hash or the public key of an open block.
+///
+/// The cache can be saved to disk so a restarted wallet doesn't need to compute work again.
+#[derive(Debug, Clone)]
+pub struct WorkCache {
+    network: Network,
+    threshold: Difficulty,
+    works: HashMap<BlockHash, Work>,
+}
+
+impl WorkCache {
+    pub fn new(network: Network, threshold: Difficulty) -> Self {
+        Self {
+            network,
+            threshold,
+            works: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, root: BlockHash, work: Work) {
+        self.works.insert(root, work);
+    }
+
+    pub fn get(&self, root: &BlockHash) -> Option<&Work> {
+        self.works.get(root)
+    }
+
+    /// Remove the work once it's been used, since the root will no longer be a frontier.
+    pub fn remove(&mut self, root: &BlockHash) -> Option<Work> {
+        self.works.remove(root)
+    }
+
+    pub fn len(&self) -> usize {
+        self.works.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.works.is_empty()
+    }
+
+    /// Load a cache saved with [WorkCache::save].
+    ///
+    /// Entries that don't pass `threshold` on `network` are dropped instead of being trusted.
+    pub async fn load<P: AsRef<Path>>(
+        path: P,
+        network: Network,
+        threshold: Difficulty,
+    ) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .await
+            .with_context(|| format!("Opening {:?}", path))?;
+        let works: HashMap<BlockHash, Work> = serde_json::from_reader(&file.into_std().await)
+            .with_context(|| format!("Decoding work cache {:?}", path))?;
+
+        let mut cache = Self::new(network, threshold);
+        for (root, work) in works {
+            let subject = Subject::Hash(root.to_owned());
+            if work.verify(&cache.network, &subject, &cache.threshold)? {
+                cache.insert(root, work);
+            }
+        }
+        Ok(cache)
+    }
+
+    pub async fn save<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .await
+            .with_context(|| format!("Creating file {:?}", path))?;
+        Ok(serde_json::to_writer_pretty(
+            file.into_std().await,
+            &self.works,
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn save_and_load() {
+        let network = Network::Live;
+        let threshold = Difficulty::from_str("fff0000000000000").unwrap();
+        let root = |s: &str| BlockHash::from_str(s).unwrap();
+        let valid = root("991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948");
+        let invalid = root("E89208DD038FBB269987689621D52292AE9C35941A7484756ECCED92A65093BA");
+
+        let mut cache = WorkCache::new(network, threshold.to_owned());
+        let work = Work::generate(&network, &Subject::Hash(valid.to_owned()), &threshold).unwrap();
+        cache.insert(valid.to_owned(), work.to_owned());
+        assert!(!Work::zero()
+            .verify(&network, &Subject::Hash(invalid.to_owned()), &threshold)
+            .unwrap());
+        cache.insert(invalid.to_owned(), Work::zero());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("save_and_load.work");
+        cache.save(&path).await.unwrap();
+        let loaded = WorkCache::load(&path, network, threshold).await.unwrap();
+
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded.get(&valid), Some(&work));
+        assert_eq!(loaded.get(&invalid), None);
+    }
+}
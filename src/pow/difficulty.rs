@@ -1,3 +1,4 @@
+use crate::blocks::Subtype;
 use crate::encoding::{deserialize_from_str, expect_len, to_hex};
 use crate::{Error, Result};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -25,6 +26,11 @@ impl Difficulty {
         Self::from_str("FFFFFFF800000000").unwrap()
     }
 
+    /// The threshold for every block of an account before it's upgraded to epoch 2.
+    pub fn epoch_1() -> Self {
+        Self::from_str("FFFFFFC000000000").unwrap()
+    }
+
     pub fn from_fixed_slice(s: &[u8; Self::LEN]) -> Result<Self> {
         Ok(Difficulty(u64::from_le_bytes(*s)))
     }
@@ -43,6 +49,30 @@ impl Difficulty {
     pub fn as_u64(&self) -> u64 {
         self.0
     }
+
+    /// The work threshold for a block of this subtype on an account at `epoch`, see
+    /// [crate::blocks::epoch_version].
+    ///
+    /// From epoch 2 receiving, including opening, is cheaper. Before that every block has the same
+    /// threshold.
+    pub fn for_subtype(subtype: &Subtype, epoch: u8) -> Self {
+        if epoch < 2 {
+            return Self::epoch_1();
+        }
+        match subtype {
+            Subtype::Receive | Subtype::Open => Self::receive(),
+            Subtype::Send | Subtype::Change | Subtype::Epoch => Self::normal(),
+        }
+    }
+
+    /// The highest threshold needed by any of `subtypes` at `epoch`, so work for a batch of blocks
+    /// can target a single difficulty. Returns `None` without any subtypes.
+    pub fn highest_for(subtypes: &[Subtype], epoch: u8) -> Option<Self> {
+        subtypes
+            .iter()
+            .map(|subtype| Self::for_subtype(subtype, epoch))
+            .max_by_key(|difficulty| difficulty.as_u64())
+    }
 }
 
 impl Debug for Difficulty {
@@ -109,5 +139,29 @@ mod tests {
         // These have unwraps in them and so this is a sanity check to make sure it doesn't panic.
         Difficulty::receive();
         Difficulty::normal();
+        Difficulty::epoch_1();
+    }
+
+    #[test]
+    fn highest_for() {
+        let batch = [
+            Subtype::Receive,
+            Subtype::Open,
+            Subtype::Send,
+            Subtype::Receive,
+        ];
+        assert_eq!(
+            Difficulty::highest_for(&batch, 2),
+            Some(Difficulty::normal())
+        );
+        assert_eq!(
+            Difficulty::highest_for(&[Subtype::Receive, Subtype::Open], 2),
+            Some(Difficulty::receive())
+        );
+        assert_eq!(
+            Difficulty::highest_for(&[Subtype::Receive, Subtype::Open], 1),
+            Some(Difficulty::epoch_1())
+        );
+        assert_eq!(Difficulty::highest_for(&[], 2), None);
     }
 }
@@ -1,5 +1,7 @@
+mod cache;
 mod difficulty;
 mod work;
 
+pub use cache::WorkCache;
 pub use difficulty::Difficulty;
-pub use work::{Subject, Work};
+pub use work::{work_hex, Subject, Work};
@@ -1,7 +1,7 @@
 use crate::blocks::BlockHash;
-use crate::encoding::{blake2b, blake2b_callback};
+use crate::encoding::{blake2b_keyed, blake2b_keyed_callback};
 use crate::pow::difficulty::Difficulty;
-use crate::{hexify, Public};
+use crate::{hexify, Network, Public};
 use bytes::Buf;
 use rand::RngCore;
 use std::convert::TryFrom;
@@ -42,57 +42,77 @@ impl Work {
     }
 
     /// Block and generate forever until we find a solution.
-    pub fn generate(subject: &Subject, threshold: &Difficulty) -> anyhow::Result<Work> {
-        let mut work_and_subject = [0u8; 40];
-
-        // We can place the subject in the second part of the slice which will not change.
-        let subject_slice = &mut work_and_subject[Self::LEN..];
+    ///
+    /// The work is hashed with the parameters of the given network, see [Network::work_key].
+    pub fn generate(
+        network: &Network,
+        subject: &Subject,
+        threshold: &Difficulty,
+    ) -> anyhow::Result<Work> {
+        Self::generate_from_nonce_start(network, subject, threshold, rand::random())
+    }
 
-        // This will panic if the source slice is too big. The assertion here is a safety measure
-        // in debug builds.
-        debug_assert_eq!(subject.as_bytes().len(), subject_slice.len());
-        subject.as_bytes().copy_to_slice(subject_slice);
+    /// Tries every nonce in order from `start` until the work meets `threshold`. [Work::generate]
+    /// starts from a random nonce, while a fixed `start` finds the same work every time, e.g. in
+    /// tests.
+    pub fn generate_from_nonce_start(
+        network: &Network,
+        subject: &Subject,
+        threshold: &Difficulty,
+        start: u64,
+    ) -> anyhow::Result<Work> {
+        let mut work_and_subject = [0u8; 40];
+        subject
+            .as_bytes()
+            .copy_to_slice(&mut work_and_subject[Self::LEN..]);
 
         let mut difficulty: Difficulty = Difficulty::new(0);
-
-        // Fill the first 8 bytes with the random work.
-        let work_slice = &mut work_and_subject[0..Self::LEN];
-        rand::thread_rng().fill_bytes(work_slice);
-
+        let mut nonce = start;
         loop {
-            // Pick a random byte position and increment.
-            // I'm guessing this is slightly faster than using fill_bytes for a new set of numbers.
-            // TODO: Bench this guess.
-            let idx = (rand::random::<u8>() % (Self::LEN as u8)) as usize;
-            let c = work_and_subject[idx];
-            work_and_subject[idx] = if c == 0xff { 0 } else { c + 1 };
-
-            blake2b_callback(Self::LEN, &work_and_subject, |b| {
+            // The work is hashed in little endian.
+            work_and_subject[0..Self::LEN].copy_from_slice(&nonce.to_le_bytes());
+            blake2b_keyed_callback(network.work_key(), Self::LEN, &work_and_subject, |b| {
                 difficulty = Difficulty::from_le_slice(b).unwrap();
             });
-            // TODO: Check if this is > or >=
             if &difficulty > threshold {
                 break;
             }
+            nonce = nonce.wrapping_add(1);
         }
 
-        let work_slice = &work_and_subject[0..Self::LEN];
-        let mut work_bytes = Vec::from(work_slice);
-        work_bytes.reverse();
-        let work = Work::try_from(work_bytes.as_slice()).unwrap();
-        return Ok(work);
+        Ok(Work::try_from(nonce.to_be_bytes().as_ref())?)
     }
 
-    pub fn hash(work_and_subject: &[u8]) -> Box<[u8]> {
-        blake2b(Self::LEN, work_and_subject)
+    pub fn hash(network: &Network, work_and_subject: &[u8]) -> Box<[u8]> {
+        blake2b_keyed(network.work_key(), Self::LEN, work_and_subject)
     }
 
-    pub fn verify(&self, subject: &Subject, threshold: &Difficulty) -> anyhow::Result<bool> {
-        let difficulty = self.difficulty(subject)?;
+    pub fn verify(
+        &self,
+        network: &Network,
+        subject: &Subject,
+        threshold: &Difficulty,
+    ) -> anyhow::Result<bool> {
+        let difficulty = self.difficulty(network, subject)?;
         Ok(&difficulty > threshold)
     }
 
-    pub fn difficulty(&self, subject: &Subject) -> anyhow::Result<Difficulty> {
+    /// Check the work against an active difficulty reported by a node, e.g. from the
+    /// `active_difficulty` RPC, instead of a static threshold.
+    ///
+    /// During spam periods the active difficulty rises above the base threshold, so work that
+    /// passes [Work::verify] might still be outprioritised and need to be regenerated.
+    pub fn meets_network_difficulty(
+        &self,
+        network: &Network,
+        root: &Subject,
+        active_difficulty: u64,
+    ) -> bool {
+        self.verify(network, root, &Difficulty::new(active_difficulty))
+            .unwrap_or(false)
+    }
+
+    pub fn difficulty(&self, network: &Network, subject: &Subject) -> anyhow::Result<Difficulty> {
         let mut work_and_subject = Vec::with_capacity(40);
 
         // For some reason this is reversed!
@@ -101,11 +121,15 @@ impl Work {
 
         work_and_subject.extend_from_slice(&reversed_work);
         work_and_subject.extend_from_slice(subject.as_bytes());
-        let hash = Self::hash(&work_and_subject);
+        let hash = Self::hash(network, &work_and_subject);
         Difficulty::from_le_slice(hash.as_ref())
     }
 
-    pub fn difficulty_block_hash(&self, block_hash: &BlockHash) -> anyhow::Result<Difficulty> {
+    pub fn difficulty_block_hash(
+        &self,
+        network: &Network,
+        block_hash: &BlockHash,
+    ) -> anyhow::Result<Difficulty> {
         let mut work_and_block_hash = Vec::with_capacity(40);
 
         // For some reason this is reversed!
@@ -114,15 +138,46 @@ impl Work {
 
         work_and_block_hash.extend_from_slice(&reversed_work);
         work_and_block_hash.extend_from_slice(block_hash.as_bytes());
-        let hash = Self::hash(&work_and_block_hash);
+        let hash = Self::hash(network, &work_and_block_hash);
         Difficulty::from_le_slice(hash.as_ref())
     }
 }
 
+/// Serde helpers for an optional [Work] in JSON, e.g. `#[serde(with = "feeless::work_hex")]`.
+///
+/// Nodes use lowercase hex in the same byte order as [Work::as_bytes], e.g. `"8a142e07a10996d5"`,
+/// regardless of the byte order used for the block on the wire. Any case is accepted when
+/// deserializing.
+pub mod work_hex {
+    use super::Work;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use std::str::FromStr;
+
+    pub fn serialize<S>(work: &Option<Work>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match work {
+            Some(work) => serializer.serialize_str(&work.as_hex_lower()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Work>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s: Option<String> = Deserialize::deserialize(deserializer)?;
+        s.map(|s| Work::from_str(&s).map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::Seed;
+    use std::convert::TryInto;
     use std::str::FromStr;
 
     #[test]
@@ -164,10 +219,10 @@ mod tests {
             let subject = Subject::Hash(hash);
             let work = Work::from_str(work).unwrap();
             let expected_difficulty = Difficulty::from_str(expected_difficulty).unwrap();
-            let difficulty = work.difficulty(&subject).unwrap();
+            let difficulty = work.difficulty(&Network::Live, &subject).unwrap();
             assert_eq!(difficulty, expected_difficulty, "{:?}", &fixture);
             assert_eq!(
-                work.verify(&subject, &threshold).unwrap(),
+                work.verify(&Network::Live, &subject, &threshold).unwrap(),
                 *is_enough_work,
                 "{:?}",
                 &fixture
@@ -189,8 +244,48 @@ mod tests {
         let public = Seed::zero().derive(0).to_public().unwrap();
         dbg!(&public);
         let subject = Subject::Public(public);
-        let work = Work::generate(&subject, &threshold).unwrap();
+        let work = Work::generate(&Network::Live, &subject, &threshold).unwrap();
         dbg!(&work);
-        assert!(work.verify(&subject, &threshold).unwrap());
+        assert!(work.verify(&Network::Live, &subject, &threshold).unwrap());
+    }
+
+    #[test]
+    fn generate_work_per_network() {
+        let threshold = Difficulty::from_str("ff00000000000000").unwrap();
+        let public = Seed::zero().derive(0).to_public().unwrap();
+        let subject = Subject::Public(public);
+        for network in &[Network::Test, Network::Beta, Network::Live] {
+            let work = Work::generate(network, &subject, &threshold).unwrap();
+            assert!(work.verify(network, &subject, &threshold).unwrap());
+        }
+    }
+
+    #[test]
+    fn generate_from_nonce_start() {
+        let threshold = Difficulty::from_str("ff00000000000000").unwrap();
+        let public = Seed::zero().derive(0).to_public().unwrap();
+        let subject = Subject::Public(public);
+        let start = 0x1234_5678_0000_0000;
+        let work =
+            Work::generate_from_nonce_start(&Network::Live, &subject, &threshold, start).unwrap();
+        assert!(work.verify(&Network::Live, &subject, &threshold).unwrap());
+        assert!(u64::from_be_bytes(work.as_bytes().try_into().unwrap()) >= start);
+
+        let again =
+            Work::generate_from_nonce_start(&Network::Live, &subject, &threshold, start).unwrap();
+        assert_eq!(work, again);
+    }
+
+    #[test]
+    fn meets_network_difficulty() {
+        let base = Difficulty::from_str("ff00000000000000").unwrap();
+        let public = Seed::zero().derive(0).to_public().unwrap();
+        let subject = Subject::Public(public);
+        let work = Work::generate(&Network::Live, &subject, &base).unwrap();
+        assert!(work.meets_network_difficulty(&Network::Live, &subject, base.as_u64()));
+
+        // The network is busy and now demands more than this work happened to reach.
+        let elevated = work.difficulty(&Network::Live, &subject).unwrap();
+        assert!(!work.meets_network_difficulty(&Network::Live, &subject, elevated.as_u64()));
     }
 }
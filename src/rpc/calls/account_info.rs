@@ -65,24 +65,24 @@ pub struct AccountInfoResponse {
     pub modified_timestamp: chrono::DateTime<Utc>,
 
     #[serde(deserialize_with = "from_str", serialize_with = "as_str")]
-    block_count: u64,
+    pub block_count: u64,
 
     #[serde(deserialize_with = "from_str", serialize_with = "as_str")]
-    confirmation_height: u64,
+    pub confirmation_height: u64,
 
-    confirmation_height_frontier: BlockHash,
+    pub confirmation_height_frontier: BlockHash,
 
     #[serde(deserialize_with = "from_str", serialize_with = "as_str")]
-    account_version: u64,
+    pub account_version: u64,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    representative: Option<Address>,
+    pub representative: Option<Address>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    weight: Option<Raw>,
+    pub weight: Option<Raw>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
-    pending: Option<Raw>,
+    pub pending: Option<Raw>,
 }
 
 #[cfg(test)]
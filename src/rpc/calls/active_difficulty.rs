@@ -39,6 +39,39 @@ pub struct ActiveDifficultyResponse {
     pub network_receive_minimum: Difficulty,
 }
 
+/// The difficulties from [ActiveDifficultyResponse] as numbers, e.g. to target work generation
+/// with [crate::Work::meets_network_difficulty] during spam periods.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActiveDifficulty {
+    pub current: u64,
+    pub base: u64,
+    pub receive_current: u64,
+    pub receive_base: u64,
+
+    /// How many times harder than the base the current difficulty is.
+    pub multiplier: f64,
+}
+
+impl ActiveDifficultyResponse {
+    pub fn to_active_difficulty(&self) -> ActiveDifficulty {
+        ActiveDifficulty {
+            current: self.network_current.as_u64(),
+            base: self.network_minimum.as_u64(),
+            receive_current: self.network_receive_current.as_u64(),
+            receive_base: self.network_receive_minimum.as_u64(),
+            multiplier: self.multiplier,
+        }
+    }
+}
+
+impl RPCClient {
+    /// The network's current and base work difficulties.
+    pub async fn active_difficulty(&self) -> Result<ActiveDifficulty> {
+        let response = (&ActiveDifficultyRequest::new()).call(self).await?;
+        Ok(response.to_active_difficulty())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,4 +89,28 @@ mod tests {
         let r2 = serde_json::from_str(&json).unwrap();
         assert_eq!(r1, r2);
     }
+
+    #[test]
+    fn decode() {
+        let s = r#"{
+            "deprecated": "1",
+            "network_minimum": "fffffff800000000",
+            "network_receive_minimum": "fffffe0000000000",
+            "network_current": "fffffffa9a4c4b3c",
+            "network_receive_current": "fffffe9a4c4b3c2e",
+            "multiplier": "1.5332939637925795"
+        }"#;
+
+        let r = serde_json::from_str::<ActiveDifficultyResponse>(s).unwrap();
+        assert_eq!(
+            r.to_active_difficulty(),
+            ActiveDifficulty {
+                current: 0xfffffffa9a4c4b3c,
+                base: 0xfffffff800000000,
+                receive_current: 0xfffffe9a4c4b3c2e,
+                receive_base: 0xfffffe0000000000,
+                multiplier: 1.5332939637925795,
+            }
+        );
+    }
 }
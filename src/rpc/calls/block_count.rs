@@ -45,6 +45,21 @@ pub struct BlockCountResponse {
     cemented: Option<u64>,
 }
 
+impl BlockCountResponse {
+    /// `(count, unchecked, cemented)`, with cemented as 0 when the node didn't include it.
+    pub fn to_tuple(&self) -> (u64, u64, u64) {
+        (self.count, self.unchecked, self.cemented.unwrap_or(0))
+    }
+}
+
+impl RPCClient {
+    /// The node's `(count, unchecked, cemented)` block counts, e.g. to monitor its health.
+    pub async fn block_count(&self) -> Result<(u64, u64, u64)> {
+        let response = (&BlockCountRequest::new()).call(self).await?;
+        Ok(response.to_tuple())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -67,6 +82,7 @@ mod tests {
                 cemented: None,
             }
         );
+        assert_eq!(r.to_tuple(), (1000, 10, 0));
     }
 
     #[test]
@@ -88,5 +104,6 @@ mod tests {
                 cemented: Some(25),
             }
         );
+        assert_eq!(r.to_tuple(), (1000, 10, 25));
     }
 }
@@ -0,0 +1,124 @@
+use crate::blocks::BlockHash;
+use crate::rpc::client::{RPCClient, RPCRequest};
+use crate::{Address, Public, Result};
+use async_trait::async_trait;
+use clap::Clap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+#[derive(Debug, Serialize, Deserialize, Clap)]
+pub struct FrontiersRequest {
+    /// The account to start from, in ledger order.
+    pub account: Address,
+
+    /// Limit the number of results to `count`.
+    #[clap(short, long, default_value = "1")]
+    pub count: u64,
+}
+
+#[async_trait]
+impl RPCRequest for &FrontiersRequest {
+    type Response = FrontiersResponse;
+
+    fn action(&self) -> &str {
+        "frontiers"
+    }
+
+    async fn call(&self, client: &RPCClient) -> Result<FrontiersResponse> {
+        client.rpc(self).await
+    }
+}
+
+impl FrontiersRequest {
+    pub fn new(account: Address, count: u64) -> Self {
+        Self { account, count }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct FrontiersResponse {
+    pub frontiers: HashMap<Address, BlockHash>,
+}
+
+impl FrontiersResponse {
+    /// The frontiers as typed pairs, in ledger order.
+    ///
+    /// Fails if the node returned an address which doesn't decode.
+    pub fn to_pairs(&self) -> Result<Vec<(Public, BlockHash)>> {
+        let mut pairs: Vec<(Public, BlockHash)> = self
+            .frontiers
+            .iter()
+            .map(|(address, hash)| Ok((Public::try_from(address)?, hash.to_owned())))
+            .collect::<Result<_>>()?;
+        pairs.sort_by(|(a, _), (b, _)| a.cmp_by_address(b));
+        Ok(pairs)
+    }
+}
+
+impl RPCClient {
+    /// Fetch up to `count` account frontiers starting at `start`.
+    pub async fn frontiers(&self, start: &Address, count: u64) -> Result<Vec<(Public, BlockHash)>> {
+        let response = (&FrontiersRequest::new(start.to_owned(), count))
+            .call(self)
+            .await?;
+        response.to_pairs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn decode() {
+        let s = r#"{
+            "frontiers" : {
+                "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3": "791AF413173EEE674A6FCF633B5DFC0F3C33F397F0DA08E987D9E0741D40D81A",
+                "nano_1111111111111111111111111111111111111111111111111111hifc8npp": "6A32397F4E95AF025DE29D9BF1ACE864D5404362258E06489FABDBA9DCCC046F"
+            }
+        }"#;
+
+        let r = serde_json::from_str::<FrontiersResponse>(s).unwrap();
+        assert_eq!(
+            r.to_pairs().unwrap(),
+            vec![
+                (
+                    Address::from_str(
+                        "nano_1111111111111111111111111111111111111111111111111111hifc8npp"
+                    )
+                    .unwrap()
+                    .to_public(),
+                    BlockHash::from_str(
+                        "6A32397F4E95AF025DE29D9BF1ACE864D5404362258E06489FABDBA9DCCC046F"
+                    )
+                    .unwrap(),
+                ),
+                (
+                    Address::from_str(
+                        "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3"
+                    )
+                    .unwrap()
+                    .to_public(),
+                    BlockHash::from_str(
+                        "791AF413173EEE674A6FCF633B5DFC0F3C33F397F0DA08E987D9E0741D40D81A"
+                    )
+                    .unwrap(),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_address() {
+        let s = r#"{
+            "frontiers" : {
+                "nano_1tgkjkq9r96zd3pg8i3ni1dz6kcntsbsf8aw2xnkmywgpcy6zp7sbju7cydj": "791AF413173EEE674A6FCF633B5DFC0F3C33F397F0DA08E987D9E0741D40D81A"
+            }
+        }"#;
+
+        let r = serde_json::from_str::<FrontiersResponse>(s).unwrap();
+        assert!(r.to_pairs().is_err());
+    }
+}
@@ -16,8 +16,12 @@ mod block_confirm;
 mod block_count;
 mod block_create;
 mod block_info;
+mod frontiers;
 mod peers;
 mod process;
+mod receivable;
+mod representatives_online;
+mod work_generate;
 mod work_validate;
 
 #[cfg(feature = "node")]
@@ -39,8 +43,8 @@ pub use account_representative::{AccountRepresentativeRequest, AccountRepresenta
 pub use account_weight::{AccountWeightRequest, AccountWeightResponse};
 pub use accounts_balances::{AccountsBalancesRequest, AccountsBalancesResponse};
 pub use accounts_frontiers::{AccountsFrontiersRequest, AccountsFrontiersResponse};
-pub use accounts_pending::{AccountsPendingRequest, AccountsPendingResponse};
-pub use active_difficulty::{ActiveDifficultyRequest, ActiveDifficultyResponse};
+pub use accounts_pending::{AccountsPendingRequest, AccountsPendingResponse, BlockEntry};
+pub use active_difficulty::{ActiveDifficulty, ActiveDifficultyRequest, ActiveDifficultyResponse};
 pub use available_supply::{AvailableSupplyRequest, AvailableSupplyResponse};
 pub use block_account::{BlockAccountRequest, BlockAccountResponse};
 pub use block_confirm::{BlockConfirmRequest, BlockConfirmResponse};
@@ -48,12 +52,19 @@ pub use block_count::{BlockCountRequest, BlockCountResponse};
 pub use block_create::{BlockCreateRequest, BlockCreateResponse};
 pub use block_info::{BlockInfoRequest, BlockInfoResponse};
 use clap::Clap;
+pub use frontiers::{FrontiersRequest, FrontiersResponse};
 pub use peers::{DetailedPeerInfo, Peers, PeersRequest, PeersResponse};
-pub use process::{ProcessRequest, ProcessResponse};
+pub use process::{ProcessRequest, ProcessResponse, StateBlockRequest};
+pub use receivable::{ReceivableRequest, ReceivableResponse};
+pub use representatives_online::{
+    RepresentativeWeight, Representatives, RepresentativesOnlineRequest,
+    RepresentativesOnlineResponse,
+};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt::Display;
 use std::ops::Deref;
 use std::str::FromStr;
+pub use work_generate::{WorkGenerateRequest, WorkGenerateResponse};
 pub use work_validate::{WorkValidateRequest, WorkValidateResponse};
 
 #[cfg(any(feature = "node"))]
@@ -85,8 +96,12 @@ pub enum RpcCommand {
     BlockCreate(BlockCreateRequest),
     BlockInfo(BlockInfoRequest),
     BlockConfirm(BlockConfirmRequest),
+    Frontiers(FrontiersRequest),
     Peers(PeersRequest),
     Process(ProcessRequest),
+    Receivable(ReceivableRequest),
+    RepresentativesOnline(RepresentativesOnlineRequest),
+    WorkGenerate(WorkGenerateRequest),
     WorkValidate(WorkValidateRequest),
 }
 
@@ -1,5 +1,5 @@
 use crate::blocks::{deserialize_to_unsure_link, BlockType, StateBlock};
-use crate::blocks::{BlockHash, Link, Subtype};
+use crate::blocks::{BlockHash, Link, Previous, Subtype};
 use crate::rpc::client::{RPCClient, RPCRequest};
 use crate::rpc::AlwaysTrue;
 use crate::{Address, Raw, Result, Signature, Work};
@@ -64,10 +64,35 @@ impl RPCRequest for &ProcessRequest {
 }
 
 impl ProcessRequest {
-    pub fn new(_subtype: Subtype, _block: StateBlock) -> Self {
-        todo!()
+    pub fn new(subtype: Subtype, block: StateBlock) -> Self {
+        Self {
+            json_block: AlwaysTrue::default(),
+            subtype,
+            block: StateBlockRequest::from(block),
+        }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ProcessResponse {}
+impl From<StateBlock> for StateBlockRequest {
+    fn from(block: StateBlock) -> Self {
+        let previous = match block.previous {
+            Previous::Block(hash) => hash,
+            Previous::Open => BlockHash::zero(),
+        };
+        Self {
+            block_type: BlockType::State,
+            account: block.account.to_address(),
+            previous,
+            representative: block.representative.to_address(),
+            balance: block.balance,
+            link: block.link,
+            work: block.work,
+            signature: block.signature,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProcessResponse {
+    pub hash: BlockHash,
+}
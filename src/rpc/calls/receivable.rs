@@ -0,0 +1,238 @@
+use crate::blocks::BlockHash;
+use crate::rpc::calls::BlockEntry;
+use crate::rpc::client::{RPCClient, RPCRequest};
+use crate::{Address, Public, Raw, Result};
+use async_trait::async_trait;
+use clap::Clap;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize, Deserialize, Clap)]
+pub struct ReceivableRequest {
+    pub account: Address,
+
+    /// Limit the number of results to `count`.
+    #[clap(short, long, default_value = "1")]
+    pub count: u64,
+
+    /// Only return blocks with at least this amount.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[clap(short, long)]
+    pub threshold: Option<Raw>,
+
+    #[clap(long)]
+    pub source: bool,
+}
+
+#[async_trait]
+impl RPCRequest for &ReceivableRequest {
+    type Response = ReceivableResponse;
+
+    fn action(&self) -> &str {
+        "receivable"
+    }
+
+    async fn call(&self, client: &RPCClient) -> Result<ReceivableResponse> {
+        client.rpc(self).await
+    }
+}
+
+impl ReceivableRequest {
+    pub fn new(account: Address, count: u64) -> Self {
+        Self {
+            account,
+            count,
+            threshold: None,
+            source: false,
+        }
+    }
+}
+
+/// Only the `source=true` form of the response is supported.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct ReceivableResponse {
+    #[serde(deserialize_with = "empty_string_as_empty_map")]
+    pub blocks: HashMap<BlockHash, BlockEntry>,
+}
+
+impl ReceivableResponse {
+    /// The receivable blocks with at least `threshold`, largest amounts first.
+    pub fn above(&self, threshold: Option<&Raw>) -> Vec<(BlockHash, Raw)> {
+        let mut blocks: Vec<(BlockHash, Raw)> = self
+            .blocks
+            .iter()
+            .filter(|(_, entry)| match threshold {
+                Some(threshold) => &entry.amount >= threshold,
+                None => true,
+            })
+            .map(|(hash, entry)| (hash.to_owned(), entry.amount.to_owned()))
+            .collect();
+        blocks.sort_by_key(|(_, amount)| std::cmp::Reverse(amount.to_u128()));
+        blocks
+    }
+}
+
+/// The node responds with `"blocks": ""` when there's nothing receivable.
+fn empty_string_as_empty_map<'de, D>(
+    deserializer: D,
+) -> std::result::Result<HashMap<BlockHash, BlockEntry>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Blocks {
+        Empty(String),
+        Blocks(HashMap<BlockHash, BlockEntry>),
+    }
+
+    match Blocks::deserialize(deserializer)? {
+        Blocks::Empty(s) if s.is_empty() => Ok(HashMap::new()),
+        Blocks::Empty(s) => Err(serde::de::Error::custom(format!(
+            "Unexpected blocks: {}",
+            s
+        ))),
+        Blocks::Blocks(blocks) => Ok(blocks),
+    }
+}
+
+impl RPCClient {
+    /// Receivable blocks for `account` with their amounts, ignoring amounts below `threshold`.
+    pub async fn receivable(
+        &self,
+        account: &Address,
+        count: u64,
+        threshold: Option<Raw>,
+    ) -> Result<Vec<(BlockHash, Raw)>> {
+        let mut request = ReceivableRequest::new(account.to_owned(), count);
+        request.source = true;
+        request.threshold = threshold;
+        let response = (&request).call(self).await?;
+        Ok(response.above(request.threshold.as_ref()))
+    }
+
+    /// [RPCClient::receivable] for each of a wallet's accounts, e.g. for an auto receiver to go
+    /// through a whole wallet at once.
+    pub async fn wallet_receivables(
+        &self,
+        accounts: &[Public],
+        count: u64,
+        threshold: Option<Raw>,
+    ) -> Result<Vec<(Public, BlockHash, Raw)>> {
+        let mut receivables = vec![];
+        for account in accounts {
+            for (hash, amount) in self
+                .receivable(&account.to_address(), count, threshold.to_owned())
+                .await?
+            {
+                receivables.push((account.to_owned(), hash, amount));
+            }
+        }
+        Ok(receivables)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn decode() {
+        let s = r#"{
+            "blocks": {
+                "142A538F36833D1CC78B94E11C766F75818F8B940771335C6C1B8AB880C5BB1D": {
+                    "amount": "6000000000000000000000000000000",
+                    "source": "nano_3dcfozsmekr1tr9skf1oa5wbgmxt81qepfdnt7zicq5x3hk65fg4fqj58mbr"
+                },
+                "4C1FEEF0BEA7F50BE35489A1233FE002B212DEA554B55B1B470D78BD8F210C74": {
+                    "amount": "100",
+                    "source": "nano_13ezf4od79h1tgj9aiu4djzcmmguendtjfuhwfukhuucboua8cpoihmh8byo"
+                }
+            }
+        }"#;
+
+        let r = serde_json::from_str::<ReceivableResponse>(s).unwrap();
+        let big = (
+            BlockHash::from_str("142A538F36833D1CC78B94E11C766F75818F8B940771335C6C1B8AB880C5BB1D")
+                .unwrap(),
+            Raw::from(6000000000000000000000000000000),
+        );
+        let small = (
+            BlockHash::from_str("4C1FEEF0BEA7F50BE35489A1233FE002B212DEA554B55B1B470D78BD8F210C74")
+                .unwrap(),
+            Raw::from(100),
+        );
+        assert_eq!(r.above(None), vec![big.to_owned(), small]);
+        assert_eq!(r.above(Some(&Raw::from(101))), vec![big]);
+
+        let empty = serde_json::from_str::<ReceivableResponse>(r#"{"blocks": ""}"#).unwrap();
+        assert!(empty.above(None).is_empty());
+    }
+
+    #[cfg(feature = "rpc_server")]
+    #[tokio::test]
+    async fn wallet_receivables() {
+        use crate::rpc::client::mock::MockRPC;
+        use crate::Seed;
+        use serde_json::json;
+
+        let with_blocks = Seed::zero().derive(0).to_public().unwrap();
+        let empty = Seed::zero().derive(1).to_public().unwrap();
+        let other = Seed::zero().derive(2).to_public().unwrap();
+        let with_blocks_address = with_blocks.to_address().to_string();
+        let other_address = other.to_address().to_string();
+        let mock = MockRPC::start(move |request| {
+            let account = request["account"].as_str().unwrap();
+            if account == with_blocks_address {
+                json!({"blocks": {
+                    "142A538F36833D1CC78B94E11C766F75818F8B940771335C6C1B8AB880C5BB1D": {
+                        "amount": "600",
+                        "source": "nano_13ezf4od79h1tgj9aiu4djzcmmguendtjfuhwfukhuucboua8cpoihmh8byo"
+                    },
+                    "4C1FEEF0BEA7F50BE35489A1233FE002B212DEA554B55B1B470D78BD8F210C74": {
+                        "amount": "100",
+                        "source": "nano_13ezf4od79h1tgj9aiu4djzcmmguendtjfuhwfukhuucboua8cpoihmh8byo"
+                    }
+                }})
+            } else if account == other_address {
+                json!({"blocks": {
+                    "570EDFC56651FBBC9AEFE5B0769DBD210614A0C0E6962F5CA0EA2FFF4C08A4B0": {
+                        "amount": "50",
+                        "source": "nano_13ezf4od79h1tgj9aiu4djzcmmguendtjfuhwfukhuucboua8cpoihmh8byo"
+                    }
+                }})
+            } else {
+                json!({"blocks": ""})
+            }
+        });
+
+        let hash = |s| BlockHash::from_str(s).unwrap();
+        let receivables = mock
+            .client()
+            .wallet_receivables(&[with_blocks.to_owned(), empty, other.to_owned()], 10, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            receivables,
+            vec![
+                (
+                    with_blocks.to_owned(),
+                    hash("142A538F36833D1CC78B94E11C766F75818F8B940771335C6C1B8AB880C5BB1D"),
+                    Raw::from(600)
+                ),
+                (
+                    with_blocks,
+                    hash("4C1FEEF0BEA7F50BE35489A1233FE002B212DEA554B55B1B470D78BD8F210C74"),
+                    Raw::from(100)
+                ),
+                (
+                    other,
+                    hash("570EDFC56651FBBC9AEFE5B0769DBD210614A0C0E6962F5CA0EA2FFF4C08A4B0"),
+                    Raw::from(50)
+                ),
+            ]
+        );
+        assert_eq!(mock.requests("receivable").len(), 3);
+    }
+}
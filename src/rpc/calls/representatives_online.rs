@@ -0,0 +1,153 @@
+use crate::rpc::client::{RPCClient, RPCRequest};
+use crate::{Address, Public, Raw, Result};
+use async_trait::async_trait;
+use clap::Clap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+#[derive(Debug, Serialize, Deserialize, Clap)]
+pub struct RepresentativesOnlineRequest {
+    /// Also return the voting weight of each representative.
+    #[clap(short, long)]
+    pub weight: bool,
+}
+
+#[async_trait]
+impl RPCRequest for &RepresentativesOnlineRequest {
+    type Response = RepresentativesOnlineResponse;
+
+    fn action(&self) -> &str {
+        "representatives_online"
+    }
+
+    async fn call(&self, client: &RPCClient) -> Result<RepresentativesOnlineResponse> {
+        client.rpc(self).await
+    }
+}
+
+impl RepresentativesOnlineRequest {
+    pub fn new(weight: bool) -> Self {
+        Self { weight }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RepresentativesOnlineResponse {
+    /// The type depends on [RepresentativesOnlineRequest::weight].
+    pub representatives: Representatives,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum Representatives {
+    Simple(Vec<Address>),
+    Weights(HashMap<Address, RepresentativeWeight>),
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct RepresentativeWeight {
+    pub weight: Raw,
+}
+
+impl RepresentativesOnlineResponse {
+    /// The representatives with their weights if requested, in address order.
+    ///
+    /// Fails if the node returned an address which doesn't decode.
+    pub fn to_pairs(&self) -> Result<Vec<(Public, Option<Raw>)>> {
+        let mut pairs: Vec<(Public, Option<Raw>)> = match &self.representatives {
+            Representatives::Simple(addresses) => addresses
+                .iter()
+                .map(|address| Ok((Public::try_from(address)?, None)))
+                .collect::<Result<_>>()?,
+            Representatives::Weights(weights) => weights
+                .iter()
+                .map(|(address, weight)| {
+                    Ok((Public::try_from(address)?, Some(weight.weight.to_owned())))
+                })
+                .collect::<Result<_>>()?,
+        };
+        pairs.sort_by(|(a, _), (b, _)| a.cmp_by_address(b));
+        Ok(pairs)
+    }
+}
+
+impl RPCClient {
+    /// The representatives the node has seen voting recently, with their weights when `weight` is
+    /// set, e.g. to work out the online weight and quorum.
+    pub async fn representatives_online(&self, weight: bool) -> Result<Vec<(Public, Option<Raw>)>> {
+        let response = (&RepresentativesOnlineRequest::new(weight))
+            .call(self)
+            .await?;
+        response.to_pairs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn public(s: &str) -> Public {
+        Address::from_str(s).unwrap().to_public()
+    }
+
+    #[test]
+    fn decode_weights() {
+        let s = r#"{
+            "representatives": {
+                "nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg": {
+                    "weight": "150462654614686936429917024683496890"
+                },
+                "nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d": {
+                    "weight": "0"
+                }
+            }
+        }"#;
+
+        let r = serde_json::from_str::<RepresentativesOnlineResponse>(s).unwrap();
+        assert_eq!(
+            r.to_pairs().unwrap(),
+            vec![
+                (
+                    public("nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d"),
+                    Some(Raw::zero())
+                ),
+                (
+                    public("nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg"),
+                    Some(Raw::from(150462654614686936429917024683496890))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_simple() {
+        let s = r#"{
+            "representatives": [
+                "nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg"
+            ]
+        }"#;
+
+        let r = serde_json::from_str::<RepresentativesOnlineResponse>(s).unwrap();
+        assert_eq!(
+            r.to_pairs().unwrap(),
+            vec![(
+                public("nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg"),
+                None
+            )]
+        );
+    }
+
+    #[test]
+    fn invalid_address() {
+        let s = r#"{
+            "representatives": [
+                "nano_1tgkjkq9r96zd3pg8i3ni1dz6kcntsbsf8aw2xnkmywgpcy6zp7sbju7cydj"
+            ]
+        }"#;
+
+        let r = serde_json::from_str::<RepresentativesOnlineResponse>(s).unwrap();
+        assert!(r.to_pairs().is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use crate::blocks::BlockHash;
+use crate::rpc::calls::{as_str, from_str};
+use crate::rpc::client::{RPCClient, RPCRequest};
+use crate::{Difficulty, Result, Work};
+use async_trait::async_trait;
+use clap::Clap;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clap)]
+pub struct WorkGenerateRequest {
+    /// The previous block hash, or the public key for an open block.
+    pub hash: BlockHash,
+
+    /// Difficulty to generate work for. Defaults to the node's active difficulty.
+    #[clap(short, long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<Difficulty>,
+}
+
+#[async_trait]
+impl RPCRequest for &WorkGenerateRequest {
+    type Response = WorkGenerateResponse;
+
+    fn action(&self) -> &str {
+        "work_generate"
+    }
+
+    async fn call(&self, client: &RPCClient) -> Result<WorkGenerateResponse> {
+        client.rpc(self).await
+    }
+}
+
+impl WorkGenerateRequest {
+    pub fn new(hash: BlockHash) -> Self {
+        Self {
+            hash,
+            difficulty: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct WorkGenerateResponse {
+    pub work: Work,
+    pub difficulty: Difficulty,
+
+    #[serde(deserialize_with = "from_str", serialize_with = "as_str")]
+    pub multiplier: f64,
+
+    pub hash: BlockHash,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn decode() {
+        let s = r#"
+        {
+            "work": "2b3d689bbcb21dca",
+            "difficulty": "fffffff93c41ec94",
+            "multiplier": "1.182623871097636",
+            "hash": "718CC2121C3E641059BC1C2CFC45666C99E8AE922F7A807B7D07B62C995D79E2"
+        }
+        "#;
+
+        let r = serde_json::from_str::<WorkGenerateResponse>(s).unwrap();
+        assert_eq!(
+            r,
+            WorkGenerateResponse {
+                work: Work::from_str("2b3d689bbcb21dca").unwrap(),
+                difficulty: Difficulty::from_str("fffffff93c41ec94").unwrap(),
+                multiplier: 1.182623871097636,
+                hash: BlockHash::from_str(
+                    "718CC2121C3E641059BC1C2CFC45666C99E8AE922F7A807B7D07B62C995D79E2"
+                )
+                .unwrap(),
+            }
+        );
+    }
+}
@@ -45,8 +45,12 @@ impl RPCClientOpts {
             RpcCommand::BlockCount(c) => self.show(c).await?,
             RpcCommand::BlockCreate(c) => self.show(c).await?,
             RpcCommand::BlockInfo(c) => self.show(c).await?,
+            RpcCommand::Frontiers(c) => self.show(c).await?,
             RpcCommand::Peers(c) => self.show(c).await?,
             RpcCommand::Process(c) => self.show(c).await?,
+            RpcCommand::Receivable(c) => self.show(c).await?,
+            RpcCommand::RepresentativesOnline(c) => self.show(c).await?,
+            RpcCommand::WorkGenerate(c) => self.show(c).await?,
             RpcCommand::WorkValidate(c) => self.show(c).await?,
         };
         Ok(())
@@ -0,0 +1,75 @@
+use crate::rpc::client::RPCClient;
+use serde_json::Value;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use warp::http::StatusCode;
+use warp::Filter;
+
+/// A fake RPC server for tests, which replies to each request using the given handler.
+///
+/// Every request received is kept so tests can check what was sent.
+pub(crate) struct MockRPC {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<Value>>>,
+    failures: Arc<AtomicUsize>,
+}
+
+impl MockRPC {
+    pub(crate) fn start<F>(handler: F) -> Self
+    where
+        F: Fn(&Value) -> Value + Send + Sync + 'static,
+    {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let recorded = requests.clone();
+        let failures = Arc::new(AtomicUsize::new(0));
+        let remaining_failures = failures.clone();
+        let handler = Arc::new(handler);
+        let route = warp::post()
+            .and(warp::body::json())
+            .map(move |request: Value| {
+                let failing = remaining_failures
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                    .is_ok();
+                let (response, status) = if failing {
+                    (Value::Null, StatusCode::SERVICE_UNAVAILABLE)
+                } else {
+                    (handler(&request), StatusCode::OK)
+                };
+                recorded.lock().unwrap().push(request);
+                warp::reply::with_status(warp::reply::json(&response), status)
+            });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        Self {
+            addr,
+            requests,
+            failures,
+        }
+    }
+
+    /// Reply to the next `count` requests with a 503, like a proxy in front of an unreachable
+    /// node.
+    pub(crate) fn fail_next(&self, count: usize) {
+        self.failures.store(count, Ordering::SeqCst);
+    }
+
+    pub(crate) fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub(crate) fn client(&self) -> RPCClient {
+        RPCClient::new(self.url())
+    }
+
+    /// All the requests received so far with the given action.
+    pub(crate) fn requests(&self, action: &str) -> Vec<Value> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request["action"] == action)
+            .cloned()
+            .collect()
+    }
+}
@@ -1,12 +1,20 @@
 mod cli;
+mod retry;
+mod verified;
+mod work;
+
+#[cfg(all(test, feature = "rpc_server"))]
+pub(crate) mod mock;
 
 use crate::{Error, Result};
 use async_trait::async_trait;
 pub(crate) use cli::RPCClientOpts;
+pub use retry::RetryPolicy;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 use tracing::debug;
+pub use work::WorkClient;
 
 #[async_trait]
 pub(crate) trait RPCRequest {
@@ -38,6 +46,7 @@ pub struct RPCError {
 pub struct RPCClient {
     url: String,
     authorization: Option<String>,
+    retry: RetryPolicy,
 }
 
 impl RPCClient {
@@ -46,14 +55,42 @@ impl RPCClient {
         Self {
             url,
             authorization: None,
+            retry: RetryPolicy::none(),
         }
     }
 
+    /// Retry requests which fail to reach the node, see [RetryPolicy].
+    pub fn with_retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = policy;
+        self
+    }
+
     pub fn authorization<S: Into<String>>(&mut self, auth: S) {
         self.authorization = Some(auth.into());
     }
 
     pub(crate) async fn rpc<S, R>(&self, request: &S) -> Result<R>
+    where
+        S: Sized + Serialize + RPCRequest,
+        R: Sized + DeserializeOwned + Debug,
+    {
+        let mut attempt = 0;
+        loop {
+            match self.rpc_once(request).await {
+                Err(err)
+                    if RetryPolicy::is_retryable(&err) && attempt + 1 < self.retry.max_attempts =>
+                {
+                    let delay = self.retry.delay(attempt);
+                    debug!("Retrying in {:?} after: {}", delay, err);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn rpc_once<S, R>(&self, request: &S) -> Result<R>
     where
         S: Sized + Serialize + RPCRequest,
         R: Sized + DeserializeOwned + Debug,
@@ -75,6 +112,13 @@ impl RPCClient {
             .body(body)
             .send()
             .await?;
+        // A proxy in front of the node replies with a 5xx when the node is unreachable, which is
+        // a failed request rather than a bad response.
+        let res = if res.status().is_server_error() {
+            res.error_for_status()?
+        } else {
+            res
+        };
 
         let text = res.text().await?;
         debug!("RECV: {}", text);
@@ -0,0 +1,106 @@
+use crate::Error;
+use std::time::Duration;
+
+/// How [super::RPCClient] retries requests which failed to reach the node.
+///
+/// Only transport errors, e.g. a refused connection, a timeout or a 5xx from a proxy, are retried.
+/// Errors returned by the node itself, like `{"error": "Fork"}`, and unparseable responses are
+/// returned straight away since trying again won't change them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// The total amount of attempts, including the first one.
+    pub max_attempts: u32,
+
+    /// The delay before the first retry, which doubles after each retry.
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, backoff: Duration) -> Self {
+        Self {
+            max_attempts,
+            backoff,
+        }
+    }
+
+    /// A single attempt without retrying.
+    pub fn none() -> Self {
+        Self::new(1, Duration::from_millis(0))
+    }
+
+    /// The delay to wait after the given failed attempt, counting from zero.
+    pub(crate) fn delay(&self, attempt: u32) -> Duration {
+        self.backoff * 2u32.saturating_pow(attempt)
+    }
+
+    pub(crate) fn is_retryable(err: &Error) -> bool {
+        matches!(err, Error::RPCRequestFailed(_))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+#[cfg(all(test, feature = "rpc_server"))]
+mod tests {
+    use super::*;
+    use crate::rpc::calls::{AccountBalanceRequest, AccountInfoRequest};
+    use crate::rpc::client::mock::MockRPC;
+    use crate::rpc::client::{RPCClient, RPCRequest};
+    use crate::Seed;
+    use serde_json::json;
+
+    fn mock() -> MockRPC {
+        MockRPC::start(|request| match request["action"].as_str().unwrap() {
+            "account_balance" => json!({"balance": "100", "pending": "0"}),
+            _ => json!({"error": "Fork"}),
+        })
+    }
+
+    fn client(mock: &MockRPC) -> RPCClient {
+        mock.client()
+            .with_retry(RetryPolicy::new(3, Duration::from_millis(1)))
+    }
+
+    fn request() -> AccountBalanceRequest {
+        AccountBalanceRequest::new(Seed::zero().derive(0).to_address().unwrap())
+    }
+
+    #[test]
+    fn backoff_doubles() {
+        let policy = RetryPolicy::new(4, Duration::from_millis(100));
+        assert_eq!(policy.delay(0), Duration::from_millis(100));
+        assert_eq!(policy.delay(1), Duration::from_millis(200));
+        assert_eq!(policy.delay(2), Duration::from_millis(400));
+    }
+
+    #[tokio::test]
+    async fn succeeds_after_transient_failures() {
+        let mock = mock();
+        mock.fail_next(2);
+        let response = (&request()).call(&client(&mock)).await.unwrap();
+        assert_eq!(response.balance.to_u128(), 100);
+        assert_eq!(mock.requests("account_balance").len(), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let mock = mock();
+        mock.fail_next(3);
+        let result = (&request()).call(&client(&mock)).await;
+        assert!(matches!(result, Err(Error::RPCRequestFailed(_))));
+        assert_eq!(mock.requests("account_balance").len(), 3);
+    }
+
+    #[tokio::test]
+    async fn node_errors_are_not_retried() {
+        let mock = mock();
+        let request = AccountInfoRequest::new(Seed::zero().derive(0).to_address().unwrap());
+        let result = (&request).call(&client(&mock)).await;
+        assert!(matches!(result, Err(Error::RPCError(_))));
+        assert_eq!(mock.requests("account_info").len(), 1);
+    }
+}
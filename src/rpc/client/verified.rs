@@ -0,0 +1,178 @@
+use crate::blocks::{Block, BlockHash, BlockHolder, Previous};
+use crate::rpc::calls::BlockInfoRequest;
+use crate::rpc::client::{RPCClient, RPCRequest};
+use crate::{Error, Public, Result};
+use std::convert::TryFrom;
+
+impl RPCClient {
+    /// Fetch a block with `block_info`, and verify it locally instead of trusting the node.
+    ///
+    /// The hash is calculated from the returned contents and has to match `hash`, and the block
+    /// has to be signed by `expected_account`.
+    pub async fn block_info_verified(
+        &self,
+        hash: &BlockHash,
+        expected_account: &Public,
+    ) -> Result<Block> {
+        let info = (&BlockInfoRequest::new(hash.to_owned())).call(self).await?;
+        let block_account = Public::try_from(&info.block_account).map_err(|err| {
+            Error::BlockVerificationFailed(format!(
+                "Invalid block account {}: {}",
+                info.block_account, err
+            ))
+        })?;
+        if &block_account != expected_account {
+            return Err(Error::BlockVerificationFailed(format!(
+                "Expected account {} but got {}",
+                expected_account.to_address(),
+                info.block_account
+            )));
+        }
+
+        let block = match &info.contents {
+            BlockHolder::State(state_block) => Block::from_state_block(state_block),
+            BlockHolder::Open(open_block) => {
+                Block::from_open_block(open_block, &Previous::Open, &info.balance)
+            }
+            other => {
+                return Err(Error::BlockVerificationFailed(format!(
+                    "Unsupported block type: {:?}",
+                    other
+                )))
+            }
+        };
+
+        let calculated = block
+            .hash()
+            .map_err(|err| Error::BlockVerificationFailed(err.to_string()))?;
+        if calculated != hash {
+            return Err(Error::BlockVerificationFailed(format!(
+                "Requested {} but the contents hash to {}",
+                hash, calculated
+            )));
+        }
+
+        if block.account() != expected_account {
+            return Err(Error::BlockVerificationFailed(format!(
+                "Block belongs to {}",
+                block.account().to_address()
+            )));
+        }
+
+        block
+            .verify_signature(expected_account)
+            .map_err(|err| Error::BlockVerificationFailed(format!("{:#}", err)))?;
+
+        Ok(block)
+    }
+}
+
+#[cfg(all(test, feature = "rpc_server"))]
+mod tests {
+    use super::*;
+    use crate::blocks::{Link, StateBlock};
+    use crate::encoding::to_hex;
+    use crate::rpc::client::mock::MockRPC;
+    use crate::{Raw, Seed};
+    use serde_json::{json, Value};
+    use std::str::FromStr;
+
+    fn signed_block() -> StateBlock {
+        let private = Seed::zero().derive(0);
+        let account = private.to_public().unwrap();
+        let mut block = StateBlock::new(
+            account.to_owned(),
+            Previous::Block(
+                BlockHash::from_str(
+                    "CE898C131AAEE25E05362F247760F8A3ACF34A9796A5AE0D9204E86B0637965E",
+                )
+                .unwrap(),
+            ),
+            account,
+            Raw::from(5000),
+            Link::DestinationAccount(Seed::zero().derive(1).to_public().unwrap()),
+        );
+        block.signature = Some(private.sign(block.hash.as_bytes()).unwrap());
+        block
+    }
+
+    fn block_info(block: &StateBlock, tamper: impl Fn(&mut Value)) -> MockRPC {
+        let contents = json!({
+            "type": "state",
+            "account": block.account.to_address().to_string(),
+            "previous": "CE898C131AAEE25E05362F247760F8A3ACF34A9796A5AE0D9204E86B0637965E",
+            "representative": block.representative.to_address().to_string(),
+            "balance": block.balance.to_string(),
+            "link": to_hex(block.link.as_bytes()),
+            "signature": block.signature.as_ref().unwrap().to_string(),
+            "work": "8a142e07a10996d5"
+        });
+        let mut response = json!({
+            "block_account": block.account.to_address().to_string(),
+            "amount": "1000",
+            "balance": block.balance.to_string(),
+            "height": "58",
+            "local_timestamp": "0",
+            "confirmed": "true",
+            "contents": contents,
+            "subtype": "send"
+        });
+        tamper(&mut response);
+        MockRPC::start(move |_| response.clone())
+    }
+
+    #[tokio::test]
+    async fn verified() {
+        let block = signed_block();
+        let mock = block_info(&block, |_| {});
+        let verified = mock
+            .client()
+            .block_info_verified(&block.hash, &block.account)
+            .await
+            .unwrap();
+        assert_eq!(verified.hash().unwrap(), &block.hash);
+    }
+
+    #[tokio::test]
+    async fn tampered_balance() {
+        let block = signed_block();
+        let mock = block_info(&block, |response| {
+            response["contents"]["balance"] = json!("5000000")
+        });
+        let result = mock
+            .client()
+            .block_info_verified(&block.hash, &block.account)
+            .await;
+        assert!(matches!(result, Err(Error::BlockVerificationFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn tampered_signature() {
+        let block = signed_block();
+        let other = Seed::zero().derive(1).sign(block.hash.as_bytes()).unwrap();
+        let mock = block_info(&block, |response| {
+            response["contents"]["signature"] = json!(other.to_string())
+        });
+        let result = mock
+            .client()
+            .block_info_verified(&block.hash, &block.account)
+            .await;
+        assert!(matches!(result, Err(Error::BlockVerificationFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn bad_block_account_checksum() {
+        let block = signed_block();
+        let mut address = block.account.to_address().to_string();
+        let last = if address.ends_with('1') { "3" } else { "1" };
+        address.replace_range(address.len() - 1.., last);
+        let mock = block_info(&block, |response| {
+            response["block_account"] = json!(address.to_owned())
+        });
+        let result = mock
+            .client()
+            .block_info_verified(&block.hash, &block.account)
+            .await;
+        assert!(matches!(result, Err(Error::BlockVerificationFailed(_))));
+    }
+}
@@ -0,0 +1,102 @@
+use crate::blocks::BlockHash;
+use crate::rpc::calls::WorkGenerateRequest;
+use crate::rpc::client::{RPCClient, RPCRequest};
+use crate::{Difficulty, Error, Result, Work};
+use reqwest::Url;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Generates work with `work_generate` on one of several work servers or nodes.
+///
+/// Servers are tried in order until one succeeds. The last server that succeeded is tried first
+/// on the next request, so a server that's down only slows down the first request.
+pub struct WorkClient {
+    servers: Vec<RPCClient>,
+    preferred: AtomicUsize,
+}
+
+impl WorkClient {
+    pub fn with_servers(servers: Vec<Url>) -> Self {
+        Self {
+            servers: servers
+                .into_iter()
+                .map(|url| RPCClient::new(url.as_str()))
+                .collect(),
+            preferred: AtomicUsize::new(0),
+        }
+    }
+
+    /// Generate work for `hash`, the previous block hash or the public key for an open block.
+    ///
+    /// Returns the error of the last server tried if they all fail.
+    pub async fn generate(&self, hash: &BlockHash, difficulty: Option<Difficulty>) -> Result<Work> {
+        let mut request = WorkGenerateRequest::new(hash.to_owned());
+        request.difficulty = difficulty;
+
+        let preferred = self.preferred.load(Ordering::SeqCst);
+        let order =
+            std::iter::once(preferred).chain((0..self.servers.len()).filter(|i| *i != preferred));
+        let mut last_err = Error::RPCError(String::from("No work servers configured"));
+        for idx in order {
+            let server = match self.servers.get(idx) {
+                Some(server) => server,
+                None => continue,
+            };
+            match (&request).call(server).await {
+                Ok(response) => {
+                    self.preferred.store(idx, Ordering::SeqCst);
+                    return Ok(response.work);
+                }
+                Err(err) => last_err = err,
+            }
+        }
+        Err(last_err)
+    }
+}
+
+#[cfg(all(test, feature = "rpc_server"))]
+mod tests {
+    use super::*;
+    use crate::rpc::client::mock::MockRPC;
+    use serde_json::json;
+    use std::str::FromStr;
+
+    fn work_server() -> MockRPC {
+        MockRPC::start(|_| {
+            json!({
+                "work": "2b3d689bbcb21dca",
+                "difficulty": "fffffff93c41ec94",
+                "multiplier": "1.182623871097636",
+                "hash": "718CC2121C3E641059BC1C2CFC45666C99E8AE922F7A807B7D07B62C995D79E2"
+            })
+        })
+    }
+
+    #[tokio::test]
+    async fn prefers_last_successful_server() {
+        let down = work_server();
+        down.fail_next(usize::MAX);
+        let up = work_server();
+        let client = WorkClient::with_servers(vec![
+            Url::parse(&down.url()).unwrap(),
+            Url::parse(&up.url()).unwrap(),
+        ]);
+        let hash =
+            BlockHash::from_str("718CC2121C3E641059BC1C2CFC45666C99E8AE922F7A807B7D07B62C995D79E2")
+                .unwrap();
+
+        let work = client.generate(&hash, None).await.unwrap();
+        assert_eq!(work, Work::from_str("2b3d689bbcb21dca").unwrap());
+        assert_eq!(down.requests("work_generate").len(), 1);
+        assert_eq!(up.requests("work_generate").len(), 1);
+
+        client.generate(&hash, None).await.unwrap();
+        assert_eq!(down.requests("work_generate").len(), 1);
+        assert_eq!(up.requests("work_generate").len(), 2);
+    }
+
+    #[tokio::test]
+    async fn no_servers() {
+        let client = WorkClient::with_servers(vec![]);
+        assert!(client.generate(&BlockHash::zero(), None).await.is_err());
+    }
+}
@@ -0,0 +1,135 @@
+//! Fixed test vectors to catch any accidental change to key derivation, address encoding or
+//! signing between versions.
+//!
+//! Enable with the `test_vectors` feature to check the vectors from outside this crate.
+use crate::{Seed, Signature};
+use anyhow::anyhow;
+use std::str::FromStr;
+
+/// The seed every vector is derived from.
+pub const SEED: &str = "1A2B3C4D5E6F708192A3B4C5D6E7F8091A2B3C4D5E6F708192A3B4C5D6E7F809";
+
+/// The message each private key signs.
+pub const MESSAGE: &[u8] = b"feeless test vector";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestVector {
+    pub index: u32,
+    pub private: String,
+    pub public: String,
+    pub address: String,
+    pub signature: String,
+}
+
+/// The expected vectors as `(index, private, public, address, signature)`.
+pub const EXPECTED: &[(u32, &str, &str, &str, &str)] = &[
+    (
+        0,
+        "54BD8CB6CD6E79E31F9D1949720A757DBC4ED7C0F469C0DD90453FE277A43841",
+        "D5E1FB881B3AEEC13B0FE557FE39DA414685C2B7C788D2A556E6CCBFECC06E32",
+        "nano_3oh3zg63pgqgr6xizscqzrwxnic8iq3dhjwatckofspeqzpe1ujkz9ajdjaq",
+        "0890954FC5AE61D76D8F226E12527C488F7D6FD416342892755055B94C3BCC3E65819F1C88F8A32901C3F18DE2EB851D4FE0E57DFEEF5E1FAC0A7658AEE9C408",
+    ),
+    (
+        1,
+        "E738D46D5EFAC1B7907302C0AC3EF08EFBA80A809DC8167E8A97F86B469776A0",
+        "C86213C6CD5AE53F32F66934944AA42EE0EE5D25FC79BE6352D74A36DC3E0199",
+        "nano_3k544h5etpq79wshetbnkj7cadq1xsgkdz5sqsjo7otc8ug5w1esp9p9fcwf",
+        "4E3E8B7F1EBCE5B753C2DE2CF2ED16063473322E6B12C998C7412C4C809841E132AB55FB63C5C9F319B0373AC9AB1DE7CEBDD3D95869B599496D04186A16D60D",
+    ),
+    (
+        2,
+        "CA7E87B15BE24614C10E8F283B137C4B5A788E77CF6F942F822B6299F3385717",
+        "6D8DADBA1F5F1ACE36F2F0EC2E43C981781120F751CBDCD6BBCD7DDE63FB84D1",
+        "nano_1uefopx3yqrtsruh7w9e7s3wm1dr46ihgngdumddqmdxusjzq38jqy96fko3",
+        "7CCF50EE682D46F1E65906A15570D40387100C2DD166A83E2D5876AE7696DF97D8E9F294F6A72E1E6E1A63E7791E68FCB05F088322A123FAC1E539FDD2942401",
+    ),
+    (
+        3,
+        "C15CEF20B0DFF9428A5F401812A8D8C085D7C2385071528641C492B297E6B889",
+        "8F88D406BBB283E6D4D346ABBFFAE38C40CB47D6DB61E48EBD6236A0C127388C",
+        "nano_35wati5dqen5wucf8jodqzxg9541sf5xfpu3wk9dtrjpn51kgg6ejf89aynj",
+        "F2287204D76F662DB8853859C664947240E7359F0D386651041C571E2443B9B2D7BA6BABAFF29561C449A7C294EAFEC6C7C6D565126040E4AC4FDF11ADA6030C",
+    ),
+    (
+        4,
+        "DB22A57F6A69239A88D9996D2B0E4D399AC6D916897FBDBFB60051EAE7C52878",
+        "6F8C8880201DCC8666A7C8F3BFFCC4579B1F37392B0B56F2953705A7A4C54CED",
+        "nano_1uwej41419geismchk9mqzyeaowu5wumkcrdcusbcfr7nykecm9f538atyia",
+        "499B5A5DA6514507E45E29797C815769BA474761B36EE68102976E6A8C74616198638566A725922543787DC2A1B5AFCC67A4A83120871ECAC971635292CE0E06",
+    ),
+];
+
+/// Generate the vectors for the first `count` accounts of [SEED].
+pub fn generate(count: u32) -> anyhow::Result<Vec<TestVector>> {
+    let seed = Seed::from_str(SEED)?;
+    let mut vectors = Vec::with_capacity(count as usize);
+    for index in 0..count {
+        let private = seed.derive(index);
+        let public = private.to_public()?;
+        let signature: Signature = private.sign(MESSAGE)?;
+        vectors.push(TestVector {
+            index,
+            private: private.to_string(),
+            public: public.to_string(),
+            address: public.to_address().to_string(),
+            signature: signature.to_string(),
+        });
+    }
+    Ok(vectors)
+}
+
+/// The vectors in [EXPECTED] as [TestVector]s.
+pub fn expected() -> Vec<TestVector> {
+    EXPECTED
+        .iter()
+        .map(|(index, private, public, address, signature)| TestVector {
+            index: *index,
+            private: private.to_string(),
+            public: public.to_string(),
+            address: address.to_string(),
+            signature: signature.to_string(),
+        })
+        .collect()
+}
+
+/// Check that this crate still produces every vector in [EXPECTED].
+pub fn verify() -> anyhow::Result<()> {
+    let generated = generate(EXPECTED.len() as u32)?;
+    for (generated, expected) in generated.iter().zip(expected()) {
+        if generated != &expected {
+            return Err(anyhow!(
+                "Test vector mismatch: expected {:?} got {:?}",
+                expected,
+                generated
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expected_vectors() {
+        assert_eq!(generate(EXPECTED.len() as u32).unwrap(), expected());
+        verify().unwrap();
+    }
+
+    #[test]
+    fn vectors_verify() {
+        for vector in expected() {
+            let public = crate::Public::from_str(&vector.public).unwrap();
+            let signature = Signature::from_str(&vector.signature).unwrap();
+            public.verify(MESSAGE, &signature).unwrap();
+            assert_eq!(
+                crate::Address::from_str(&vector.address)
+                    .unwrap()
+                    .to_public(),
+                public
+            );
+        }
+    }
+}
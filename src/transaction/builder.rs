@@ -0,0 +1,227 @@
+use crate::blocks::{BlockHash, Link, Previous, StateBlock};
+use crate::{Address, Error, Private, Public, Raw, Result};
+
+type Predicate = Box<dyn Fn(&Address) -> bool + Send + Sync>;
+
+/// Builds and signs a send block from the account's frontier, balance and representative, e.g.
+/// from `account_info`.
+///
+/// Sends to some destinations, like exchanges expecting an identifier, can lose funds. Use
+/// [TransactionBuilder::require_confirmation_for] to refuse building those until
+/// [TransactionBuilder::confirm] is called.
+pub struct TransactionBuilder {
+    private: Private,
+    frontier: BlockHash,
+    representative: Public,
+    balance: Raw,
+    destination: Address,
+    amount: Raw,
+    requires_confirmation: Option<Predicate>,
+    confirmed: bool,
+}
+
+impl TransactionBuilder {
+    pub fn new(
+        private: Private,
+        frontier: BlockHash,
+        representative: Public,
+        balance: Raw,
+        destination: Address,
+        amount: Raw,
+    ) -> Self {
+        Self {
+            private,
+            frontier,
+            representative,
+            balance,
+            destination,
+            amount,
+            requires_confirmation: None,
+            confirmed: false,
+        }
+    }
+
+    /// Refuse to build when `predicate` returns true for the destination, unless
+    /// [TransactionBuilder::confirm] is called.
+    pub fn require_confirmation_for<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&Address) -> bool + Send + Sync + 'static,
+    {
+        self.requires_confirmation = Some(Box::new(predicate));
+        self
+    }
+
+    /// Explicitly allow sending to a destination flagged by
+    /// [TransactionBuilder::require_confirmation_for].
+    pub fn confirm(mut self) -> Self {
+        self.confirmed = true;
+        self
+    }
+
+    /// The signed send block, without work.
+    pub fn build(&self) -> Result<StateBlock> {
+        if let Some(predicate) = &self.requires_confirmation {
+            if !self.confirmed && predicate(&self.destination) {
+                return Err(Error::ConfirmationRequired(self.destination.to_owned()));
+            }
+        }
+
+        let balance =
+            self.balance
+                .checked_sub(&self.amount)
+                .ok_or_else(|| Error::InsufficientBalance {
+                    balance: self.balance.to_owned(),
+                    amount: self.amount.to_owned(),
+                })?;
+        let mut block = StateBlock::new(
+            self.private.to_public()?,
+            Previous::Block(self.frontier.to_owned()),
+            self.representative.to_owned(),
+            balance,
+            Link::DestinationAccount(self.destination.to_public()),
+        );
+        block.signature = Some(self.private.sign(block.hash.as_bytes())?);
+        Ok(block)
+    }
+}
+
+/// Builds and signs a receive block on top of `previous`, or an open block when it's
+/// [Previous::Open].
+///
+/// `balance` is the balance at `previous`, which is zero for an account that isn't opened yet.
+pub struct ReceiveBuilder {
+    private: Private,
+    previous: Previous,
+    representative: Public,
+    balance: Raw,
+}
+
+impl ReceiveBuilder {
+    pub fn new(private: Private, previous: Previous, representative: Public, balance: Raw) -> Self {
+        Self {
+            private,
+            previous,
+            representative,
+            balance,
+        }
+    }
+
+    /// The signed block receiving `amount` from the send block `source`, without work.
+    pub fn build(&self, source: BlockHash, amount: Raw) -> Result<StateBlock> {
+        let balance = self
+            .balance
+            .checked_add(&amount)
+            .ok_or_else(|| Error::BalanceOverflow {
+                balance: self.balance.to_owned(),
+                amount: amount.to_owned(),
+            })?;
+        let mut block = StateBlock::new(
+            self.private.to_public()?,
+            self.previous.to_owned(),
+            self.representative.to_owned(),
+            balance,
+            Link::Source(source),
+        );
+        block.signature = Some(self.private.sign(block.hash.as_bytes())?);
+        Ok(block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Seed;
+    use std::collections::HashSet;
+    use std::convert::TryFrom;
+
+    fn builder(destination: Address) -> TransactionBuilder {
+        let private = Seed::zero().derive(0);
+        let representative = private.to_public().unwrap();
+        TransactionBuilder::new(
+            private,
+            BlockHash::zero(),
+            representative,
+            Raw::from(1000),
+            destination,
+            Raw::from(300),
+        )
+    }
+
+    #[test]
+    fn flagged_destination_requires_confirmation() {
+        let exchange = Seed::zero().derive(1).to_address().unwrap();
+        let friend = Seed::zero().derive(2).to_address().unwrap();
+        let mut exchanges = HashSet::new();
+        exchanges.insert(exchange.to_owned());
+        let exchanges = std::sync::Arc::new(exchanges);
+
+        let flagged = exchanges.clone();
+        let blocked = builder(exchange.to_owned())
+            .require_confirmation_for(move |address| flagged.contains(address));
+        assert!(matches!(
+            blocked.build(),
+            Err(Error::ConfirmationRequired(address)) if address == exchange
+        ));
+        let block = blocked.confirm().build().unwrap();
+        assert_eq!(block.balance, Raw::from(700));
+        block.verify_self_signature().unwrap();
+
+        let flagged = exchanges.clone();
+        assert!(builder(friend)
+            .require_confirmation_for(move |address| flagged.contains(address))
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn insufficient_balance() {
+        let mut builder = builder(Seed::zero().derive(1).to_address().unwrap());
+        builder.amount = Raw::from(1001);
+        assert!(matches!(
+            builder.build(),
+            Err(Error::InsufficientBalance { .. })
+        ));
+    }
+
+    #[test]
+    fn receive() {
+        let private = Seed::zero().derive(0);
+        let representative = private.to_public().unwrap();
+        let source = BlockHash::try_from([7u8; BlockHash::LEN].as_ref()).unwrap();
+
+        let builder = ReceiveBuilder::new(
+            private.to_owned(),
+            Previous::Block(BlockHash::zero()),
+            representative.to_owned(),
+            Raw::from(1000),
+        );
+        let block = builder.build(source.to_owned(), Raw::from(250)).unwrap();
+        assert_eq!(block.balance, Raw::from(1250));
+        assert_eq!(block.previous, Previous::Block(BlockHash::zero()));
+        assert_eq!(block.link, Link::Source(source.to_owned()));
+        block.verify_self_signature().unwrap();
+
+        let open = ReceiveBuilder::new(
+            private.to_owned(),
+            Previous::Open,
+            representative,
+            Raw::zero(),
+        )
+        .build(source.to_owned(), Raw::from(250))
+        .unwrap();
+        assert_eq!(open.balance, Raw::from(250));
+        assert_eq!(open.previous, Previous::Open);
+        open.verify_self_signature().unwrap();
+
+        let full = ReceiveBuilder::new(
+            private,
+            Previous::Block(BlockHash::zero()),
+            Seed::zero().derive(1).to_public().unwrap(),
+            Raw::max(),
+        );
+        assert!(matches!(
+            full.build(source, Raw::from(1)),
+            Err(Error::BalanceOverflow { .. })
+        ));
+    }
+}
@@ -0,0 +1,10 @@
+//! High level helpers that talk to a node over RPC to build, sign and broadcast blocks.
+mod builder;
+mod receive;
+mod receiver;
+mod send;
+
+pub use builder::{ReceiveBuilder, TransactionBuilder};
+pub use receive::receive;
+pub use receiver::{Received, Receiver, ReceiverHandle};
+pub use send::send;
@@ -0,0 +1,58 @@
+use crate::blocks::{BlockHash, Previous, Subtype};
+use crate::rpc::calls::{AccountInfoRequest, ProcessRequest, WorkGenerateRequest};
+use crate::rpc::client::{RPCClient, RPCRequest};
+use crate::transaction::ReceiveBuilder;
+use crate::{Error, Private, Public, Raw, Result};
+use std::convert::TryFrom;
+
+/// Receive the pending send block `source` of `amount` into the account of `private`, returning
+/// the hash of the broadcasted receive block.
+///
+/// If the account hasn't been opened yet, an open block is created with `representative`,
+/// otherwise the account's current representative is kept.
+pub async fn receive(
+    private: &Private,
+    source: &BlockHash,
+    amount: Raw,
+    representative: &Public,
+    rpc: &RPCClient,
+) -> Result<BlockHash> {
+    let account = private.to_public()?;
+    let info = (&AccountInfoRequest::new(account.to_address()))
+        .call(rpc)
+        .await;
+
+    let (subtype, previous, representative, balance, work_root) = match info {
+        Ok(info) => {
+            let representative = info
+                .representative
+                .as_ref()
+                .ok_or_else(|| Error::RPCError("No representative in account_info".into()))?
+                .to_public();
+            (
+                Subtype::Receive,
+                Previous::Block(info.frontier.to_owned()),
+                representative,
+                info.balance,
+                info.frontier,
+            )
+        }
+        Err(Error::RPCError(err)) if err == "Account not found" => (
+            Subtype::Open,
+            Previous::Open,
+            representative.to_owned(),
+            Raw::zero(),
+            // The work for an open block is done on the account's public key.
+            BlockHash::try_from(account.as_bytes())?,
+        ),
+        Err(err) => return Err(err),
+    };
+
+    let mut block = ReceiveBuilder::new(private.to_owned(), previous, representative, balance)
+        .build(source.to_owned(), amount)?;
+    let work = (&WorkGenerateRequest::new(work_root)).call(rpc).await?;
+    block.work = Some(work.work);
+
+    let response = (&ProcessRequest::new(subtype, block)).call(rpc).await?;
+    Ok(response.hash)
+}
@@ -0,0 +1,283 @@
+use crate::blocks::BlockHash;
+use crate::rpc::calls::{AccountsPendingRequest, AccountsPendingResponse};
+use crate::rpc::client::{RPCClient, RPCRequest};
+use crate::transaction::receive;
+use crate::{Address, Private, Public, Raw, Result, Seed};
+use std::collections::HashMap;
+use std::ops::Range;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// A receive block that was broadcasted by a [Receiver].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Received {
+    pub account: Address,
+    pub index: u32,
+
+    /// The hash of the send block that was received.
+    pub source: BlockHash,
+
+    /// The hash of the new receive (or open) block.
+    pub hash: BlockHash,
+
+    pub amount: Raw,
+}
+
+/// Periodically scans a range of accounts of a seed for pending blocks, and receives them.
+///
+/// This is the typical "hot wallet" auto receive service.
+pub struct Receiver {
+    seed: Seed,
+    range: Range<u32>,
+    interval: Duration,
+    representative: Option<Public>,
+    min_receive: Raw,
+}
+
+impl Receiver {
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+
+    pub fn new(seed: Seed, range: Range<u32>) -> Self {
+        Self {
+            seed,
+            range,
+            interval: Self::DEFAULT_INTERVAL,
+            representative: None,
+            min_receive: Raw::from(1),
+        }
+    }
+
+    /// How long to wait between each scan.
+    pub fn interval(&mut self, interval: Duration) -> &mut Self {
+        self.interval = interval;
+        self
+    }
+
+    /// The representative used when an account needs to be opened. Defaults to the account itself.
+    pub fn representative(&mut self, representative: Public) -> &mut Self {
+        self.representative = Some(representative);
+        self
+    }
+
+    /// Leave pending blocks below `min_receive` alone, so dust doesn't cause a receive block for
+    /// each of them. Defaults to 1 raw, i.e. everything is received.
+    pub fn min_receive(&mut self, min_receive: Raw) -> &mut Self {
+        self.min_receive = min_receive;
+        self
+    }
+
+    /// Scan all the accounts once, receiving every pending block found.
+    pub async fn scan(&self, rpc: &RPCClient) -> Result<Vec<Received>> {
+        let mut accounts: HashMap<Address, (u32, Private)> = HashMap::new();
+        for index in self.range.clone() {
+            let private = self.seed.derive(index);
+            accounts.insert(private.to_address()?, (index, private));
+        }
+
+        let mut request = AccountsPendingRequest::new(accounts.keys().cloned().collect(), 100);
+        // A threshold is needed for the response to contain the amounts.
+        request.threshold = Some(if self.min_receive > 0 {
+            self.min_receive.to_owned()
+        } else {
+            Raw::from(1)
+        });
+        let pending = match (&request).call(rpc).await? {
+            AccountsPendingResponse::OnlyBlockHash { .. } => return Ok(vec![]),
+            AccountsPendingResponse::Threshold { blocks } => blocks,
+            AccountsPendingResponse::Source { blocks } => blocks
+                .into_iter()
+                .map(|(account, blocks)| {
+                    let blocks = blocks
+                        .into_iter()
+                        .map(|(hash, entry)| (hash, entry.amount))
+                        .collect();
+                    (account, blocks)
+                })
+                .collect(),
+        };
+
+        let mut received = vec![];
+        for (account, blocks) in pending {
+            let (index, private) = match accounts.get(&account) {
+                Some(a) => a,
+                None => continue,
+            };
+            let public = account.to_public();
+            let representative = self.representative.as_ref().unwrap_or(&public);
+            for (source, amount) in blocks {
+                if amount < self.min_receive {
+                    continue;
+                }
+                debug!("Receiving {} from {} into {}", amount, source, account);
+                let hash =
+                    receive(private, &source, amount.to_owned(), representative, rpc).await?;
+                received.push(Received {
+                    account: account.to_owned(),
+                    index: *index,
+                    source,
+                    hash,
+                    amount,
+                });
+            }
+        }
+        Ok(received)
+    }
+
+    /// Start scanning in the background. Each received block is sent to the returned channel.
+    pub fn start(self, rpc: RPCClient) -> (ReceiverHandle, mpsc::Receiver<Received>) {
+        let (tx, rx) = mpsc::channel(100);
+        let (cancel_tx, mut cancel_rx) = oneshot::channel();
+        let join = tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = &mut cancel_rx => return,
+                    result = self.scan(&rpc) => match result {
+                        Ok(received) => {
+                            for r in received {
+                                if tx.send(r).await.is_err() {
+                                    return;
+                                }
+                            }
+                        }
+                        Err(err) => warn!("Receiver scan failed: {}", err),
+                    },
+                }
+                tokio::select! {
+                    _ = &mut cancel_rx => return,
+                    _ = tokio::time::sleep(self.interval) => {}
+                }
+            }
+        });
+        (
+            ReceiverHandle {
+                cancel: cancel_tx,
+                join,
+            },
+            rx,
+        )
+    }
+}
+
+/// Returned by [Receiver::start] to stop the receiver.
+pub struct ReceiverHandle {
+    cancel: oneshot::Sender<()>,
+    join: JoinHandle<()>,
+}
+
+impl ReceiverHandle {
+    /// Stop the receiver, waiting for it to finish.
+    pub async fn cancel(self) {
+        // The receiver might have already stopped by itself.
+        let _ = self.cancel.send(());
+        let _ = self.join.await;
+    }
+}
+
+#[cfg(all(test, feature = "rpc_server"))]
+mod tests {
+    use super::*;
+    use crate::rpc::client::mock::MockRPC;
+    use crate::rpc::StateBlockRequest;
+    use serde_json::json;
+    use std::str::FromStr;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[tokio::test]
+    async fn receives_pending() {
+        let seed = Seed::zero();
+        let account = seed.derive(1).to_address().unwrap();
+        let source = "142A538F36833D1CC78B94E11C766F75818F8B940771335C6C1B8AB880C5BB1D";
+        let receive_hash = "4C1FEEF0BEA7F50BE35489A1233FE002B212DEA554B55B1B470D78BD8F210C74";
+
+        let delivered = AtomicBool::new(false);
+        let pending_account = account.to_string();
+        let mock = MockRPC::start(move |request| match request["action"].as_str().unwrap() {
+            // Only deliver the pending block once, as if it was received afterwards.
+            "accounts_pending" if !delivered.swap(true, Ordering::SeqCst) => json!({
+                "blocks": { pending_account.as_str(): { source: "6000" } }
+            }),
+            "accounts_pending" => json!({ "blocks": {} }),
+            "account_info" => json!({ "error": "Account not found" }),
+            "work_generate" => json!({
+                "work": "2b3d689bbcb21dca",
+                "difficulty": "fffffff93c41ec94",
+                "multiplier": "1.182623871097636",
+                "hash": request["hash"],
+            }),
+            "process" => json!({ "hash": receive_hash }),
+            action => json!({ "error": format!("Unexpected action {}", action) }),
+        });
+
+        let mut receiver = Receiver::new(seed, 0..3);
+        receiver.interval(Duration::from_millis(10));
+        let (handle, mut rx) = receiver.start(mock.client());
+        let received = rx.recv().await.unwrap();
+        handle.cancel().await;
+
+        assert_eq!(
+            received,
+            Received {
+                account: account.to_owned(),
+                index: 1,
+                source: BlockHash::from_str(source).unwrap(),
+                hash: BlockHash::from_str(receive_hash).unwrap(),
+                amount: Raw::from(6000),
+            }
+        );
+
+        let processed = mock.requests("process");
+        assert_eq!(processed.len(), 1);
+        assert_eq!(processed[0]["subtype"], "open");
+        let block: StateBlockRequest =
+            serde_json::from_str(&processed[0]["block"].to_string()).unwrap();
+        assert_eq!(block.account, account);
+        assert_eq!(block.previous, BlockHash::zero());
+        assert_eq!(block.representative, account);
+        assert_eq!(block.balance, Raw::from(6000));
+        assert_eq!(
+            block.link.as_bytes(),
+            BlockHash::from_str(source).unwrap().as_bytes()
+        );
+
+        // The work for opening an account is on the public key.
+        let work = mock.requests("work_generate");
+        assert_eq!(work[0]["hash"], account.to_public().as_hex());
+    }
+
+    #[tokio::test]
+    async fn ignores_dust() {
+        let seed = Seed::zero();
+        let account = seed.derive(0).to_address().unwrap();
+        let large = "142A538F36833D1CC78B94E11C766F75818F8B940771335C6C1B8AB880C5BB1D";
+        let dust = "4C1FEEF0BEA7F50BE35489A1233FE002B212DEA554B55B1B470D78BD8F210C74";
+
+        let pending_account = account.to_string();
+        let mock = MockRPC::start(move |request| match request["action"].as_str().unwrap() {
+            // Thresholds are ignored, as by a node which doesn't support them.
+            "accounts_pending" => json!({
+                "blocks": { pending_account.as_str(): { large: "6000", dust: "5" } }
+            }),
+            "account_info" => json!({ "error": "Account not found" }),
+            "work_generate" => json!({
+                "work": "2b3d689bbcb21dca",
+                "difficulty": "fffffff93c41ec94",
+                "multiplier": "1.182623871097636",
+                "hash": request["hash"],
+            }),
+            "process" => json!({ "hash": large }),
+            action => json!({ "error": format!("Unexpected action {}", action) }),
+        });
+
+        let mut receiver = Receiver::new(seed, 0..1);
+        receiver.min_receive(Raw::from(100));
+        let received = receiver.scan(&mock.client()).await.unwrap();
+
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].source, BlockHash::from_str(large).unwrap());
+        assert_eq!(received[0].amount, Raw::from(6000));
+        assert_eq!(mock.requests("process").len(), 1);
+        assert_eq!(mock.requests("accounts_pending")[0]["threshold"], "100");
+    }
+}
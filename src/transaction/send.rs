@@ -0,0 +1,142 @@
+use crate::blocks::{BlockHash, Subtype};
+use crate::rpc::calls::{AccountInfoRequest, ProcessRequest, WorkGenerateRequest};
+use crate::rpc::client::{RPCClient, RPCRequest};
+use crate::transaction::TransactionBuilder;
+use crate::{Address, Error, Private, Raw, Result};
+
+/// Send `amount` from the account of `private` to `destination`, returning the hash of the
+/// broadcasted send block.
+///
+/// The frontier, balance and representative of the account are fetched with `account_info`, the
+/// work is fetched with `work_generate`, then the signed block is broadcasted with `process`.
+pub async fn send(
+    private: &Private,
+    destination: &Address,
+    amount: Raw,
+    rpc: &RPCClient,
+) -> Result<BlockHash> {
+    let account = private.to_public()?;
+    let info = (&AccountInfoRequest::new(account.to_address()))
+        .call(rpc)
+        .await?;
+    let representative = info
+        .representative
+        .as_ref()
+        .ok_or_else(|| Error::RPCError("No representative in account_info".into()))?
+        .to_public();
+
+    let mut block = TransactionBuilder::new(
+        private.to_owned(),
+        info.frontier.to_owned(),
+        representative,
+        info.balance,
+        destination.to_owned(),
+        amount,
+    )
+    .build()?;
+    let work = (&WorkGenerateRequest::new(info.frontier)).call(rpc).await?;
+    block.work = Some(work.work);
+
+    let response = (&ProcessRequest::new(Subtype::Send, block))
+        .call(rpc)
+        .await?;
+    Ok(response.hash)
+}
+
+#[cfg(all(test, feature = "rpc_server"))]
+mod tests {
+    use super::*;
+    use crate::blocks::{Link, Previous, StateBlock};
+    use crate::rpc::calls::StateBlockRequest;
+    use crate::rpc::client::mock::MockRPC;
+    use crate::{Seed, Work};
+    use serde_json::json;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn send_builds_and_broadcasts() {
+        let private = Seed::zero().derive(0);
+        let account = private.to_public().unwrap();
+        let destination = Seed::zero().derive(1).to_address().unwrap();
+        let frontier = "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948";
+        let work = "2b3d689bbcb21dca";
+
+        let representative = account.to_address().to_string();
+        let mock = MockRPC::start(move |request| match request["action"].as_str().unwrap() {
+            "account_info" => json!({
+                "frontier": frontier,
+                "open_block": frontier,
+                "representative_block": frontier,
+                "balance": "1000",
+                "modified_timestamp": "1501793775",
+                "block_count": "1",
+                "confirmation_height" : "1",
+                "confirmation_height_frontier" : frontier,
+                "account_version": "2",
+                "representative": representative,
+            }),
+            "work_generate" => json!({
+                "work": work,
+                "difficulty": "fffffff93c41ec94",
+                "multiplier": "1.182623871097636",
+                "hash": frontier,
+            }),
+            "process" => json!({ "hash": "0000000000000000000000000000000000000000000000000000000000000001" }),
+            action => json!({ "error": format!("Unexpected action {}", action) }),
+        });
+
+        let hash = send(&private, &destination, Raw::from(300), &mock.client())
+            .await
+            .unwrap();
+        assert_eq!(
+            hash,
+            BlockHash::from_str("0000000000000000000000000000000000000000000000000000000000000001")
+                .unwrap()
+        );
+
+        let processed = mock.requests("process");
+        assert_eq!(processed.len(), 1);
+        assert_eq!(processed[0]["subtype"], "send");
+        let sent: StateBlockRequest = serde_json::from_str(&processed[0]["block"].to_string()).unwrap();
+        assert_eq!(sent.account, account.to_address());
+        assert_eq!(sent.previous, BlockHash::from_str(frontier).unwrap());
+        assert_eq!(sent.representative, account.to_address());
+        assert_eq!(sent.balance, Raw::from(700));
+        assert_eq!(sent.work, Some(Work::from_str(work).unwrap()));
+
+        let mut block = StateBlock::new(
+            sent.account.to_public(),
+            Previous::Block(sent.previous),
+            sent.representative.to_public(),
+            sent.balance,
+            sent.link,
+        );
+        block.signature = sent.signature;
+        block.set_link_type(true, Raw::from(300)).unwrap();
+        assert_eq!(block.link, Link::DestinationAccount(destination.to_public()));
+        block.verify_self_signature().unwrap();
+    }
+
+    #[tokio::test]
+    async fn send_more_than_balance() {
+        let mock = MockRPC::start(|_| {
+            json!({
+                "frontier": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+                "open_block": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+                "representative_block": "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+                "balance": "1000",
+                "modified_timestamp": "1501793775",
+                "block_count": "1",
+                "confirmation_height" : "1",
+                "confirmation_height_frontier" : "991CF190094C00F0B68E2E5F75F6BEE95A2E0BD93CEAA4A6734DB9F19B728948",
+                "account_version": "2",
+                "representative": "nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3",
+            })
+        });
+        let private = Seed::zero().derive(0);
+        let destination = Seed::zero().derive(1).to_address().unwrap();
+        let result = send(&private, &destination, Raw::from(1001), &mock.client()).await;
+        assert!(matches!(result, Err(Error::InsufficientBalance { .. })));
+        assert!(mock.requests("process").is_empty());
+    }
+}
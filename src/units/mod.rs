@@ -90,7 +90,7 @@ use bigdecimal::BigDecimal;
 use bigdecimal::ToPrimitive;
 use doc_comment::doc_comment;
 use once_cell::sync::Lazy;
-pub use raw::Raw;
+pub use raw::{Direction, Raw};
 use std::convert::TryFrom;
 use std::str::FromStr;
 
@@ -44,6 +44,36 @@ impl Raw {
         Ok(Raw::try_from(vec.as_slice())?)
     }
 
+    /// Parse a decimal raw amount the way a user might enter it, e.g. `340_282_366` or
+    /// `340 282 366`. Underscores and whitespace are stripped before parsing.
+    pub fn from_rpc_string(s: &str) -> Result<Self, Error> {
+        let digits: String = s
+            .trim()
+            .chars()
+            .filter(|c| *c != '_' && !c.is_whitespace())
+            .collect();
+        Raw::from_str(&digits)
+    }
+
+    /// Parse a decimal amount of [Nano], e.g. `1.5`.
+    ///
+    /// Errors when the amount has more decimal places than whole raw, instead of rounding.
+    pub fn from_nano(s: &str) -> Result<Self, Error> {
+        Self::from_raw_big_decimal(Nano::from_str(s.trim())?.to_raw_big_decimal(), s)
+    }
+
+    /// Parse a decimal amount of [Mnano] (NANO), e.g. `1.5`. See [Raw::from_nano].
+    pub fn from_mnano(s: &str) -> Result<Self, Error> {
+        Self::from_raw_big_decimal(Mnano::from_str(s.trim())?.to_raw_big_decimal(), s)
+    }
+
+    fn from_raw_big_decimal(raw: BigDecimal, s: &str) -> Result<Self, Error> {
+        if raw.with_scale(0) != raw {
+            return Err(Error::TooManyDecimals(s.to_owned()));
+        }
+        Raw::try_from(&raw)
+    }
+
     pub fn zero() -> Self {
         Self(0)
     }
@@ -68,10 +98,44 @@ impl Raw {
         self.0.to_be_bytes().to_vec()
     }
 
+    /// The big endian bytes without leading zeros, e.g. to store small balances compactly. Zero is
+    /// empty.
+    ///
+    /// Use [Raw::to_vec] for the fixed 16 bytes used on the wire and in hashes.
+    pub fn to_minimal_bytes(&self) -> Vec<u8> {
+        let bytes = self.0.to_be_bytes();
+        let start = bytes.iter().position(|b| *b != 0).unwrap_or(Self::LEN);
+        bytes[start..].to_vec()
+    }
+
+    /// Read the output of [Raw::to_minimal_bytes]. Leading zeros are allowed.
+    pub fn from_minimal_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() > Self::LEN {
+            return Err(Error::WrongLength {
+                msg: "Minimal raw".to_string(),
+                expected: Self::LEN,
+                found: bytes.len(),
+            });
+        }
+        let mut b = [0u8; Self::LEN];
+        b[Self::LEN - bytes.len()..].copy_from_slice(bytes);
+        Ok(Raw(u128::from_be_bytes(b)))
+    }
+
     pub fn to_hex_string(&self) -> String {
         to_hex(self.0.to_be_bytes().as_ref())
     }
 
+    /// The amount in [Nano] without trailing zeros, e.g. `1.5`.
+    pub fn to_nano_string(&self) -> String {
+        self.to_nano().to_big_decimal().to_string()
+    }
+
+    /// The amount in [Mnano] (NANO) without trailing zeros, e.g. `1.5`.
+    pub fn to_mnano_string(&self) -> String {
+        self.to_mnano().to_big_decimal().to_string()
+    }
+
     pub fn to_u128(&self) -> u128 {
         self.0
     }
@@ -88,6 +152,36 @@ impl Raw {
     pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
         self.0.checked_sub(rhs.0).map(Raw::from)
     }
+
+    /// The amount to send to empty an account except for `reserve`, e.g. for a "send max" button
+    /// which keeps a little back.
+    pub fn send_amount_with_reserve(balance: Raw, reserve: Raw) -> Result<Raw, Error> {
+        balance
+            .checked_sub(&reserve)
+            .ok_or(Error::InsufficientBalance {
+                balance,
+                amount: reserve,
+            })
+    }
+
+    /// How much has to be sent or received to go from this balance to `target`, e.g. to top up an
+    /// account to a fixed amount.
+    ///
+    /// When the balance is already `target` this is a receive of zero.
+    pub fn delta_to(&self, target: &Raw) -> (Direction, Raw) {
+        if self.0 > target.0 {
+            (Direction::Send, Raw(self.0 - target.0))
+        } else {
+            (Direction::Receive, Raw(target.0 - self.0))
+        }
+    }
+}
+
+/// Whether a balance change is sent out of an account or received into it. See [Raw::delta_to].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Direction {
+    Send,
+    Receive,
 }
 
 impl FromStr for Raw {
@@ -114,8 +208,8 @@ impl<'de> Deserialize<'de> for Raw {
     where
         D: Deserializer<'de>,
     {
-        let s: &str = Deserialize::deserialize(deserializer)?;
-        Ok(Raw::from_str(s).map_err(de::Error::custom)?)
+        let s: String = Deserialize::deserialize(deserializer)?;
+        Ok(Raw::from_str(&s).map_err(de::Error::custom)?)
     }
 }
 
@@ -133,8 +227,8 @@ pub fn deserialize_from_hex<'de, D>(deserializer: D) -> Result<Raw, <D as Deseri
 where
     D: Deserializer<'de>,
 {
-    let s: &str = Deserialize::deserialize(deserializer)?;
-    Ok(Raw::from_hex(s).map_err(de::Error::custom)?)
+    let s: String = Deserialize::deserialize(deserializer)?;
+    Ok(Raw::from_hex(&s).map_err(de::Error::custom)?)
 }
 
 impl Display for Raw {
@@ -234,6 +328,106 @@ mod tests {
     use super::*;
     use std::convert::TryFrom;
 
+    #[test]
+    fn units() {
+        let raw = Raw::from_mnano("1.5").unwrap();
+        assert_eq!(raw.to_u128(), 1_500_000_000_000_000_000_000_000_000_000);
+        assert_eq!(raw.to_mnano_string(), "1.5");
+        assert_eq!(raw.to_nano_string(), "1500000");
+        assert_eq!(Raw::from_nano("1500000").unwrap(), raw);
+
+        let one = Raw::from(1);
+        assert_eq!(one.to_mnano_string(), "0.000000000000000000000000000001");
+        assert_eq!(
+            Raw::from_mnano("0.000000000000000000000000000001").unwrap(),
+            one
+        );
+        assert_eq!(
+            Raw::max().to_mnano_string(),
+            "340282366.920938463463374607431768211455"
+        );
+        assert!(matches!(
+            Raw::from_mnano("0.0000000000000000000000000000001"),
+            Err(Error::TooManyDecimals(_))
+        ));
+        assert!(Raw::from_mnano("-1").is_err());
+        assert!(Raw::from_mnano("340282367").is_err());
+
+        assert_eq!(raw.checked_sub(&Raw::from_mnano("2").unwrap()), None);
+        assert_eq!(Raw::max().checked_add(&one), None);
+    }
+
+    #[test]
+    fn minimal_bytes() {
+        assert!(Raw::zero().to_minimal_bytes().is_empty());
+        assert_eq!(Raw::from(1000).to_minimal_bytes(), vec![0x03, 0xe8]);
+        assert_eq!(Raw::max().to_minimal_bytes().len(), 16);
+        for raw in &[
+            Raw::zero(),
+            Raw::from(1),
+            Raw::from(1000),
+            Raw::from_mnano("1.5").unwrap(),
+            Raw::max(),
+        ] {
+            let bytes = raw.to_minimal_bytes();
+            assert_eq!(&Raw::from_minimal_bytes(&bytes).unwrap(), raw);
+        }
+
+        assert_eq!(
+            Raw::from_minimal_bytes(&[0, 0, 0x03, 0xe8]).unwrap(),
+            Raw::from(1000)
+        );
+        assert!(Raw::from_minimal_bytes(&[1u8; 17]).is_err());
+    }
+
+    #[test]
+    fn send_amount_with_reserve() {
+        assert_eq!(
+            Raw::send_amount_with_reserve(Raw::from(1000), Raw::from(10)).unwrap(),
+            Raw::from(990)
+        );
+        assert_eq!(
+            Raw::send_amount_with_reserve(Raw::from(10), Raw::from(10)).unwrap(),
+            Raw::zero()
+        );
+        assert!(matches!(
+            Raw::send_amount_with_reserve(Raw::from(10), Raw::from(11)),
+            Err(Error::InsufficientBalance { .. })
+        ));
+    }
+
+    #[test]
+    fn delta_to() {
+        let balance = Raw::from(1000);
+        assert_eq!(
+            balance.delta_to(&Raw::from(1500)),
+            (Direction::Receive, Raw::from(500))
+        );
+        assert_eq!(
+            balance.delta_to(&Raw::from(200)),
+            (Direction::Send, Raw::from(800))
+        );
+        assert_eq!(
+            balance.delta_to(&balance),
+            (Direction::Receive, Raw::zero())
+        );
+        assert_eq!(
+            Raw::zero().delta_to(&Raw::max()),
+            (Direction::Receive, Raw::max())
+        );
+    }
+
+    #[test]
+    fn from_rpc_string() {
+        let expected = Raw::from(340_282_366u128);
+        assert_eq!(Raw::from_rpc_string("340_282_366").unwrap(), expected);
+        assert_eq!(Raw::from_rpc_string("340 282 366").unwrap(), expected);
+        assert_eq!(Raw::from_rpc_string(" 340282366\n").unwrap(), expected);
+        assert!(Raw::from_rpc_string("340,282,366").is_err());
+        assert!(Raw::from_rpc_string("340_282_366nano").is_err());
+        assert!(Raw::from_rpc_string("_").is_err());
+    }
+
     #[test]
     fn display() {
         assert_eq!(Raw::zero().to_string(), "0");
@@ -0,0 +1,87 @@
+use crate::Address;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Saved addresses by a user chosen name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct AddressBook(BTreeMap<String, Address>);
+
+impl AddressBook {
+    pub fn new() -> Self {
+        Self(BTreeMap::new())
+    }
+
+    pub fn insert<S: Into<String>>(&mut self, name: S, address: Address) {
+        self.0.insert(name.into(), address);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Address> {
+        self.0.get(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Address> {
+        self.0.remove(name)
+    }
+
+    /// The saved address closest to `input` by edit distance, if within `max_distance`.
+    ///
+    /// This can be used to ask "did you mean X?" when a pasted address is slightly off. An exact
+    /// match has a distance of 0. When there's a tie, the first name alphabetically wins.
+    pub fn nearest(&self, input: &str, max_distance: usize) -> Option<(&Address, usize)> {
+        let mut nearest: Option<(&Address, usize)> = None;
+        for address in self.0.values() {
+            let distance = edit_distance(input, &address.to_string());
+            if distance > max_distance {
+                continue;
+            }
+            match nearest {
+                Some((_, d)) if d <= distance => {}
+                _ => nearest = Some((address, distance)),
+            }
+        }
+        nearest
+    }
+}
+
+/// Levenshtein distance between two strings, by character.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == *cb { 0 } else { 1 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn nearest() {
+        let saved =
+            Address::from_str("nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b7")
+                .unwrap();
+        let mut book = AddressBook::new();
+        book.insert("savings", saved.to_owned());
+        book.insert(
+            "genesis",
+            Address::from_str("nano_3t6k35gi95xu6tergt6p69ck76ogmitsa8mnijtpxm9fkcm736xtoncuohr3")
+                .unwrap(),
+        );
+
+        // The last character is off by one.
+        let typo = "nano_3i1aq1cchnmbn9x5rsbap8b15akfh7wj7pwskuzi7ahz8oq6cobd99d4r3b8";
+        assert_eq!(book.nearest(typo, 2), Some((&saved, 1)));
+        assert_eq!(book.nearest(&saved.to_string(), 0), Some((&saved, 0)));
+        assert_eq!(book.nearest(typo, 0), None);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
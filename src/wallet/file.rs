@@ -0,0 +1,164 @@
+use crate::wallet::AddressBook;
+use crate::{Address, EncryptedKey, Error, KdfParams, Seed};
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::fs::File;
+
+/// The seed of a [WalletFile], optionally encrypted with a password.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StoredSeed {
+    Plain(Seed),
+    Encrypted(EncryptedKey),
+}
+
+impl StoredSeed {
+    /// The seed, decrypting it with `password` when encrypted.
+    ///
+    /// A password is required exactly when the seed is encrypted, so a plain seed isn't mistaken
+    /// for one protected by the given password.
+    pub fn to_seed(&self, password: Option<&str>) -> Result<Seed, Error> {
+        match (self, password) {
+            (StoredSeed::Plain(seed), None) => Ok(seed.to_owned()),
+            (StoredSeed::Plain(_), Some(_)) => Err(Error::UnexpectedPassword),
+            (StoredSeed::Encrypted(encrypted), Some(password)) => encrypted.decrypt_seed(password),
+            (StoredSeed::Encrypted(_), None) => Err(Error::PasswordRequired),
+        }
+    }
+}
+
+/// Which accounts of the seed are in use.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Derivation {
+    pub first_index: u32,
+    pub count: u32,
+}
+
+impl Default for Derivation {
+    fn default() -> Self {
+        Self {
+            first_index: 0,
+            count: 1,
+        }
+    }
+}
+
+/// Everything needed to restore a wallet in a single JSON file, e.g. as a portable backup.
+///
+/// Unlike [super::WalletManager], which stores the secrets of several wallets, this is a single
+/// seed with the user's settings for it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WalletFile {
+    pub seed: StoredSeed,
+
+    #[serde(default)]
+    pub address_book: AddressBook,
+
+    /// The representative to use for new blocks, or the node's default when `None`.
+    #[serde(default)]
+    pub representative: Option<Address>,
+
+    #[serde(default)]
+    pub derivation: Derivation,
+}
+
+impl WalletFile {
+    /// A wallet file with `seed` encrypted with `password`, using the default [KdfParams].
+    pub fn new(seed: &Seed, password: &str) -> Self {
+        Self::with_seed(StoredSeed::Encrypted(
+            EncryptedKey::from_seed(seed, password, KdfParams::default())
+                .expect("Default KDF parameters are valid"),
+        ))
+    }
+
+    pub fn with_seed(seed: StoredSeed) -> Self {
+        Self {
+            seed,
+            address_book: AddressBook::new(),
+            representative: None,
+            derivation: Derivation::default(),
+        }
+    }
+
+    pub async fn save<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let file = File::create(path)
+            .await
+            .with_context(|| format!("Creating file {:?}", path))?;
+        Ok(serde_json::to_writer_pretty(file.into_std().await, self)?)
+    }
+
+    /// Load a wallet file along with its decrypted seed, failing on a wrong `password`, see
+    /// [StoredSeed::to_seed].
+    pub async fn load<P: AsRef<Path>>(
+        path: P,
+        password: Option<&str>,
+    ) -> anyhow::Result<(Self, Seed)> {
+        let path = path.as_ref();
+        let file = File::open(path)
+            .await
+            .with_context(|| format!("Opening {:?}", path))?;
+        let wallet: Self = serde_json::from_reader(file.into_std().await)
+            .with_context(|| format!("Parsing wallet file {:?}", path))?;
+        let seed = wallet.seed.to_seed(password)?;
+        Ok((wallet, seed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("round_trip.wallet.json");
+        let seed = Seed::random();
+        let kdf = KdfParams {
+            memory: 64,
+            iterations: 1,
+            parallelism: 1,
+        };
+        let mut wallet = WalletFile::with_seed(StoredSeed::Encrypted(
+            EncryptedKey::from_seed(&seed, "secret", kdf).unwrap(),
+        ));
+        let friend =
+            Address::from_str("nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d")
+                .unwrap();
+        wallet.address_book.insert("friend", friend.to_owned());
+        wallet.representative = Some(
+            Address::from_str("nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg")
+                .unwrap(),
+        );
+        wallet.derivation.count = 3;
+        wallet.save(&path).await.unwrap();
+
+        let (loaded, loaded_seed) = WalletFile::load(&path, Some("secret")).await.unwrap();
+        assert_eq!(loaded, wallet);
+        assert_eq!(loaded_seed, seed);
+        assert_eq!(loaded.address_book.get("friend"), Some(&friend));
+        assert!(WalletFile::load(&path, Some("wrong")).await.is_err());
+        assert!(WalletFile::load(&path, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn plain_seed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plain.wallet.json");
+        let seed = Seed::random();
+        WalletFile::with_seed(StoredSeed::Plain(seed.to_owned()))
+            .save(&path)
+            .await
+            .unwrap();
+
+        let (_, loaded_seed) = WalletFile::load(&path, None).await.unwrap();
+        assert_eq!(loaded_seed, seed);
+        let err = WalletFile::load(&path, Some("secret")).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<Error>(),
+            Some(Error::UnexpectedPassword)
+        ));
+    }
+}
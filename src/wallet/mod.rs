@@ -31,9 +31,14 @@
 //! # Ok(())
 //! # }
 //! ```
+mod address_book;
+mod file;
+
 use crate::phrase::{Language, MnemonicType};
 use crate::{hexify, Address, Error, Phrase, Private, Public, Seed};
+pub use address_book::AddressBook;
 use anyhow::{anyhow, Context};
+pub use file::{Derivation, StoredSeed, WalletFile};
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -0,0 +1,269 @@
+//! Messages published by a node's WebSocket server.
+use crate::blocks::{BlockHash, Subtype};
+use crate::encoding::{deserialize_from_str, expect_len};
+use crate::{Address, Public, Raw};
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::time::{Duration, Instant};
+
+/// A block confirmed by the network, from the `confirmation` topic.
+///
+/// Addresses are checked when deserializing, since [Address]'s own serde accepts any string.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Confirmation {
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub account: Address,
+    pub amount: Raw,
+    pub hash: BlockHash,
+    pub confirmation_type: String,
+    pub block: ConfirmationBlock,
+}
+
+/// The parts of the confirmed block needed to describe it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConfirmationBlock {
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub account: Address,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub representative: Address,
+    pub balance: Raw,
+    #[serde(deserialize_with = "deserialize_from_str")]
+    pub link_as_account: Address,
+    pub subtype: Subtype,
+}
+
+impl Confirmation {
+    /// A line describing the confirmation, e.g.
+    /// `Received 1.5 NANO on nano_1abc... (block ABCD...)`.
+    pub fn summary(&self) -> String {
+        let amount = self.amount.to_mnano().to_big_decimal().to_owned();
+        match self.block.subtype {
+            Subtype::Send => format!(
+                "Sent {} NANO from {} to {} (block {})",
+                amount, self.account, self.block.link_as_account, self.hash
+            ),
+            Subtype::Receive | Subtype::Open => format!(
+                "Received {} NANO on {} (block {})",
+                amount, self.account, self.hash
+            ),
+            Subtype::Change => format!(
+                "Changed the representative of {} to {} (block {})",
+                self.account, self.block.representative, self.hash
+            ),
+            Subtype::Epoch => format!("Upgraded {} (block {})", self.account, self.hash),
+        }
+    }
+}
+
+/// A confirmation stored as a fixed size record, e.g. to log every confirmation seen.
+///
+/// The account, hash, amount and subtype are kept, which is 81 bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfirmationRecord {
+    pub account: Public,
+    pub hash: BlockHash,
+    pub amount: Raw,
+    pub subtype: Subtype,
+}
+
+impl ConfirmationRecord {
+    pub const LEN: usize = Public::LEN + BlockHash::LEN + Raw::LEN + 1;
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        let (account, rest) = bytes.split_at_mut(Public::LEN);
+        let (hash, rest) = rest.split_at_mut(BlockHash::LEN);
+        let (amount, subtype) = rest.split_at_mut(Raw::LEN);
+        account.copy_from_slice(self.account.as_bytes());
+        hash.copy_from_slice(self.hash.as_bytes());
+        amount.copy_from_slice(&self.amount.to_vec());
+        subtype[0] = match self.subtype {
+            Subtype::Send => 0,
+            Subtype::Receive => 1,
+            Subtype::Open => 2,
+            Subtype::Change => 3,
+            Subtype::Epoch => 4,
+        };
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> anyhow::Result<Self> {
+        expect_len(bytes.len(), Self::LEN, "Confirmation record")?;
+        let (account, rest) = bytes.split_at(Public::LEN);
+        let (hash, rest) = rest.split_at(BlockHash::LEN);
+        let (amount, subtype) = rest.split_at(Raw::LEN);
+        let subtype = match subtype[0] {
+            0 => Subtype::Send,
+            1 => Subtype::Receive,
+            2 => Subtype::Open,
+            3 => Subtype::Change,
+            4 => Subtype::Epoch,
+            other => return Err(anyhow!("Invalid subtype in confirmation record: {}", other)),
+        };
+        Ok(Self {
+            account: Public::try_from(account)?,
+            hash: BlockHash::try_from(hash)?,
+            amount: Raw::try_from(amount)?,
+            subtype,
+        })
+    }
+}
+
+impl TryFrom<&Confirmation> for ConfirmationRecord {
+    type Error = anyhow::Error;
+
+    fn try_from(confirmation: &Confirmation) -> anyhow::Result<Self> {
+        Ok(Self {
+            account: Public::try_from(&confirmation.account)
+                .with_context(|| format!("Confirmation account {}", confirmation.account))?,
+            hash: confirmation.hash.to_owned(),
+            amount: confirmation.amount.to_owned(),
+            subtype: confirmation.block.subtype.to_owned(),
+        })
+    }
+}
+
+/// Confirmations per second over the last `window`, e.g. to monitor network throughput.
+#[derive(Debug, Clone)]
+pub struct ConfirmationRate {
+    window: Duration,
+    seen: VecDeque<Instant>,
+}
+
+impl ConfirmationRate {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            seen: VecDeque::new(),
+        }
+    }
+
+    /// Record a confirmation seen at `at`. Confirmations have to be recorded in time order.
+    pub fn record(&mut self, at: Instant) {
+        self.seen.push_back(at);
+        self.expire(at);
+    }
+
+    /// The rate as of `now`, counting the confirmations seen within the window before it.
+    pub fn rate(&mut self, now: Instant) -> f64 {
+        self.expire(now);
+        let secs = self.window.as_secs_f64();
+        if secs == 0.0 {
+            return 0.0;
+        }
+        self.seen.len() as f64 / secs
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while let Some(oldest) = self.seen.front() {
+            if now.saturating_duration_since(*oldest) < self.window {
+                break;
+            }
+            self.seen.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn confirmation_json(subtype: &str) -> String {
+        format!(
+            r#"{{
+            "account": "nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg",
+            "amount": "1500000000000000000000000000000",
+            "hash": "3E746E9C46DF7C559E4D0E39774F029426C8911C133D96A2CC3F2210F1E0D5F8",
+            "confirmation_type": "active_quorum",
+            "block": {{
+                "type": "state",
+                "account": "nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg",
+                "previous": "4E5004CA1E7A5E2FE6AB3C4F6B09E57F7A7DA43A5804BB30E015E6B39ED4B632",
+                "representative": "nano_13ezf4od79h1tgj9aiu4djzcmmguendtjfuhwfukhuucboua8cpoihmh8byo",
+                "balance": "3500000000000000000000000000000",
+                "link": "5D1AA8A45F8736519D707FCB375976A7F9AF795091021D7E9C7548D6F45DD8D5",
+                "link_as_account": "nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d",
+                "signature": "82D41BC16F313E4B2243D14DFFA2FB04679C540C2095FEE7EAE0F2F26880AD56DD48D87A7CC5DD760C5B2D76EE2C205506AA557BF00B60D8DEE312EC7343A501",
+                "work": "8a142e07a10996d5",
+                "subtype": "{}"
+            }}
+        }}"#,
+            subtype
+        )
+    }
+
+    fn confirmation(subtype: &str) -> Confirmation {
+        serde_json::from_str(&confirmation_json(subtype)).unwrap()
+    }
+
+    #[test]
+    fn invalid_address() {
+        // A typo in the checksum of the link.
+        let json = confirmation_json("send").replace("p69emk8y1d", "p69emk8y1e");
+        assert!(serde_json::from_str::<Confirmation>(&json).is_err());
+    }
+
+    #[test]
+    fn summary() {
+        assert_eq!(
+            confirmation("send").summary(),
+            "Sent 1.5 NANO from nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg \
+            to nano_1pu7p5n3ghq1i1p4rhmek41f5add1uh34xpb94nkbxe8g4a6x1p69emk8y1d \
+            (block 3E746E9C46DF7C559E4D0E39774F029426C8911C133D96A2CC3F2210F1E0D5F8)"
+        );
+        assert_eq!(
+            confirmation("receive").summary(),
+            "Received 1.5 NANO on nano_3o3nkaqbgxbuhmcrf38tpxyhsf5semmcahejyk9z5ybffm7tjhizrfqo7xkg \
+            (block 3E746E9C46DF7C559E4D0E39774F029426C8911C133D96A2CC3F2210F1E0D5F8)"
+        );
+    }
+
+    #[test]
+    fn record() {
+        for subtype in &["send", "receive", "open", "change", "epoch"] {
+            let confirmation = confirmation(subtype);
+            let record = ConfirmationRecord::try_from(&confirmation).unwrap();
+            let bytes = record.to_bytes();
+            assert_eq!(bytes.len(), 81);
+
+            let decoded = ConfirmationRecord::from_bytes(&bytes).unwrap();
+            assert_eq!(decoded, record);
+            assert_eq!(decoded.account.to_address(), confirmation.account);
+            assert_eq!(decoded.amount, confirmation.amount);
+        }
+
+        let mut bytes = ConfirmationRecord {
+            account: Public::from_str(
+                "2994D330022A052DF83E10FCE1B3E140496CDCD7E0C0F2FF6DE2670291B88011",
+            )
+            .unwrap(),
+            hash: confirmation("send").hash,
+            amount: Raw::from(1),
+            subtype: Subtype::Send,
+        }
+        .to_bytes();
+        bytes[ConfirmationRecord::LEN - 1] = 5;
+        assert!(ConfirmationRecord::from_bytes(&bytes).is_err());
+        assert!(ConfirmationRecord::from_bytes(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn confirmation_rate() {
+        let mut rate = ConfirmationRate::new(Duration::from_secs(10));
+        let start = Instant::now();
+        assert_eq!(rate.rate(start), 0.0);
+
+        // 20 confirmations over the first 5 seconds.
+        for i in 0..20 {
+            rate.record(start + Duration::from_millis(i * 250));
+        }
+        assert_eq!(rate.rate(start + Duration::from_secs(5)), 2.0);
+
+        // The ones seen in the first 2 seconds have dropped out of the window.
+        assert_eq!(rate.rate(start + Duration::from_secs(12)), 1.1);
+        assert_eq!(rate.rate(start + Duration::from_secs(15)), 0.0);
+    }
+}